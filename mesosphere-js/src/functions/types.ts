@@ -8,14 +8,32 @@ export type LooseArgs<TArgs extends FunctionArgsDefinition> = {
   [K in keyof TArgs]: unknown;
 };
 
+export type AggregateSpec =
+  | { $count: string }
+  | { $sum: string }
+  | { $avg: string }
+  | { $min: string }
+  | { $max: string };
+
 export type DbReadCursor = {
+  filter(where: Record<string, unknown>): DbReadCursor;
+  order(field: string, direction?: "asc" | "desc"): DbReadCursor;
+  take(limit: number): DbReadCursor;
+  skip(offset: number): DbReadCursor;
   collect(): Promise<unknown[]>;
+  aggregate(
+    aggregates: Record<string, AggregateSpec>,
+    groupBy?: string[],
+  ): Promise<Record<string, unknown>[]>;
 };
 
 export type DbApi = {
   read(table: string): DbReadCursor;
   insert(table: string, value: unknown): Promise<string>;
+  insertMany(table: string, values: unknown[]): Promise<string[]>;
   get(table: string, id: string): Promise<unknown | null>;
+  patch(table: string, id: string, changes: unknown): Promise<number>;
+  upsert(table: string, uniqueFields: string[], value: unknown): Promise<string>;
 };
 
 export type FunctionContext = {
@@ -1,14 +1,22 @@
 """Mesosphere HTTP SDK."""
 
 from mesosphere.embeddings import (
+    CachedEmbedding,
+    EmbeddingBatchError,
+    EmbeddingCache,
     EmbeddingCallable,
+    LoggedEmbedding,
+    CohereEmbedding,
+    HashEmbedding,
     OllamaEmbedding,
     OpenAIEmbedding,
     SentenceTransformerEmbedding,
+    VoyageEmbedding,
     get_embedding_function,
 )
 from mesosphere.errors import MesosphereError
 from mesosphere.functions import api
+from mesosphere.rerankers import CohereReranker, CrossEncoderReranker, Reranker
 from mesosphere.httpclient import (
     AsyncHttpClient,
     HttpRelationalClient,
@@ -24,10 +32,20 @@ __all__ = [
     "HttpRelationalClient",
     "HttpTransportError",
     "MesosphereError",
+    "CachedEmbedding",
+    "EmbeddingBatchError",
+    "EmbeddingCache",
     "EmbeddingCallable",
+    "LoggedEmbedding",
+    "CohereEmbedding",
+    "HashEmbedding",
     "OllamaEmbedding",
     "OpenAIEmbedding",
     "SentenceTransformerEmbedding",
+    "VoyageEmbedding",
     "get_embedding_function",
+    "CohereReranker",
+    "CrossEncoderReranker",
+    "Reranker",
     "api",
 ]
@@ -1,19 +1,33 @@
 """Embeddings module."""
 
+from mesosphere.embeddings.cohere import CohereEmbedding
+from mesosphere.embeddings.hash import HashEmbedding
 from mesosphere.embeddings.ollama import OllamaEmbedding
 from mesosphere.embeddings.openai import OpenAIEmbedding
 from mesosphere.embeddings.sentence_transformers import SentenceTransformerEmbedding
+from mesosphere.embeddings.voyage import VoyageEmbedding
 from mesosphere.embeddings.core import (
+    CachedEmbedding,
+    EmbeddingBatchError,
+    EmbeddingCache,
     EmbeddingCallable,
+    LoggedEmbedding,
     EmbeddingsFunction,
     get_embedding_function,
 )
 
 __all__ = [
+    "CohereEmbedding",
+    "HashEmbedding",
     "OllamaEmbedding",
     "OpenAIEmbedding",
     "SentenceTransformerEmbedding",
+    "VoyageEmbedding",
+    "CachedEmbedding",
+    "EmbeddingBatchError",
+    "EmbeddingCache",
     "EmbeddingCallable",
+    "LoggedEmbedding",
     "EmbeddingsFunction",
     "get_embedding_function"
 ]
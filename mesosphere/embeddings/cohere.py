@@ -0,0 +1,107 @@
+"""Cohere embedding functions for vector operations."""
+
+import os
+from typing import Any, List, Optional
+from mesosphere.embeddings.core import EmbeddingCallable, EmbeddingsFunction
+from mesosphere.embeddings.core.retry import RetryPolicy, embed_in_batches
+
+
+class CohereEmbedding(EmbeddingsFunction, EmbeddingCallable):
+    """Cohere embedding function."""
+
+    def __init__(
+        self,
+        api_key: Optional[str] = None,
+        model: str = "embed-english-v3.0",
+        input_type: str = "search_document",
+        base_url: Optional[str] = None,
+        timeout: Optional[float] = None,
+        dimension: Optional[int] = None,
+        batch_size: int = 96,
+        max_retries: int = 3,
+        initial_backoff: float = 1.0,
+        max_backoff: float = 30.0,
+    ):
+        """
+        Initialize Cohere embedding function.
+
+        Args:
+            api_key: Cohere API key. If not provided, COHERE_API_KEY is used.
+            model: Cohere embedding model.
+            input_type: Cohere input type (search_document, search_query,
+                classification or clustering).
+            base_url: Optional custom Cohere base URL.
+            timeout: Optional timeout in seconds.
+            batch_size: Maximum number of texts sent per request (Cohere allows 96).
+            max_retries: Retries per request on 429s and transient errors.
+            initial_backoff: Seconds before the first retry, doubled per retry.
+            max_backoff: Upper bound in seconds for a single retry delay.
+        """
+
+        super().__init__(dimension=dimension)
+        self.api_key = api_key or os.getenv("COHERE_API_KEY")
+        if not self.api_key:
+            raise ValueError(
+                "Cohere API key is required. Provide `api_key` or set COHERE_API_KEY."
+            )
+
+        if batch_size < 1:
+            raise ValueError("batch_size must be at least 1")
+        self.model = model
+        self.input_type = input_type
+        self.base_url = base_url
+        self.timeout = timeout
+        self.batch_size = batch_size
+
+        try:
+            import cohere
+        except ImportError as exc:
+            raise ImportError(
+                "Cohere embedding provider requires the `cohere` package. "
+                "Install it with `pip install cohere`."
+            ) from exc
+
+        client_kwargs: dict[str, Any] = {"api_key": self.api_key}
+        if self.base_url is not None:
+            client_kwargs["base_url"] = self.base_url
+        if self.timeout is not None:
+            client_kwargs["timeout"] = self.timeout
+
+        self._client = cohere.ClientV2(**client_kwargs)
+        self._retry = RetryPolicy(
+            max_retries=max_retries,
+            initial_backoff=initial_backoff,
+            max_backoff=max_backoff,
+        )
+
+    def embed(self, texts: List[str]) -> List[List[float]]:
+        """
+        Generate embeddings for a list of texts using Cohere API.
+
+        Raises:
+            EmbeddingBatchError: If only some batches failed after retries.
+        """
+
+        if not texts:
+            return []
+
+        embeddings = embed_in_batches(
+            texts, self.batch_size, self._embed_batch, self._retry
+        )
+        if self._dimension is None and embeddings:
+            self._dimension = len(embeddings[0])
+        return embeddings
+
+    def _embed_batch(
+        self,
+        texts: List[str],
+    ) -> List[List[float]]:
+        """Embed one batch of texts with a single API request."""
+
+        response = self._client.embed(
+            texts=texts,
+            model=self.model,
+            input_type=self.input_type,
+            embedding_types=["float"],
+        )
+        return [list(vector) for vector in response.embeddings.float_]
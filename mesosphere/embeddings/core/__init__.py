@@ -1,7 +1,18 @@
 """Embedding function module."""
 
 from mesosphere.embeddings.core.embeddings_function import EmbeddingsFunction
+from mesosphere.embeddings.core.embedding_cache import CachedEmbedding, EmbeddingCache
+from mesosphere.embeddings.core.embedding_logging import LoggedEmbedding
 from mesosphere.embeddings.core.get_embeddings_function import get_embedding_function
+from mesosphere.embeddings.core.retry import EmbeddingBatchError
 from mesosphere.utils.embedding_callable import EmbeddingCallable
 
-__all__ = ["EmbeddingsFunction", "EmbeddingCallable", "get_embedding_function"]
+__all__ = [
+    "CachedEmbedding",
+    "EmbeddingBatchError",
+    "EmbeddingCache",
+    "EmbeddingsFunction",
+    "EmbeddingCallable",
+    "LoggedEmbedding",
+    "get_embedding_function",
+]
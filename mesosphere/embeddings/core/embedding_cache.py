@@ -0,0 +1,183 @@
+"""On-disk embedding cache keyed by a hash of the embedded text."""
+
+import hashlib
+import json
+import sqlite3
+import threading
+import time
+from typing import Dict, List, Optional
+
+from mesosphere.embeddings.core.embeddings_function import EmbeddingsFunction
+from mesosphere.utils.embedding_callable import EmbeddingCallable
+
+
+class EmbeddingCache:
+    """SQLite-backed store mapping content hashes to embedding vectors."""
+
+    def __init__(
+        self,
+        path: str,
+        max_entries: int = 100_000,
+    ) -> None:
+        """
+        Open (or create) an embedding cache file.
+
+        Args:
+            path: Path of the SQLite cache file.
+            max_entries: Maximum number of cached vectors. The least recently
+                used entries are evicted once the limit is exceeded.
+        """
+
+        if max_entries < 1:
+            raise ValueError("max_entries must be at least 1")
+        self.path = path
+        self.max_entries = max_entries
+        self._lock = threading.Lock()
+        self._connection = sqlite3.connect(path, check_same_thread=False)
+        with self._connection:
+            self._connection.execute(
+                "CREATE TABLE IF NOT EXISTS embeddings ("
+                "key TEXT PRIMARY KEY, vector TEXT NOT NULL, last_used REAL NOT NULL)"
+            )
+            self._connection.execute(
+                "CREATE INDEX IF NOT EXISTS idx_embeddings_last_used "
+                "ON embeddings (last_used)"
+            )
+
+    @staticmethod
+    def key(
+        namespace: str,
+        text: str,
+    ) -> str:
+        """Build the cache key for a text embedded by a given provider/model."""
+
+        return hashlib.sha256(f"{namespace}\0{text}".encode("utf-8")).hexdigest()
+
+    def get_many(
+        self,
+        keys: List[str],
+    ) -> Dict[str, List[float]]:
+        """Return cached vectors for the keys that are present."""
+
+        if not keys:
+            return {}
+        found: Dict[str, List[float]] = {}
+        with self._lock, self._connection:
+            for start in range(0, len(keys), 500):
+                chunk = keys[start : start + 500]
+                placeholders = ", ".join("?" for _ in chunk)
+                rows = self._connection.execute(
+                    f"SELECT key, vector FROM embeddings WHERE key IN ({placeholders})",
+                    chunk,
+                ).fetchall()
+                for key, vector in rows:
+                    found[key] = json.loads(vector)
+            if found:
+                now = time.time()
+                self._connection.executemany(
+                    "UPDATE embeddings SET last_used = ? WHERE key = ?",
+                    [(now, key) for key in found],
+                )
+        return found
+
+    def put_many(
+        self,
+        entries: Dict[str, List[float]],
+    ) -> None:
+        """Store vectors and evict the least recently used entries over the limit."""
+
+        if not entries:
+            return
+        now = time.time()
+        with self._lock, self._connection:
+            self._connection.executemany(
+                "INSERT OR REPLACE INTO embeddings (key, vector, last_used) VALUES (?, ?, ?)",
+                [(key, json.dumps(vector), now) for key, vector in entries.items()],
+            )
+            (count,) = self._connection.execute(
+                "SELECT COUNT(*) FROM embeddings"
+            ).fetchone()
+            if count > self.max_entries:
+                self._connection.execute(
+                    "DELETE FROM embeddings WHERE key IN ("
+                    "SELECT key FROM embeddings ORDER BY last_used ASC LIMIT ?)",
+                    (count - self.max_entries,),
+                )
+
+    def clear(self) -> None:
+        """Remove every cached vector."""
+
+        with self._lock, self._connection:
+            self._connection.execute("DELETE FROM embeddings")
+
+
+class CachedEmbedding(EmbeddingsFunction, EmbeddingCallable):
+    """Embedding function wrapper that serves repeated texts from an EmbeddingCache."""
+
+    def __init__(
+        self,
+        function: EmbeddingsFunction,
+        cache: EmbeddingCache,
+        namespace: Optional[str] = None,
+    ) -> None:
+        """
+        Wrap an embedding function with a cache.
+
+        Args:
+            function: Embedding function called for texts missing from the cache.
+            cache: Cache storing previously computed vectors.
+            namespace: Cache namespace. Defaults to the provider class and model,
+                so vectors from different models never mix.
+        """
+
+        super().__init__(dimension=function.dimension())
+        self.function = function
+        self.cache = cache
+        self.namespace = namespace or function.fingerprint()
+
+    def embed(
+        self,
+        texts: List[str],
+        bypass_cache: bool = False,
+    ) -> List[List[float]]:
+        """
+        Generate embeddings, calling the wrapped function only for uncached texts.
+
+        Args:
+            texts: List of texts to embed
+            bypass_cache: Recompute every text and refresh its cached vector
+        """
+
+        if not texts:
+            return []
+
+        keys = [EmbeddingCache.key(self.namespace, text) for text in texts]
+        cached = {} if bypass_cache else self.cache.get_many(keys)
+
+        missing: Dict[str, str] = {}
+        for key, text in zip(keys, texts):
+            if key not in cached and key not in missing:
+                missing[key] = text
+
+        if missing:
+            vectors = self.function.embed(list(missing.values()))
+            computed = dict(zip(missing.keys(), vectors))
+            self.cache.put_many(computed)
+            cached.update(computed)
+
+        embeddings = [cached[key] for key in keys]
+        if self._dimension is None:
+            self._dimension = len(embeddings[0])
+        return embeddings
+
+    def fingerprint(self) -> str:
+        """Get the provider/model fingerprint of the wrapped function."""
+
+        return self.function.fingerprint()
+
+    def get_dimension(self) -> int:
+        """Get the embedding dimension of the wrapped function."""
+
+        if self._dimension is None:
+            self._dimension = self.function.get_dimension()
+        return self._dimension
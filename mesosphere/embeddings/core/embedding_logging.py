@@ -0,0 +1,139 @@
+"""Structured logging of embedding calls with input redaction."""
+
+import logging
+import re
+import time
+from typing import List, Optional
+
+from mesosphere.embeddings.core.embeddings_function import EmbeddingsFunction
+from mesosphere.utils.embedding_callable import EmbeddingCallable
+
+logger = logging.getLogger("mesosphere.embeddings")
+
+# Verbosity levels accepted by `LoggedEmbedding`, from quietest to loudest
+VERBOSITY_LEVELS = ("off", "calls", "inputs")
+
+# Patterns replaced in input previews, most specific first
+_REDACTIONS = (
+    (re.compile(r"[\w.+-]+@[\w-]+(?:\.[\w-]+)+"), "[email]"),
+    (re.compile(r"\b(?:sk|pk|key|token)[-_][A-Za-z0-9_-]{8,}\b"), "[secret]"),
+    (re.compile(r"\+?\d[\d\s().-]{7,}\d"), "[number]"),
+)
+
+
+def redact_preview(
+    text: str,
+    max_chars: int = 80,
+) -> str:
+    """Redact emails, secret-looking tokens and long digit runs, then truncate."""
+
+    for pattern, replacement in _REDACTIONS:
+        text = pattern.sub(replacement, text)
+    if len(text) > max_chars:
+        return text[:max_chars] + "..."
+    return text
+
+
+class LoggedEmbedding(EmbeddingsFunction, EmbeddingCallable):
+    """Embedding function wrapper that logs each call to the `mesosphere.embeddings` logger."""
+
+    def __init__(
+        self,
+        function: EmbeddingsFunction,
+        verbosity: str = "calls",
+        preview_chars: int = 80,
+        max_previews: int = 5,
+    ) -> None:
+        """
+        Wrap an embedding function with call logging.
+
+        Args:
+            function: Embedding function whose calls are logged.
+            verbosity: `off`, `calls` (provider, model, batch size, dimension and
+                latency at INFO) or `inputs` (additionally redacted previews of
+                the first texts at DEBUG).
+            preview_chars: Characters kept per input preview.
+            max_previews: Inputs previewed per call.
+        """
+
+        if verbosity not in VERBOSITY_LEVELS:
+            raise ValueError(
+                f"verbosity must be one of {', '.join(VERBOSITY_LEVELS)}, got '{verbosity}'"
+            )
+        super().__init__(dimension=function.dimension())
+        self.function = function
+        self.verbosity = verbosity
+        self.preview_chars = preview_chars
+        self.max_previews = max_previews
+
+    def embed(
+        self,
+        texts: List[str],
+    ) -> List[List[float]]:
+        """Generate embeddings with the wrapped function, logging the call."""
+
+        if self.verbosity == "off":
+            return self.function.embed(texts)
+
+        provider, _, model = self.function.fingerprint().partition(":")
+        fields = {
+            "provider": provider,
+            "model": model,
+            "batch_size": len(texts),
+        }
+        if self.verbosity == "inputs":
+            logger.debug(
+                "embedding inputs",
+                extra={
+                    **fields,
+                    "previews": [
+                        redact_preview(text, self.preview_chars)
+                        for text in texts[: self.max_previews]
+                    ],
+                },
+            )
+
+        started = time.perf_counter()
+        try:
+            embeddings = self.function.embed(texts)
+        except Exception as error:
+            logger.warning(
+                "embedding call failed",
+                extra={
+                    **fields,
+                    "latency_ms": self._elapsed_ms(started),
+                    "error": type(error).__name__,
+                },
+            )
+            raise
+
+        dimension: Optional[int] = len(embeddings[0]) if embeddings else None
+        logger.info(
+            "embedding call",
+            extra={
+                **fields,
+                "dimension": dimension,
+                "latency_ms": self._elapsed_ms(started),
+            },
+        )
+        if self._dimension is None:
+            self._dimension = dimension
+        return embeddings
+
+    @staticmethod
+    def _elapsed_ms(started: float) -> float:
+        """Milliseconds elapsed since `started`, rounded to 0.1 ms."""
+
+        return round((time.perf_counter() - started) * 1000.0, 1)
+
+    def fingerprint(self) -> str:
+        """Get the provider/model fingerprint of the wrapped function."""
+
+        return self.function.fingerprint()
+
+    def get_dimension(self) -> int:
+        """Get the embedding dimension of the wrapped function."""
+
+        if self._dimension is None:
+            self._dimension = self.function.get_dimension()
+        return self._dimension
@@ -42,6 +42,16 @@ class EmbeddingsFunction:
                 self._dimension = len(embedding)
         return embeddings
 
+    def fingerprint(self) -> str:
+        """
+        Get the provider/model fingerprint stored on collections built with this function.
+
+        Returns:
+            The provider class name and model, e.g. "OpenAIEmbedding:text-embedding-3-small".
+        """
+
+        return f"{type(self).__name__}:{getattr(self, 'model', '')}"
+
     def dimension(self) -> Optional[int]:
         """
         Get the embedding dimension.
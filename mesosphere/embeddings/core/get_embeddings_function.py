@@ -16,6 +16,16 @@ def _validate_remaining_config(
         )
 
 
+def _pop_retry_config(config: dict) -> dict:
+    """Pop the retry options shared by remote providers."""
+
+    return {
+        "max_retries": config.pop("max_retries", 3),
+        "initial_backoff": config.pop("initial_backoff", 1.0),
+        "max_backoff": config.pop("max_backoff", 30.0),
+    }
+
+
 def get_embedding_function(
     provider: str = "ollama", **config: Any
 ) -> Callable[[List[str]], List[List[float]]]:
@@ -23,10 +33,46 @@ def get_embedding_function(
     Get an embedding function from supported providers.
 
     Args:
-        provider: Embedding provider (ollama, openai, sentence-transformers)
-        **config: Provider-specific configuration
+        provider: Embedding provider (ollama, openai, cohere, voyage,
+            sentence-transformers, hash). `hash` produces deterministic
+            embeddings without any model or network, for demos, CI and
+            offline development.
+        **config: Provider-specific configuration. `cache_path` enables an
+            on-disk embedding cache for any provider, bounded by
+            `cache_max_entries` (default 100000). `log_verbosity` (`off`,
+            `calls` or `inputs`) logs provider calls to the
+            `mesosphere.embeddings` logger; `inputs` adds redacted previews.
+            `timeout` (seconds) bounds each provider call for every provider.
     """
 
+    cache_path = config.pop("cache_path", None)
+    cache_max_entries = config.pop("cache_max_entries", 100_000)
+    log_verbosity = config.pop("log_verbosity", "off")
+    function = _create_provider(provider, config)
+    if log_verbosity != "off":
+        from mesosphere.embeddings.core.embedding_logging import LoggedEmbedding
+
+        # inside the cache, so only calls that reach the provider are logged
+        function = LoggedEmbedding(function, verbosity=log_verbosity)
+    if cache_path is None:
+        return function
+
+    from mesosphere.embeddings.core.embedding_cache import (
+        CachedEmbedding,
+        EmbeddingCache,
+    )
+
+    return CachedEmbedding(
+        function, EmbeddingCache(cache_path, max_entries=cache_max_entries)
+    )
+
+
+def _create_provider(
+    provider: str,
+    config: dict,
+) -> Any:
+    """Create the embedding function for a provider name."""
+
     provider = provider.lower().strip().replace("_", "-")
 
     # ollama provider
@@ -36,8 +82,20 @@ def get_embedding_function(
         model = config.pop("model", "mxbai-embed-large")
         base_url = config.pop("base_url", "http://localhost:11434")
         dimension = config.pop("dimension", None)
+        batch_size = config.pop("batch_size", 64)
+        max_concurrency = config.pop("max_concurrency", 4)
+        timeout = config.pop("timeout", 60.0)
+        retry = _pop_retry_config(config)
         _validate_remaining_config(provider, config)
-        return OllamaEmbedding(model=model, base_url=base_url, dimension=dimension)
+        return OllamaEmbedding(
+            model=model,
+            base_url=base_url,
+            dimension=dimension,
+            batch_size=batch_size,
+            max_concurrency=max_concurrency,
+            timeout=timeout,
+            **retry,
+        )
 
     # openai provider
     if provider == "openai":
@@ -50,6 +108,8 @@ def get_embedding_function(
         project = config.pop("project", None)
         timeout = config.pop("timeout", None)
         dimension = config.pop("dimension", None)
+        batch_size = config.pop("batch_size", 256)
+        retry = _pop_retry_config(config)
         _validate_remaining_config(provider, config)
         return OpenAIEmbedding(
             api_key=api_key,
@@ -59,6 +119,54 @@ def get_embedding_function(
             project=project,
             timeout=timeout,
             dimension=dimension,
+            batch_size=batch_size,
+            **retry,
+        )
+
+    # cohere provider
+    if provider == "cohere":
+        from mesosphere.embeddings.cohere import CohereEmbedding
+
+        api_key = config.pop("api_key", None)
+        model = config.pop("model", "embed-english-v3.0")
+        input_type = config.pop("input_type", "search_document")
+        base_url = config.pop("base_url", None)
+        timeout = config.pop("timeout", None)
+        dimension = config.pop("dimension", None)
+        batch_size = config.pop("batch_size", 96)
+        retry = _pop_retry_config(config)
+        _validate_remaining_config(provider, config)
+        return CohereEmbedding(
+            api_key=api_key,
+            model=model,
+            input_type=input_type,
+            base_url=base_url,
+            timeout=timeout,
+            dimension=dimension,
+            batch_size=batch_size,
+            **retry,
+        )
+
+    # voyage provider
+    if provider in {"voyage", "voyageai", "voyage-ai"}:
+        from mesosphere.embeddings.voyage import VoyageEmbedding
+
+        api_key = config.pop("api_key", None)
+        model = config.pop("model", "voyage-3")
+        input_type = config.pop("input_type", None)
+        timeout = config.pop("timeout", None)
+        dimension = config.pop("dimension", None)
+        batch_size = config.pop("batch_size", 128)
+        retry = _pop_retry_config(config)
+        _validate_remaining_config(provider, config)
+        return VoyageEmbedding(
+            api_key=api_key,
+            model=model,
+            input_type=input_type,
+            timeout=timeout,
+            dimension=dimension,
+            batch_size=batch_size,
+            **retry,
         )
 
     # sentence-transformers provider
@@ -71,14 +179,32 @@ def get_embedding_function(
         device = config.pop("device", None)
         normalize_embeddings = config.pop("normalize_embeddings", False)
         dimension = config.pop("dimension", None)
+        timeout = config.pop("timeout", None)
+        batch_size = config.pop("batch_size", 32)
+        max_seq_length = config.pop("max_seq_length", None)
+        devices = config.pop("devices", None)
         _validate_remaining_config(provider, config)
         return SentenceTransformerEmbedding(
             model=model,
             device=device,
             normalize_embeddings=normalize_embeddings,
             dimension=dimension,
+            timeout=timeout,
+            batch_size=batch_size,
+            max_seq_length=max_seq_length,
+            devices=devices,
         )
+
+    # hash provider (deterministic, offline)
+    if provider == "hash":
+        from mesosphere.embeddings.hash import HashEmbedding
+
+        dim = config.pop("dim", 384)
+        # computed locally without I/O; accepted so `timeout` works for every provider
+        config.pop("timeout", None)
+        _validate_remaining_config(provider, config)
+        return HashEmbedding(dim=dim)
     raise ValueError(
         f"Unsupported embedding provider '{provider}'. "
-        "Supported providers: ollama, openai, sentence-transformers."
+        "Supported providers: ollama, openai, cohere, voyage, sentence-transformers, hash."
     )
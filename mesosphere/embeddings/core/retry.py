@@ -0,0 +1,170 @@
+"""Retry and partial-failure helpers shared by remote embedding providers."""
+
+import time
+from concurrent.futures import ThreadPoolExecutor
+from typing import Any, Callable, Dict, List, Optional, Tuple, Type, TypeVar
+
+T = TypeVar("T")
+
+# HTTP statuses worth retrying: rate limits and transient server errors
+RETRYABLE_STATUS_CODES = {408, 429, 500, 502, 503, 504}
+
+
+class EmbeddingBatchError(Exception):
+    """Raised when some, but not all, texts of an embedding call failed."""
+
+    def __init__(
+        self,
+        embeddings: List[Optional[List[float]]],
+        failures: Dict[int, Exception],
+    ) -> None:
+        """
+        Args:
+            embeddings: Embeddings in input order, with None for failed texts.
+            failures: Error for each failed text, keyed by its input index.
+        """
+
+        super().__init__(
+            f"{len(failures)} of {len(embeddings)} texts failed to embed: "
+            f"{next(iter(failures.values()))}"
+        )
+        self.embeddings = embeddings
+        self.failures = failures
+
+
+class RetryPolicy:
+    """Exponential backoff for transient provider errors, honoring Retry-After."""
+
+    def __init__(
+        self,
+        max_retries: int = 3,
+        initial_backoff: float = 1.0,
+        max_backoff: float = 30.0,
+        retryable: Tuple[Type[BaseException], ...] = (),
+    ) -> None:
+        """
+        Args:
+            max_retries: Retries after the first attempt (0 disables retrying).
+            initial_backoff: Delay in seconds before the first retry, doubled per retry.
+            max_backoff: Upper bound in seconds for any single delay.
+            retryable: Extra exception types that are always retried.
+        """
+
+        if max_retries < 0:
+            raise ValueError("max_retries must not be negative")
+        self.max_retries = max_retries
+        self.initial_backoff = initial_backoff
+        self.max_backoff = max_backoff
+        self.retryable = (ConnectionError, TimeoutError) + tuple(retryable)
+
+    def call(
+        self,
+        function: Callable[[], T],
+    ) -> T:
+        """Call `function`, retrying retryable errors until the policy is exhausted."""
+
+        attempt = 0
+        while True:
+            try:
+                return function()
+            except Exception as error:
+                if attempt >= self.max_retries or not self._is_retryable(error):
+                    raise
+                time.sleep(self._delay(error, attempt))
+                attempt += 1
+
+    def _is_retryable(
+        self,
+        error: Exception,
+    ) -> bool:
+        """Check whether an error is a rate limit or transient failure."""
+
+        if isinstance(error, self.retryable):
+            return True
+        return _status_code(error) in RETRYABLE_STATUS_CODES
+
+    def _delay(
+        self,
+        error: Exception,
+        attempt: int,
+    ) -> float:
+        """Seconds to wait before the next attempt."""
+
+        retry_after = _retry_after(error)
+        if retry_after is not None:
+            return min(retry_after, self.max_backoff)
+        return min(self.initial_backoff * (2**attempt), self.max_backoff)
+
+
+def _status_code(error: Exception) -> Optional[int]:
+    """Read the HTTP status from urllib (`code`) or SDK (`status_code`, `http_status`) errors."""
+
+    for attribute in ("status_code", "http_status", "code"):
+        value = getattr(error, attribute, None)
+        if isinstance(value, int):
+            return value
+    return None
+
+
+def _retry_after(error: Exception) -> Optional[float]:
+    """Read a Retry-After header given in seconds, if the error carries one."""
+
+    headers: Any = getattr(error, "headers", None)
+    if headers is None:
+        response = getattr(error, "response", None)
+        headers = getattr(response, "headers", None)
+    if headers is None:
+        return None
+    value = headers.get("Retry-After") or headers.get("retry-after")
+    try:
+        return max(float(value), 0.0)
+    except (TypeError, ValueError):
+        return None
+
+
+def embed_in_batches(
+    texts: List[str],
+    batch_size: int,
+    embed_batch: Callable[[List[str]], List[List[float]]],
+    retry: RetryPolicy,
+    max_concurrency: int = 1,
+) -> List[List[float]]:
+    """
+    Embed texts batch by batch, retrying each batch independently.
+
+    Raises:
+        EmbeddingBatchError: If some batches still fail after retries. The
+            embeddings of the successful batches are kept on the error.
+        Exception: The first batch error, if every batch failed.
+    """
+
+    batches = [
+        (start, texts[start : start + batch_size])
+        for start in range(0, len(texts), batch_size)
+    ]
+    embeddings: List[Optional[List[float]]] = [None] * len(texts)
+    failures: Dict[int, Exception] = {}
+
+    def run(batch: Tuple[int, List[str]]) -> None:
+        start, chunk = batch
+        try:
+            vectors = retry.call(lambda: embed_batch(chunk))
+        except Exception as error:
+            for index in range(start, start + len(chunk)):
+                failures[index] = error
+            return
+        for offset, vector in enumerate(vectors):
+            embeddings[start + offset] = vector
+
+    if max_concurrency > 1 and len(batches) > 1:
+        with ThreadPoolExecutor(max_workers=max_concurrency) as executor:
+            list(executor.map(run, batches))
+    else:
+        for batch in batches:
+            run(batch)
+
+    if failures:
+        if len(failures) == len(texts):
+            raise failures[min(failures)]
+        raise EmbeddingBatchError(embeddings, dict(sorted(failures.items())))
+    return embeddings  # type: ignore[return-value]
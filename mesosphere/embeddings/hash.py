@@ -0,0 +1,72 @@
+"""Deterministic hash embedding function for offline development and tests."""
+
+import hashlib
+import math
+import re
+from typing import List, Tuple
+
+from mesosphere.embeddings.core import EmbeddingCallable, EmbeddingsFunction
+
+_TOKEN_PATTERN = re.compile(r"\w+")
+
+
+class HashEmbedding(EmbeddingsFunction, EmbeddingCallable):
+    """Hash embedding function that needs no model or network."""
+
+    def __init__(
+        self,
+        dim: int = 384,
+    ):
+        """
+        Initialize the hash embedding function.
+
+        Each lowercased word is hashed to a signed bucket (feature hashing), so
+        the same text always yields the same unit-length vector and texts that
+        share words land close together.
+
+        Args:
+            dim: Embedding dimension.
+        """
+
+        if dim < 1:
+            raise ValueError("dim must be at least 1")
+        super().__init__(dimension=dim)
+        self.dim = dim
+        self.model = f"hash-{dim}"
+
+    def _embed_text(
+        self,
+        text: str,
+    ) -> List[float]:
+        """Embed one text by hashing its tokens into signed buckets."""
+
+        vector = [0.0] * self.dim
+        for token in _TOKEN_PATTERN.findall(text.lower()):
+            bucket, sign = self._bucket(token)
+            vector[bucket] += sign
+
+        norm = math.sqrt(sum(value * value for value in vector))
+        if norm == 0.0:
+            # no words, or their signs cancelled out: fall back to the whole text
+            bucket, sign = self._bucket(text)
+            vector[bucket] = sign
+            return vector
+        return [value / norm for value in vector]
+
+    def _bucket(
+        self,
+        token: str,
+    ) -> Tuple[int, float]:
+        """Map a token to its bucket index and sign."""
+
+        digest = hashlib.sha256(token.encode("utf-8")).digest()
+        bucket = int.from_bytes(digest[:8], "little") % self.dim
+        return bucket, 1.0 if digest[8] & 1 else -1.0
+
+    def embed(
+        self,
+        texts: List[str],
+    ) -> List[List[float]]:
+        """Generate deterministic embeddings for a list of texts."""
+
+        return [self._embed_text(text) for text in texts]
@@ -5,8 +5,9 @@ Ollama embedding functions for vector operations.
 import json
 import urllib.request
 import urllib.error
-from typing import List, Optional
+from typing import Any, List, Optional
 from mesosphere.embeddings.core import EmbeddingCallable, EmbeddingsFunction
+from mesosphere.embeddings.core.retry import RetryPolicy, embed_in_batches
 
 
 class OllamaEmbedding(EmbeddingsFunction, EmbeddingCallable):
@@ -17,6 +18,12 @@ class OllamaEmbedding(EmbeddingsFunction, EmbeddingCallable):
         model: str = "mxbai-embed-large",
         base_url: str = "http://localhost:11434",
         dimension: Optional[int] = None,
+        batch_size: int = 64,
+        max_concurrency: int = 4,
+        max_retries: int = 3,
+        initial_backoff: float = 1.0,
+        max_backoff: float = 30.0,
+        timeout: Optional[float] = 60.0,
     ):
         """
         Initialize Ollama embedding function.
@@ -24,11 +31,68 @@ class OllamaEmbedding(EmbeddingsFunction, EmbeddingCallable):
         Args:
             model: Name of the Ollama embedding model to use
             base_url: Base URL for Ollama API (default: http://localhost:11434)
+            batch_size: Maximum number of texts sent per `/api/embed` request
+            max_concurrency: Maximum parallel requests when falling back to
+                one request per text on servers without `/api/embed`
+            max_retries: Retries per request on rate limits and transient errors
+            initial_backoff: Seconds before the first retry, doubled per retry
+            max_backoff: Upper bound in seconds for a single retry delay
+            timeout: Seconds to wait for each HTTP request (None waits forever)
         """
 
         super().__init__(dimension=dimension)
+        if batch_size < 1:
+            raise ValueError("batch_size must be at least 1")
+        if max_concurrency < 1:
+            raise ValueError("max_concurrency must be at least 1")
         self.model = model
         self.base_url = base_url.rstrip("/")
+        self.batch_size = batch_size
+        self.max_concurrency = max_concurrency
+        self.timeout = timeout
+        self._batch_supported: Optional[bool] = None
+        self._retry = RetryPolicy(
+            max_retries=max_retries,
+            initial_backoff=initial_backoff,
+            max_backoff=max_backoff,
+        )
+
+    def _post(
+        self,
+        path: str,
+        payload: dict,
+    ) -> Any:
+        """
+        Send a JSON request to the Ollama API and decode the JSON response.
+
+        Raises:
+            ConnectionError: If Ollama server is not reachable
+            ValueError: If the response is not valid JSON
+        """
+
+        base_url = getattr(self, "base_url", "http://localhost:11434").rstrip("/")
+        timeout = getattr(self, "timeout", 60.0)
+        data = json.dumps(payload).encode("utf-8")
+
+        request = urllib.request.Request(
+            f"{base_url}{path}",
+            data=data,
+            headers={"Content-Type": "application/json"},
+            method="POST",
+        )
+
+        try:
+            with urllib.request.urlopen(request, timeout=timeout) as response:
+                return json.loads(response.read().decode("utf-8"))
+        except urllib.error.HTTPError:
+            raise
+        except urllib.error.URLError as e:
+            raise ConnectionError(
+                f"Cannot connect to Ollama at {base_url}. "
+                f"Make sure Ollama is running. If you haven't installed it go to https://ollama.com/download and install it. Error: {e}"
+            )
+        except json.JSONDecodeError as e:
+            raise ValueError(f"Invalid response from Ollama: {e}")
 
     def _get_embedding(
         self,
@@ -48,33 +112,79 @@ class OllamaEmbedding(EmbeddingsFunction, EmbeddingCallable):
             ValueError: If embedding generation fails
         """
 
-        base_url = getattr(self, "base_url", "http://localhost:11434").rstrip("/")
         model = getattr(self, "model", "mxbai-embed-large")
-        url = f"{base_url}/api/embeddings"
+        result = self._post("/api/embeddings", {"model": model, "prompt": text})
+        embedding = result.get("embedding")
+        if embedding is None:
+            raise ValueError(
+                f"No embedding returned from Ollama. "
+                f"Make sure model '{model}' is an embedding model."
+            )
+        return embedding
 
-        data = json.dumps({"model": model, "prompt": text}).encode("utf-8")
+    def _get_embeddings_batch(
+        self,
+        texts: List[str],
+    ) -> List[List[float]]:
+        """
+        Get embeddings for several texts with one `/api/embed` request.
 
-        request = urllib.request.Request(
-            url, data=data, headers={"Content-Type": "application/json"}, method="POST"
-        )
+        Raises:
+            urllib.error.HTTPError: If the server rejects the request
+            ValueError: If embedding generation fails
+        """
 
-        try:
-            with urllib.request.urlopen(request, timeout=60) as response:
-                result = json.loads(response.read().decode("utf-8"))
-                embedding = result.get("embedding")
-                if embedding is None:
-                    raise ValueError(
-                        f"No embedding returned from Ollama. "
-                        f"Make sure model '{model}' is an embedding model."
-                    )
-                return embedding
-        except urllib.error.URLError as e:
-            raise ConnectionError(
-                f"Cannot connect to Ollama at {base_url}. "
-                f"Make sure Ollama is running. If you haven't installed it go to https://ollama.com/download and install it. Error: {e}"
+        result = self._post("/api/embed", {"model": self.model, "input": texts})
+        embeddings = result.get("embeddings")
+        if not isinstance(embeddings, list) or len(embeddings) != len(texts):
+            raise ValueError(
+                f"No embeddings returned from Ollama. "
+                f"Make sure model '{self.model}' is an embedding model."
             )
-        except json.JSONDecodeError as e:
-            raise ValueError(f"Invalid response from Ollama: {e}")
+        return embeddings
+
+    def embed(
+        self,
+        texts: List[str],
+    ) -> List[List[float]]:
+        """
+        Generate embeddings for a list of texts.
+
+        Uses Ollama's batch `/api/embed` endpoint, falling back to bounded
+        parallel `/api/embeddings` requests on servers that do not provide it.
+        Each request is retried on rate limits and transient errors.
+
+        Raises:
+            EmbeddingBatchError: If only some texts failed after retries
+        """
+
+        if not texts:
+            return []
+
+        embeddings: Optional[List[List[float]]] = None
+        if self._batch_supported is not False:
+            try:
+                embeddings = embed_in_batches(
+                    texts, self.batch_size, self._get_embeddings_batch, self._retry
+                )
+                self._batch_supported = True
+            except urllib.error.HTTPError as e:
+                if e.code != 404 or self._batch_supported:
+                    raise ValueError(f"Ollama embedding request failed: {e}")
+                self._batch_supported = False
+
+        if embeddings is None:
+            embeddings = embed_in_batches(
+                texts,
+                1,
+                lambda chunk: [self._get_embedding(chunk[0])],
+                self._retry,
+                max_concurrency=self.max_concurrency,
+            )
+
+        if self._dimension is None and embeddings:
+            self._dimension = len(embeddings[0])
+        return embeddings
 
     def get_dimension(self) -> int:
         """
@@ -86,6 +196,5 @@ class OllamaEmbedding(EmbeddingsFunction, EmbeddingCallable):
 
         if self._dimension is None:
             # generate a test embedding to determine dimension
-            test_embedding = self._get_embedding("test")
-            self._dimension = len(test_embedding)
+            self.embed(["test"])
         return self._dimension
@@ -3,6 +3,7 @@
 import os
 from typing import Any, List, Optional
 from mesosphere.embeddings.core import EmbeddingCallable, EmbeddingsFunction
+from mesosphere.embeddings.core.retry import RetryPolicy, embed_in_batches
 
 
 class OpenAIEmbedding(EmbeddingsFunction, EmbeddingCallable):
@@ -17,6 +18,10 @@ class OpenAIEmbedding(EmbeddingsFunction, EmbeddingCallable):
         project: Optional[str] = None,
         timeout: Optional[float] = None,
         dimension: Optional[int] = None,
+        batch_size: int = 256,
+        max_retries: int = 3,
+        initial_backoff: float = 1.0,
+        max_backoff: float = 30.0,
     ):
         """
         Initialize OpenAI embedding function.
@@ -28,6 +33,10 @@ class OpenAIEmbedding(EmbeddingsFunction, EmbeddingCallable):
             organization: Optional OpenAI organization ID.
             project: Optional OpenAI project ID.
             timeout: Optional timeout in seconds.
+            batch_size: Maximum number of texts sent per request.
+            max_retries: Retries per request on 429s and transient errors.
+            initial_backoff: Seconds before the first retry, doubled per retry.
+            max_backoff: Upper bound in seconds for a single retry delay.
         """
 
         super().__init__(dimension=dimension)
@@ -37,21 +46,25 @@ class OpenAIEmbedding(EmbeddingsFunction, EmbeddingCallable):
                 "OpenAI API key is required. Provide `api_key` or set OPENAI_API_KEY."
             )
 
+        if batch_size < 1:
+            raise ValueError("batch_size must be at least 1")
         self.model = model
         self.base_url = base_url
         self.organization = organization
         self.project = project
         self.timeout = timeout
+        self.batch_size = batch_size
 
         try:
-            from openai import OpenAI
+            from openai import APIConnectionError, APITimeoutError, OpenAI
         except ImportError as exc:
             raise ImportError(
                 "OpenAI embedding provider requires the `openai` package. "
                 "Install it with `pip install openai`."
             ) from exc
 
-        client_kwargs: dict[str, Any] = {"api_key": self.api_key}
+        # retries are handled by the retry policy so Retry-After is honored per batch
+        client_kwargs: dict[str, Any] = {"api_key": self.api_key, "max_retries": 0}
         if self.base_url is not None:
             client_kwargs["base_url"] = self.base_url
         if self.organization is not None:
@@ -62,15 +75,36 @@ class OpenAIEmbedding(EmbeddingsFunction, EmbeddingCallable):
             client_kwargs["timeout"] = self.timeout
 
         self._client = OpenAI(**client_kwargs)
+        self._retry = RetryPolicy(
+            max_retries=max_retries,
+            initial_backoff=initial_backoff,
+            max_backoff=max_backoff,
+            retryable=(APIConnectionError, APITimeoutError),
+        )
 
     def embed(self, texts: List[str]) -> List[List[float]]:
-        """Generate embeddings for a list of texts using OpenAI API."""
+        """
+        Generate embeddings for a list of texts using OpenAI API.
+
+        Raises:
+            EmbeddingBatchError: If only some batches failed after retries.
+        """
 
         if not texts:
             return []
 
-        response = self._client.embeddings.create(model=self.model, input=texts)
-        embeddings = [list(item.embedding) for item in response.data]
+        embeddings = embed_in_batches(
+            texts, self.batch_size, self._embed_batch, self._retry
+        )
         if self._dimension is None and embeddings:
             self._dimension = len(embeddings[0])
         return embeddings
+
+    def _embed_batch(
+        self,
+        texts: List[str],
+    ) -> List[List[float]]:
+        """Embed one batch of texts with a single API request."""
+
+        response = self._client.embeddings.create(model=self.model, input=texts)
+        return [list(item.embedding) for item in response.data]
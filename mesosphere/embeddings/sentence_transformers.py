@@ -1,6 +1,7 @@
 """Sentence Transformers embedding functions for vector operations."""
 
-from typing import Any, List, Optional
+import threading
+from typing import Any, Dict, List, Optional
 from mesosphere.embeddings.core import EmbeddingCallable, EmbeddingsFunction
 
 
@@ -13,6 +14,10 @@ class SentenceTransformerEmbedding(EmbeddingsFunction, EmbeddingCallable):
         device: Optional[str] = None,
         normalize_embeddings: bool = False,
         dimension: Optional[int] = None,
+        timeout: Optional[float] = None,
+        batch_size: int = 32,
+        max_seq_length: Optional[int] = None,
+        devices: Optional[List[str]] = None,
     ):
         """
         Initialize Sentence Transformers embedding function.
@@ -21,12 +26,29 @@ class SentenceTransformerEmbedding(EmbeddingsFunction, EmbeddingCallable):
             model: Sentence Transformers model name.
             device: Optional device override (e.g. cpu, cuda).
             normalize_embeddings: Whether to return normalized embeddings.
+            timeout: Seconds to wait for one embed() call before raising
+                TimeoutError (None waits forever). The encode thread cannot be
+                interrupted and finishes in the background.
+            batch_size: Texts encoded per forward pass; large embed() calls
+                are split into batches of this size.
+            max_seq_length: Optional token limit per text (longer texts are
+                truncated); defaults to the model's own limit.
+            devices: Optional device pool (e.g. ["cuda:0", "cuda:1"]). With
+                more than one device, texts are spread over one worker
+                process per device. Call close() to stop the workers.
         """
 
         super().__init__(dimension=dimension)
         self.model = model
         self.device = device
         self.normalize_embeddings = normalize_embeddings
+        self.timeout = timeout
+        if batch_size < 1:
+            raise ValueError("batch_size must be at least 1")
+        self.batch_size = batch_size
+        self.devices = list(devices) if devices else None
+        self._pool: Optional[Dict[str, Any]] = None
+        self._pool_lock = threading.Lock()
 
         try:
             from sentence_transformers import SentenceTransformer
@@ -53,6 +75,8 @@ class SentenceTransformerEmbedding(EmbeddingsFunction, EmbeddingCallable):
                     "Install with `pip install sentence-transformers[onnx]`."
                 ) from exc
             raise
+        if max_seq_length is not None:
+            self._model.max_seq_length = max_seq_length
 
     @staticmethod
     def _to_list(vector: Any) -> List[float]:
@@ -62,6 +86,80 @@ class SentenceTransformerEmbedding(EmbeddingsFunction, EmbeddingCallable):
             return list(vector.tolist())
         return list(vector)
 
+    def _encode(
+        self,
+        texts: List[str],
+    ) -> Any:
+        """Encode texts with the loaded model, across the device pool if configured."""
+
+        if self.devices is not None and len(self.devices) > 1:
+            return self._model.encode_multi_process(
+                texts,
+                self._device_pool(),
+                batch_size=self.batch_size,
+                normalize_embeddings=self.normalize_embeddings,
+            )
+        return self._model.encode(
+            texts,
+            batch_size=self.batch_size,
+            convert_to_numpy=True,
+            normalize_embeddings=self.normalize_embeddings,
+        )
+
+    def _device_pool(self) -> Dict[str, Any]:
+        """Start the multi-process pool on first use."""
+
+        with self._pool_lock:
+            if self._pool is None:
+                self._pool = self._model.start_multi_process_pool(
+                    target_devices=self.devices
+                )
+            return self._pool
+
+    def close(self) -> None:
+        """Stop the device pool worker processes, if any were started."""
+
+        with self._pool_lock:
+            if self._pool is not None:
+                self._model.stop_multi_process_pool(self._pool)
+                self._pool = None
+
+    def _encode_with_deadline(
+        self,
+        texts: List[str],
+    ) -> Any:
+        """
+        Encode texts, giving up after `timeout` seconds.
+
+        Raises:
+            TimeoutError: If encoding did not finish in time
+        """
+
+        if self.timeout is None:
+            return self._encode(texts)
+
+        outcome: Dict[str, Any] = {}
+
+        def run() -> None:
+            try:
+                outcome["vectors"] = self._encode(texts)
+            except BaseException as error:
+                outcome["error"] = error
+
+        worker = threading.Thread(
+            target=run, name="sentence-transformers-encode", daemon=True
+        )
+        worker.start()
+        worker.join(self.timeout)
+        if worker.is_alive():
+            raise TimeoutError(
+                f"Sentence Transformers embedding of {len(texts)} texts did not "
+                f"finish within {self.timeout} seconds."
+            )
+        if "error" in outcome:
+            raise outcome["error"]
+        return outcome["vectors"]
+
     def embed(
         self,
         texts: List[str],
@@ -71,9 +169,7 @@ class SentenceTransformerEmbedding(EmbeddingsFunction, EmbeddingCallable):
         if not texts:
             return []
 
-        vectors = self._model.encode(
-            texts, convert_to_numpy=True, normalize_embeddings=self.normalize_embeddings
-        )
+        vectors = self._encode_with_deadline(texts)
         embeddings = [self._to_list(vector) for vector in vectors]
         if self._dimension is None and embeddings:
             self._dimension = len(embeddings[0])
@@ -0,0 +1,99 @@
+"""Voyage AI embedding functions for vector operations."""
+
+import os
+from typing import Any, List, Optional
+from mesosphere.embeddings.core import EmbeddingCallable, EmbeddingsFunction
+from mesosphere.embeddings.core.retry import RetryPolicy, embed_in_batches
+
+
+class VoyageEmbedding(EmbeddingsFunction, EmbeddingCallable):
+    """Voyage AI embedding function."""
+
+    def __init__(
+        self,
+        api_key: Optional[str] = None,
+        model: str = "voyage-3",
+        input_type: Optional[str] = None,
+        timeout: Optional[float] = None,
+        dimension: Optional[int] = None,
+        batch_size: int = 128,
+        max_retries: int = 3,
+        initial_backoff: float = 1.0,
+        max_backoff: float = 30.0,
+    ):
+        """
+        Initialize Voyage AI embedding function.
+
+        Args:
+            api_key: Voyage AI API key. If not provided, VOYAGE_API_KEY is used.
+            model: Voyage AI embedding model.
+            input_type: Optional input type (document or query).
+            timeout: Optional timeout in seconds.
+            batch_size: Maximum number of texts sent per request.
+            max_retries: Retries per request on 429s and transient errors.
+            initial_backoff: Seconds before the first retry, doubled per retry.
+            max_backoff: Upper bound in seconds for a single retry delay.
+        """
+
+        super().__init__(dimension=dimension)
+        self.api_key = api_key or os.getenv("VOYAGE_API_KEY")
+        if not self.api_key:
+            raise ValueError(
+                "Voyage AI API key is required. Provide `api_key` or set VOYAGE_API_KEY."
+            )
+
+        if batch_size < 1:
+            raise ValueError("batch_size must be at least 1")
+        self.model = model
+        self.input_type = input_type
+        self.timeout = timeout
+        self.batch_size = batch_size
+
+        try:
+            import voyageai
+        except ImportError as exc:
+            raise ImportError(
+                "Voyage AI embedding provider requires the `voyageai` package. "
+                "Install it with `pip install voyageai`."
+            ) from exc
+
+        # retries are handled by the retry policy so Retry-After is honored per batch
+        client_kwargs: dict[str, Any] = {"api_key": self.api_key, "max_retries": 0}
+        if self.timeout is not None:
+            client_kwargs["timeout"] = self.timeout
+
+        self._client = voyageai.Client(**client_kwargs)
+        self._retry = RetryPolicy(
+            max_retries=max_retries,
+            initial_backoff=initial_backoff,
+            max_backoff=max_backoff,
+        )
+
+    def embed(self, texts: List[str]) -> List[List[float]]:
+        """
+        Generate embeddings for a list of texts using Voyage AI API.
+
+        Raises:
+            EmbeddingBatchError: If only some batches failed after retries.
+        """
+
+        if not texts:
+            return []
+
+        embeddings = embed_in_batches(
+            texts, self.batch_size, self._embed_batch, self._retry
+        )
+        if self._dimension is None and embeddings:
+            self._dimension = len(embeddings[0])
+        return embeddings
+
+    def _embed_batch(
+        self,
+        texts: List[str],
+    ) -> List[List[float]]:
+        """Embed one batch of texts with a single API request."""
+
+        response = self._client.embed(
+            texts, model=self.model, input_type=self.input_type
+        )
+        return [list(vector) for vector in response.embeddings]
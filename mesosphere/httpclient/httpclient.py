@@ -4,13 +4,21 @@ from __future__ import annotations
 
 import asyncio
 from dataclasses import dataclass
-from typing import Any, Dict, List, Optional
+from typing import Any, Dict, Iterator, List, Optional
 from urllib.parse import quote
 
 import httpx
 
 from mesosphere.embeddings import get_embedding_function
 
+# Collection metadata key naming the registered embedding function a collection uses.
+EMBEDDING_FUNCTION_METADATA_KEY = "embedding_function"
+# Collection metadata key holding the provider config a collection was built with.
+EMBEDDING_CONFIG_METADATA_KEY = "embedding_config"
+# Config keys shared through collection metadata. Endpoints, credentials, devices and
+# other machine-local settings always come from the client's own config.
+_PERSISTED_EMBEDDING_CONFIG_KEYS = ("model", "dimension", "dim", "input_type")
+
 
 @dataclass(slots=True)
 class HttpTransportError(Exception):
@@ -196,6 +204,32 @@ def _apply_paging(
     return rows[start : start + max(0, int(limit))]
 
 
+def _server_include(include: List[str]) -> List[str]:
+    """Keep the include fields the server returns on request (`uris`, `data`)."""
+
+    return [field for field in include if field in ("uris", "data")]
+
+
+def _embedding_fingerprint(embedding_function: Optional[Any]) -> Optional[str]:
+    """Return the provider/model fingerprint of an embedding function, if it has one."""
+
+    fingerprint = getattr(embedding_function, "fingerprint", None)
+    return fingerprint() if callable(fingerprint) else None
+
+
+def _persisted_embedding_config(
+    provider: str, config: Optional[Dict[str, Any]]
+) -> Dict[str, Any]:
+    """Return the allow-listed provider config stored on collections."""
+
+    persisted = {
+        key: value
+        for key, value in (config or {}).items()
+        if key in _PERSISTED_EMBEDDING_CONFIG_KEYS
+    }
+    return {"provider": provider, **persisted}
+
+
 def _normalize_collection_rows(payload: Any) -> List[Dict[str, Any]]:
     """Normalize list-collections payload into a validated list of collection objects."""
     rows: Any = payload
@@ -237,6 +271,8 @@ def _normalize_collection_rows(payload: Any) -> List[Dict[str, Any]]:
             {
                 "name": name,
                 "metadata": metadata if isinstance(metadata, dict) else {},
+                "embedding_model": row.get("embedding_model"),
+                "embedding_dimension": row.get("embedding_dimension"),
             }
         )
 
@@ -252,11 +288,21 @@ class HttpCollection:
         name: str,
         metadata: Optional[Dict[str, Any]] = None,
         embedding_function: Optional[Any] = None,
+        embedding_model: Optional[str] = None,
+        embedding_dimension: Optional[int] = None,
+        reranker: Optional[Any] = None,
+        embedding_function_name: Optional[str] = None,
+        embedding_config: Optional[Dict[str, Any]] = None,
     ):
         self._transport = transport
         self._name = name
         self._metadata = metadata or {}
         self._embedding_function = embedding_function
+        self._embedding_function_name = embedding_function_name
+        self._embedding_config = embedding_config
+        self._embedding_model = embedding_model
+        self._embedding_dimension = embedding_dimension
+        self._reranker = reranker
 
     @property
     def name(self) -> str:
@@ -266,46 +312,121 @@ class HttpCollection:
     def metadata(self) -> Dict[str, Any]:
         return self._metadata
 
+    def _require_bound_function(self) -> None:
+        """Resolve the collection's embedding function, refusing an unregistered named one."""
+
+        if self._embedding_function is None and self._embedding_config is not None:
+            # built on first use so listing collections never loads models; the stored
+            # metadata is re-filtered because any client can write it
+            config = _persisted_embedding_config(
+                self._embedding_config["provider"], self._embedding_config
+            )
+            self._embedding_function = get_embedding_function(**config)
+        if self._embedding_function is None and self._embedding_function_name is not None:
+            raise ValueError(
+                f"Collection '{self._name}' is bound to embedding function "
+                f"'{self._embedding_function_name}', which is not registered on this "
+                "client. Register it with register_embedding_function()."
+            )
+
+    def _embed(self, texts: List[str]) -> List[List[float]]:
+        """Embed texts, refusing a function that differs from the collection's fingerprint."""
+
+        active_model = _embedding_fingerprint(self._embedding_function)
+        if (
+            self._embedding_model is not None
+            and active_model is not None
+            and active_model != self._embedding_model
+        ):
+            raise ValueError(
+                f"Collection '{self._name}' was built with embedding model "
+                f"'{self._embedding_model}', but the active embedding function is "
+                f"'{active_model}'. Results would not be comparable; configure the "
+                "same embedding provider and model."
+            )
+        embeddings = self._embedding_function(texts)
+        if (
+            self._embedding_dimension is not None
+            and embeddings
+            and len(embeddings[0]) != self._embedding_dimension
+        ):
+            raise ValueError(
+                f"Collection '{self._name}' holds {self._embedding_dimension}-dimensional "
+                f"embeddings, but the active embedding function produced "
+                f"{len(embeddings[0])} dimensions. Configure the embedding provider "
+                "and model the collection was built with."
+            )
+        return embeddings
+
     def add(
         self,
         *,
-        ids: List[str],
+        ids: Optional[List[str]] = None,
         embeddings: Optional[List[List[float]]] = None,
         documents: Optional[List[str]] = None,
         metadatas: Optional[List[Dict[str, Any]]] = None,
-    ) -> None:
+        uris: Optional[List[str]] = None,
+        data: Optional[List[Dict[str, Any]]] = None,
+        namespace: Optional[str] = None,
+        chunk_size: Optional[int] = None,
+        id_mode: Optional[str] = None,
+    ) -> List[str]:
+        """Add items and return their ids.
+
+        Without `ids` the server generates them: random UUIDs by default, or
+        content-derived ids with `id_mode="content_hash"`, which makes re-adding
+        identical items a no-op.
+        """
+
         if embeddings is None:
             if documents is None:
                 raise ValueError("Either embeddings or documents must be provided.")
-            if self._embedding_function is None:
-                raise ValueError(
-                    "Documents provided but no embedding function set. Configure embedding_provider first."
-                )
-            embeddings = self._embedding_function(documents)
-
-        if len(embeddings) != len(ids):
+            # Without a local embedding function the server embeds the documents.
+            self._require_bound_function()
+            if self._embedding_function is not None:
+                embeddings = self._embed(documents)
+        if id_mode is not None and id_mode not in ("uuid", "content_hash"):
+            raise ValueError("id_mode must be 'uuid' or 'content_hash'.")
+
+        if ids is None:
+            count = len(embeddings) if embeddings is not None else len(documents or [])
+            ids = [None] * count
+
+        if embeddings is not None and len(embeddings) != len(ids):
             raise ValueError("Number of embeddings must match number of IDs.")
         if documents is not None and len(documents) != len(ids):
             raise ValueError("Number of documents must match number of IDs.")
         if metadatas is not None and len(metadatas) != len(ids):
             raise ValueError("Number of metadatas must match number of IDs.")
+        if uris is not None and len(uris) != len(ids):
+            raise ValueError("Number of uris must match number of IDs.")
+        if data is not None and len(data) != len(ids):
+            raise ValueError("Number of data entries must match number of IDs.")
 
         items = []
         for index, item_id in enumerate(ids):
-            items.append(
-                {
-                    "id": item_id,
-                    "embedding": embeddings[index],
-                    "document": documents[index] if documents is not None else None,
-                    "metadata": metadatas[index] if metadatas is not None else None,
-                }
-            )
+            item = {
+                "id": item_id,
+                "document": documents[index] if documents is not None else None,
+                "metadata": metadatas[index] if metadatas is not None else None,
+                "uri": uris[index] if uris is not None else None,
+                "data": data[index] if data is not None else None,
+            }
+            if embeddings is not None:
+                item["embedding"] = embeddings[index]
+            items.append(item)
 
-        self._transport.request(
+        generated = self._transport.request(
             "POST",
             f"/v1/vector/collections/{_encode_segment(self._name)}/items/add",
-            {"items": items},
+            {
+                "items": items,
+                "namespace": namespace,
+                "chunk_size": chunk_size,
+                "id_mode": id_mode or "uuid",
+            },
         )
+        return list(generated or [])
 
     def get(
         self,
@@ -318,15 +439,20 @@ class HttpCollection:
         offset: Optional[int] = None,
     ) -> Dict[str, Any]:
         include = include or ["embeddings", "documents", "metadatas"]
-        rows = self._fetch_rows(ids=ids)
-        filtered = [
-            row
-            for row in rows
-            if _matches_where(row.get("metadata"), where)
-            and _matches_where_document(row.get("document"), where_document)
-        ]
-        paged = _apply_paging(filtered, limit, offset)
-        return {
+        if where_document is None:
+            # Metadata filters and paging run in SQL, so only the page is transferred.
+            paged = self._fetch_rows(
+                ids=ids, include=include, where=where, limit=limit, offset=offset
+            )
+        else:
+            rows = self._fetch_rows(ids=ids, include=include, where=where)
+            filtered = [
+                row
+                for row in rows
+                if _matches_where_document(row.get("document"), where_document)
+            ]
+            paged = _apply_paging(filtered, limit, offset)
+        result = {
             "ids": [row["id"] for row in paged],
             "embeddings": None,
             "documents": (
@@ -340,6 +466,11 @@ class HttpCollection:
                 else None
             ),
         }
+        if "uris" in include:
+            result["uris"] = [row.get("uri") for row in paged]
+        if "data" in include:
+            result["data"] = [row.get("data") for row in paged]
+        return result
 
     def query(
         self,
@@ -350,28 +481,56 @@ class HttpCollection:
         where: Optional[Dict[str, Any]] = None,
         where_document: Optional[Dict[str, str]] = None,
         include: Optional[List[str]] = None,
+        reranker: Optional[Any] = None,
+        rerank_candidates: Optional[int] = None,
+        namespace: Optional[str] = None,
+        explain: bool = False,
     ) -> Dict[str, Any]:
+        """
+        Query the collection by embeddings or texts.
+
+        With a reranker (per call, or set on the client), the top
+        `rerank_candidates` matches (default `4 * n_results`) are rescored
+        against the query texts and the best `n_results` are returned, with
+        their scores under `rerank_scores`. With `explain=True` the server's
+        execution trace (candidate counts and timings) is returned under
+        `explain`.
+        """
+
         include = include or ["embeddings", "documents", "metadatas", "distances"]
+        reranker = reranker if reranker is not None else self._reranker
+        if reranker is not None and query_texts is None:
+            raise ValueError("Reranking requires query_texts.")
+        candidate_count = n_results
+        if reranker is not None:
+            candidate_count = max(n_results, rerank_candidates or 4 * n_results)
 
         if query_embeddings is None:
             if query_texts is None:
                 raise ValueError(
                     "Either query_embeddings or query_texts must be provided."
                 )
-            if self._embedding_function is None:
-                raise ValueError(
-                    "Query texts provided but no embedding function set. Configure embedding_provider first."
-                )
-            query_embeddings = self._embedding_function(query_texts)
+            self._require_bound_function()
+            if self._embedding_function is not None:
+                query_embeddings = self._embed(query_texts)
 
-        fetch_limit = n_results
-        if where is not None or where_document is not None:
-            fetch_limit = max(fetch_limit, len(self._fetch_rows(ids=None)))
+        fetch_limit = candidate_count
+        if where_document is not None:
+            # the server cannot match documents, so rank every item passing `where`
+            fetch_limit = max(fetch_limit, self.count(where))
 
         payload = {
-            "query_embeddings": query_embeddings,
             "n_results": fetch_limit,
+            "include": _server_include(include),
+            "namespace": namespace,
+            "explain": explain,
         }
+        if where is not None:
+            payload["where"] = where
+        if query_embeddings is not None:
+            payload["query_embeddings"] = query_embeddings
+        else:
+            payload["query_texts"] = query_texts
         response = self._transport.request(
             "POST",
             f"/v1/vector/collections/{_encode_segment(self._name)}/query",
@@ -382,6 +541,9 @@ class HttpCollection:
         result_documents: List[List[Optional[str]]] = []
         result_metadatas: List[List[Optional[Dict[str, Any]]]] = []
         result_distances: List[List[float]] = []
+        result_uris: List[List[Optional[str]]] = []
+        result_data: List[List[Optional[Dict[str, Any]]]] = []
+        result_scores: List[List[float]] = []
 
         for query_index, id_row in enumerate(response.get("ids", [])):
             doc_row = (
@@ -399,6 +561,8 @@ class HttpCollection:
                 if response.get("distances")
                 else []
             )
+            uri_row = response["uris"][query_index] if response.get("uris") else []
+            data_row = response["data"][query_index] if response.get("data") else []
 
             filtered: List[Dict[str, Any]] = []
             for index, item_id in enumerate(id_row):
@@ -407,25 +571,45 @@ class HttpCollection:
                     "document": doc_row[index] if index < len(doc_row) else None,
                     "metadata": meta_row[index] if index < len(meta_row) else None,
                     "distance": dist_row[index] if index < len(dist_row) else 0.0,
+                    "uri": uri_row[index] if index < len(uri_row) else None,
+                    "data": data_row[index] if index < len(data_row) else None,
                 }
-                if _matches_where(
-                    candidate["metadata"], where
-                ) and _matches_where_document(candidate["document"], where_document):
+                if _matches_where_document(candidate["document"], where_document):
                     filtered.append(candidate)
 
-            top = filtered[:n_results]
+            if reranker is not None:
+                candidates = filtered[:candidate_count]
+                ranked = reranker.rerank(
+                    query_texts[query_index],
+                    [row["document"] or "" for row in candidates],
+                )[:n_results]
+                top = [candidates[index] for index, _ in ranked]
+                result_scores.append([score for _, score in ranked])
+            else:
+                top = filtered[:n_results]
             result_ids.append([row["id"] for row in top])
             result_documents.append([row["document"] for row in top])
             result_metadatas.append([row["metadata"] for row in top])
             result_distances.append([row["distance"] for row in top])
+            result_uris.append([row["uri"] for row in top])
+            result_data.append([row["data"] for row in top])
 
-        return {
+        result = {
             "ids": result_ids,
             "embeddings": None,
             "documents": result_documents if "documents" in include else None,
             "metadatas": result_metadatas if "metadatas" in include else None,
             "distances": result_distances if "distances" in include else None,
         }
+        if "uris" in include:
+            result["uris"] = result_uris
+        if "data" in include:
+            result["data"] = result_data
+        if reranker is not None:
+            result["rerank_scores"] = result_scores
+        if explain:
+            result["explain"] = response.get("explain")
+        return result
 
     def update(
         self,
@@ -436,11 +620,12 @@ class HttpCollection:
         metadatas: Optional[List[Dict[str, Any]]] = None,
     ) -> None:
         if embeddings is None and documents is not None:
+            self._require_bound_function()
             if self._embedding_function is None:
                 raise ValueError(
                     "Documents provided but no embedding function set. Configure embedding_provider first."
                 )
-            embeddings = self._embedding_function(documents)
+            embeddings = self._embed(documents)
 
         items = []
         for index, item_id in enumerate(ids):
@@ -465,14 +650,24 @@ class HttpCollection:
         ids: Optional[List[str]] = None,
         where: Optional[Dict[str, Any]] = None,
         where_document: Optional[Dict[str, str]] = None,
-    ) -> None:
-        if ids:
-            self._transport.request(
+        namespace: Optional[str] = None,
+    ) -> int:
+        """Delete matching items and return how many were removed.
+
+        `ids` and `where` are resolved server-side in a single statement; only
+        `where_document` still needs the rows to be fetched and matched locally.
+        """
+
+        if ids or (where and where_document is None):
+            payload: Dict[str, Any] = {"ids": ids or [], "namespace": namespace}
+            if where:
+                payload["where"] = where
+            result = self._transport.request(
                 "POST",
                 f"/v1/vector/collections/{_encode_segment(self._name)}/items/delete",
-                {"ids": ids},
+                payload,
             )
-            return
+            return int(result.get("affected_rows", 0))
 
         rows = self._fetch_rows(ids=None)
         ids_to_delete = []
@@ -487,24 +682,72 @@ class HttpCollection:
                 raise ValueError(
                     "delete() requires at least one of 'ids', 'where', or 'where_document' to be provided."
                 )
-            return
+            return 0
 
-        self._transport.request(
+        result = self._transport.request(
             "POST",
             f"/v1/vector/collections/{_encode_segment(self._name)}/items/delete",
-            {"ids": ids_to_delete},
+            {"ids": ids_to_delete, "namespace": namespace},
+        )
+        return int(result.get("affected_rows", 0))
+
+    def count(self, where: Optional[Dict[str, Any]] = None) -> int:
+        payload: Dict[str, Any] = {}
+        if where:
+            payload["where"] = where
+        result = self._transport.request(
+            "POST",
+            f"/v1/vector/collections/{_encode_segment(self._name)}/items/count",
+            payload,
         )
+        return int(result.get("count", 0))
 
-    def count(self) -> int:
-        return len(self._fetch_rows(ids=None))
+    def iter_items(
+        self,
+        *,
+        where: Optional[Dict[str, Any]] = None,
+        batch_size: int = 100,
+    ) -> Iterator[Dict[str, Any]]:
+        """Yield every matching item in id order, fetching `batch_size` items per request."""
+        cursor: Optional[str] = None
+        while True:
+            payload: Dict[str, Any] = {"batch_size": batch_size}
+            if cursor is not None:
+                payload["cursor"] = cursor
+            if where:
+                payload["where"] = where
+            page = self._transport.request(
+                "POST",
+                f"/v1/vector/collections/{_encode_segment(self._name)}/items/scroll",
+                payload,
+            )
+            yield from page.get("items") or []
+            cursor = page.get("next_cursor")
+            if cursor is None:
+                return
 
     def peek(self, limit: int = 10) -> Dict[str, Any]:
         return self.get(limit=limit)
 
-    def _fetch_rows(self, ids: Optional[List[str]]) -> List[Dict[str, Any]]:
+    def _fetch_rows(
+        self,
+        ids: Optional[List[str]],
+        include: Optional[List[str]] = None,
+        where: Optional[Dict[str, Any]] = None,
+        limit: Optional[int] = None,
+        offset: Optional[int] = None,
+    ) -> List[Dict[str, Any]]:
         payload: Dict[str, Any] = {}
         if ids:
             payload["ids"] = ids
+        if include:
+            payload["include"] = _server_include(include)
+        if where:
+            payload["where"] = where
+        if limit is not None:
+            payload["limit"] = max(0, int(limit))
+        if offset is not None:
+            payload["offset"] = max(0, int(offset))
         return self._transport.request(
             "POST",
             f"/v1/vector/collections/{_encode_segment(self._name)}/items/get",
@@ -523,7 +766,17 @@ class HttpClient:
         timeout: float = 30.0,
         embedding_provider: Optional[str] = None,
         embedding_model_config: Optional[Dict[str, Any]] = None,
+        embedding_functions: Optional[Dict[str, Any]] = None,
+        reranker: Optional[Any] = None,
     ):
+        """
+        Args:
+            embedding_provider: Provider of the default embedding function.
+            embedding_model_config: Config passed to `get_embedding_function`.
+            embedding_functions: Named embedding functions collections can bind
+                to, in addition to the default one.
+        """
+
         if not api_url.strip():
             raise ValueError("api_url must be a non-empty string.")
         if not api_key.strip():
@@ -537,39 +790,108 @@ class HttpClient:
                 provider=embedding_provider,
                 **(embedding_model_config or {}),
             )
+            self._embedding_config: Optional[Dict[str, Any]] = (
+                _persisted_embedding_config(embedding_provider, embedding_model_config)
+            )
         else:
             self._embedding_function = None
+            self._embedding_config = None
+        self._embedding_functions: Dict[str, Any] = {}
+        for function_name, function in (embedding_functions or {}).items():
+            self.register_embedding_function(function_name, function)
+        self._reranker = reranker
+
+    def register_embedding_function(self, name: str, function: Any) -> None:
+        """Register an embedding function that collections can bind to by name."""
+
+        if not isinstance(name, str) or not name.strip():
+            raise ValueError("Embedding function name must be a non-empty string.")
+        if not callable(function):
+            raise ValueError(f"Embedding function '{name}' must be callable.")
+        self._embedding_functions[name] = function
+
+    def _collection_from_row(self, row: Dict[str, Any]) -> HttpCollection:
+        """Build a collection handle, resolving the embedding function it is bound to."""
+
+        metadata = row.get("metadata") or {}
+        function_name = metadata.get(EMBEDDING_FUNCTION_METADATA_KEY)
+        embedding_config = metadata.get(EMBEDDING_CONFIG_METADATA_KEY)
+        if isinstance(function_name, str):
+            embedding_function = self._embedding_functions.get(function_name)
+            embedding_config = None
+        else:
+            function_name = None
+            embedding_function = self._embedding_function
+            if (
+                isinstance(embedding_config, dict)
+                and isinstance(embedding_config.get("provider"), str)
+                and embedding_config != self._embedding_config
+            ):
+                # built with another provider config: resolve that one instead
+                embedding_function = None
+            else:
+                embedding_config = None
+        return HttpCollection(
+            transport=self._transport,
+            name=row["name"],
+            metadata=metadata,
+            embedding_function=embedding_function,
+            embedding_model=row.get("embedding_model"),
+            embedding_dimension=row.get("embedding_dimension"),
+            reranker=self._reranker,
+            embedding_function_name=function_name,
+            embedding_config=embedding_config,
+        )
 
     def create_collection(
         self,
         name: str,
         metadata: Optional[Dict[str, Any]] = None,
+        embedding_function: Optional[str] = None,
     ) -> HttpCollection:
+        """
+        Create a collection.
+
+        With `embedding_function`, the collection is bound to that registered
+        function: its name is stored in the collection metadata and every
+        client resolves it by name on add, query and update. Otherwise the
+        client's provider, model, dimension and input type are stored, and
+        clients configured differently rebuild that provider for this
+        collection with their own credentials, endpoints and devices.
+        """
+
+        function = self._embedding_function
+        if embedding_function is not None:
+            if embedding_function not in self._embedding_functions:
+                raise ValueError(
+                    f"Embedding function '{embedding_function}' is not registered."
+                )
+            function = self._embedding_functions[embedding_function]
+            metadata = {
+                **(metadata or {}),
+                EMBEDDING_FUNCTION_METADATA_KEY: embedding_function,
+            }
+        elif self._embedding_config is not None:
+            metadata = {
+                **(metadata or {}),
+                EMBEDDING_CONFIG_METADATA_KEY: self._embedding_config,
+            }
         data = self._transport.request(
             "POST",
             "/v1/vector/collections",
-            {"name": name, "metadata": metadata},
-        )
-        return HttpCollection(
-            transport=self._transport,
-            name=data["name"],
-            metadata=data.get("metadata") or {},
-            embedding_function=self._embedding_function,
+            {
+                "name": name,
+                "metadata": metadata,
+                "embedding_model": _embedding_fingerprint(function),
+            },
         )
+        return self._collection_from_row(data)
 
     def list_collections(self) -> List[HttpCollection]:
         rows = _normalize_collection_rows(
             self._transport.request("GET", "/v1/vector/collections")
         )
-        return [
-            HttpCollection(
-                transport=self._transport,
-                name=row["name"],
-                metadata=row["metadata"],
-                embedding_function=self._embedding_function,
-            )
-            for row in rows
-        ]
+        return [self._collection_from_row(row) for row in rows]
 
     def get_collection(self, name: str) -> HttpCollection:
         for collection in self.list_collections():
@@ -581,11 +903,12 @@ class HttpClient:
         self,
         name: str,
         metadata: Optional[Dict[str, Any]] = None,
+        embedding_function: Optional[str] = None,
     ) -> HttpCollection:
         try:
             return self.get_collection(name)
         except ValueError:
-            return self.create_collection(name, metadata)
+            return self.create_collection(name, metadata, embedding_function)
 
     def delete_collection(self, name: str) -> None:
         self._transport.request(
@@ -593,6 +916,24 @@ class HttpClient:
             f"/v1/vector/collections/{_encode_segment(name)}",
         )
 
+    def collection_stats(self, name: str) -> Dict[str, Any]:
+        """Item count, dimensions, approximate bytes, timestamps and top metadata keys."""
+
+        return self._transport.request(
+            "GET",
+            f"/v1/vector/collections/{_encode_segment(name)}/stats",
+        )
+
+    def compact(self) -> Dict[str, Any]:
+        """Reclaim space left by deletes; returns the purged count and per-table steps."""
+
+        return self._transport.request("POST", "/v1/vector/compact", {})
+
+    def usage(self) -> Dict[str, Any]:
+        """Usage recorded for this client's API key, with the deployment's quotas."""
+
+        return self._transport.request("GET", "/v1/usage")
+
     def close(self) -> None:
         self._transport.close()
 
@@ -611,8 +952,8 @@ class AsyncHttpCollection:
     def metadata(self) -> Dict[str, Any]:
         return self._collection.metadata
 
-    async def add(self, **kwargs: Any) -> None:
-        await asyncio.to_thread(self._collection.add, **kwargs)
+    async def add(self, **kwargs: Any) -> List[str]:
+        return await asyncio.to_thread(self._collection.add, **kwargs)
 
     async def get(self, **kwargs: Any) -> Dict[str, Any]:
         return await asyncio.to_thread(self._collection.get, **kwargs)
@@ -623,11 +964,11 @@ class AsyncHttpCollection:
     async def update(self, **kwargs: Any) -> None:
         await asyncio.to_thread(self._collection.update, **kwargs)
 
-    async def delete(self, **kwargs: Any) -> None:
-        await asyncio.to_thread(self._collection.delete, **kwargs)
+    async def delete(self, **kwargs: Any) -> int:
+        return await asyncio.to_thread(self._collection.delete, **kwargs)
 
-    async def count(self) -> int:
-        return await asyncio.to_thread(self._collection.count)
+    async def count(self, where: Optional[Dict[str, Any]] = None) -> int:
+        return await asyncio.to_thread(self._collection.count, where)
 
     async def peek(self, limit: int = 10) -> Dict[str, Any]:
         return await asyncio.to_thread(self._collection.peek, limit)
@@ -639,13 +980,17 @@ class AsyncHttpClient:
     def __init__(self, **kwargs: Any):
         self._client = HttpClient(**kwargs)
 
+    def register_embedding_function(self, name: str, function: Any) -> None:
+        self._client.register_embedding_function(name, function)
+
     async def create_collection(
         self,
         name: str,
         metadata: Optional[Dict[str, Any]] = None,
+        embedding_function: Optional[str] = None,
     ) -> AsyncHttpCollection:
         collection = await asyncio.to_thread(
-            self._client.create_collection, name, metadata
+            self._client.create_collection, name, metadata, embedding_function
         )
         return AsyncHttpCollection(collection)
 
@@ -657,9 +1002,10 @@ class AsyncHttpClient:
         self,
         name: str,
         metadata: Optional[Dict[str, Any]] = None,
+        embedding_function: Optional[str] = None,
     ) -> AsyncHttpCollection:
         collection = await asyncio.to_thread(
-            self._client.get_or_create_collection, name, metadata
+            self._client.get_or_create_collection, name, metadata, embedding_function
         )
         return AsyncHttpCollection(collection)
 
@@ -670,6 +1016,15 @@ class AsyncHttpClient:
     async def delete_collection(self, name: str) -> None:
         await asyncio.to_thread(self._client.delete_collection, name)
 
+    async def collection_stats(self, name: str) -> Dict[str, Any]:
+        return await asyncio.to_thread(self._client.collection_stats, name)
+
+    async def compact(self) -> Dict[str, Any]:
+        return await asyncio.to_thread(self._client.compact)
+
+    async def usage(self) -> Dict[str, Any]:
+        return await asyncio.to_thread(self._client.usage)
+
     async def close(self) -> None:
         await asyncio.to_thread(self._client.close)
 
@@ -0,0 +1,11 @@
+"""Rerankers module."""
+
+from mesosphere.rerankers.cohere import CohereReranker
+from mesosphere.rerankers.cross_encoder import CrossEncoderReranker
+from mesosphere.rerankers.reranker import Reranker
+
+__all__ = [
+    "CohereReranker",
+    "CrossEncoderReranker",
+    "Reranker",
+]
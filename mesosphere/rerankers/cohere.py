@@ -0,0 +1,81 @@
+"""Cohere Rerank API reranker."""
+
+import os
+from typing import Any, List, Optional, Tuple
+from mesosphere.embeddings.core.retry import RetryPolicy
+from mesosphere.rerankers.reranker import Reranker
+
+
+class CohereReranker(Reranker):
+    """Reranker calling the Cohere Rerank API."""
+
+    def __init__(
+        self,
+        api_key: Optional[str] = None,
+        model: str = "rerank-english-v3.0",
+        base_url: Optional[str] = None,
+        timeout: Optional[float] = None,
+        max_retries: int = 3,
+        initial_backoff: float = 1.0,
+        max_backoff: float = 30.0,
+    ):
+        """
+        Initialize Cohere reranker.
+
+        Args:
+            api_key: Cohere API key. If not provided, COHERE_API_KEY is used.
+            model: Cohere rerank model.
+            base_url: Optional custom Cohere base URL.
+            timeout: Optional timeout in seconds.
+            max_retries: Retries per request on 429s and transient errors.
+            initial_backoff: Seconds before the first retry, doubled per retry.
+            max_backoff: Upper bound in seconds for a single retry delay.
+        """
+
+        self.api_key = api_key or os.getenv("COHERE_API_KEY")
+        if not self.api_key:
+            raise ValueError(
+                "Cohere API key is required. Provide `api_key` or set COHERE_API_KEY."
+            )
+
+        self.model = model
+
+        try:
+            import cohere
+        except ImportError as exc:
+            raise ImportError(
+                "Cohere reranker requires the `cohere` package. "
+                "Install it with `pip install cohere`."
+            ) from exc
+
+        client_kwargs: dict[str, Any] = {"api_key": self.api_key}
+        if base_url is not None:
+            client_kwargs["base_url"] = base_url
+        if timeout is not None:
+            client_kwargs["timeout"] = timeout
+
+        self._client = cohere.ClientV2(**client_kwargs)
+        self._retry = RetryPolicy(
+            max_retries=max_retries,
+            initial_backoff=initial_backoff,
+            max_backoff=max_backoff,
+        )
+
+    def rerank(
+        self,
+        query: str,
+        documents: List[str],
+    ) -> List[Tuple[int, float]]:
+        """Score documents with one Rerank API request."""
+
+        if not documents:
+            return []
+
+        response = self._retry.call(
+            lambda: self._client.rerank(
+                model=self.model, query=query, documents=documents
+            )
+        )
+        return [
+            (result.index, float(result.relevance_score)) for result in response.results
+        ]
@@ -0,0 +1,49 @@
+"""Local cross-encoder reranker backed by Sentence Transformers."""
+
+from typing import List, Optional, Tuple
+from mesosphere.rerankers.reranker import Reranker
+
+
+class CrossEncoderReranker(Reranker):
+    """Cross-encoder reranker running locally with the ONNX backend."""
+
+    def __init__(
+        self,
+        model: str = "cross-encoder/ms-marco-MiniLM-L-6-v2",
+        device: Optional[str] = None,
+    ):
+        """
+        Initialize a cross-encoder reranker.
+
+        Args:
+            model: Cross-encoder model name.
+            device: Optional device override (e.g. cpu, cuda).
+        """
+
+        self.model = model
+        self.device = device
+
+        try:
+            from sentence_transformers import CrossEncoder
+        except ImportError as exc:
+            raise ImportError(
+                "Cross-encoder reranker requires the `sentence-transformers` "
+                "package. Install it with `pip install sentence-transformers[onnx]`."
+            ) from exc
+
+        self._model = CrossEncoder(self.model, device=self.device, backend="onnx")
+
+    def rerank(
+        self,
+        query: str,
+        documents: List[str],
+    ) -> List[Tuple[int, float]]:
+        """Score each (query, document) pair with the cross-encoder."""
+
+        if not documents:
+            return []
+
+        scores = self._model.predict([(query, document) for document in documents])
+        ranked = [(index, float(score)) for index, score in enumerate(scores)]
+        ranked.sort(key=lambda pair: pair[1], reverse=True)
+        return ranked
@@ -0,0 +1,27 @@
+"""Base class for rerankers that rescore query candidates."""
+
+from typing import List, Tuple
+
+
+class Reranker:
+    """Rescores candidate documents against a query text."""
+
+    def rerank(
+        self,
+        query: str,
+        documents: List[str],
+    ) -> List[Tuple[int, float]]:
+        """
+        Score documents for a query.
+
+        Args:
+            query: Query text
+            documents: Candidate documents
+
+        Returns:
+            (document index, relevance score) pairs, most relevant first
+        """
+
+        raise NotImplementedError(
+            f"{self.__class__.__name__} must implement `rerank`."
+        )
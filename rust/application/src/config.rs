@@ -1,6 +1,8 @@
 use std::env;
 
+use hmac::{Hmac, Mac};
 use mesosphere_errors::AppError;
+use sha2::Sha256;
 
 /// Runtime configuration loaded from environment variables.
 #[derive(Debug, Clone)]
@@ -21,6 +23,16 @@ pub struct AppConfig {
     pub cors_origins: Vec<String>,
     /// Maximum vector embedding dimension accepted by API.
     pub vector_max_dim: usize,
+    /// Memory budget in bytes for cached decoded embeddings (`0` disables the cache).
+    pub vector_cache_max_bytes: usize,
+    /// Deleted vector items that trigger a background compaction (`0` disables it).
+    pub vector_auto_compact_threshold: usize,
+    /// Live vector items allowed per API key (`0` is unlimited).
+    pub quota_max_vector_items: u64,
+    /// Estimated vector storage bytes allowed per API key (`0` is unlimited).
+    pub quota_max_storage_bytes: u64,
+    /// Vector queries allowed per API key per UTC day (`0` is unlimited).
+    pub quota_max_queries_per_day: u64,
     /// Maximum query limit accepted by relational endpoints.
     pub query_max_limit: u32,
     /// Filesystem directory where uploaded storage objects are persisted.
@@ -31,6 +43,19 @@ pub struct AppConfig {
     pub storage_upload_url_ttl_seconds: u32,
     /// Maximum allowed upload size in bytes for storage uploads.
     pub storage_max_upload_bytes: usize,
+    /// Maximum total size in bytes of one multipart upload.
+    pub storage_max_multipart_bytes: u64,
+    /// Seconds between storage garbage-collection runs (`0` disables the background job).
+    pub storage_gc_interval_seconds: u32,
+    /// ImageMagick executable used for image transformations (unset or empty disables them).
+    pub storage_image_command: String,
+    /// HMAC secret for signed storage download URLs; derived from the API key when unset.
+    pub storage_signing_secret: String,
+    /// Rejects unsigned public storage downloads when enabled; `storageGetUrl` links from
+    /// functions are unsigned and stop working.
+    pub storage_require_signed_urls: bool,
+    /// Seeds generated sample data at startup and marks responses with `x-mesosphere-demo`.
+    pub demo_mode: bool,
 }
 
 impl AppConfig {
@@ -45,6 +70,15 @@ impl AppConfig {
         let mysql_pool_max = parse_u32_with_default("MESOSPHERE_MYSQL_POOL_MAX", 10)?;
         let log_level = env::var("MESOSPHERE_LOG_LEVEL").unwrap_or_else(|_| "info".to_string());
         let vector_max_dim = parse_usize_with_default("MESOSPHERE_VECTOR_MAX_DIM", 4096)?;
+        let vector_cache_max_bytes =
+            parse_usize_with_default("MESOSPHERE_VECTOR_CACHE_MAX_BYTES", 0)?;
+        let vector_auto_compact_threshold =
+            parse_usize_with_default("MESOSPHERE_VECTOR_AUTO_COMPACT_THRESHOLD", 0)?;
+        let quota_max_vector_items = parse_u64_with_default("MESOSPHERE_QUOTA_MAX_VECTOR_ITEMS", 0)?;
+        let quota_max_storage_bytes =
+            parse_u64_with_default("MESOSPHERE_QUOTA_MAX_STORAGE_BYTES", 0)?;
+        let quota_max_queries_per_day =
+            parse_u64_with_default("MESOSPHERE_QUOTA_MAX_QUERIES_PER_DAY", 0)?;
         let query_max_limit = parse_u32_with_default("MESOSPHERE_QUERY_MAX_LIMIT", 500)?;
         let storage_dir =
             env::var("MESOSPHERE_STORAGE_DIR").unwrap_or_else(|_| "./mesosphere-storage".to_string());
@@ -61,6 +95,31 @@ impl AppConfig {
             parse_u32_with_default("MESOSPHERE_STORAGE_UPLOAD_URL_TTL_SECONDS", 900)?;
         let storage_max_upload_bytes =
             parse_usize_with_default("MESOSPHERE_STORAGE_MAX_UPLOAD_BYTES", 25 * 1024 * 1024)?;
+        let storage_max_multipart_bytes = parse_u64_with_default(
+            "MESOSPHERE_STORAGE_MAX_MULTIPART_BYTES",
+            5 * 1024 * 1024 * 1024,
+        )?;
+        let storage_gc_interval_seconds =
+            parse_u32_with_default("MESOSPHERE_STORAGE_GC_INTERVAL_SECONDS", 3600)?;
+        let storage_image_command = env::var("MESOSPHERE_STORAGE_IMAGE_COMMAND")
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        let storage_signing_secret = match env::var("MESOSPHERE_STORAGE_SIGNING_SECRET")
+            .ok()
+            .filter(|secret| !secret.trim().is_empty())
+        {
+            Some(secret) => secret,
+            None => derive_storage_signing_secret(&api_key)?,
+        };
+        let storage_require_signed_urls = env::var("MESOSPHERE_STORAGE_REQUIRE_SIGNED_URLS")
+            .ok()
+            .map(|value| matches!(value.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        let demo_mode = env::var("MESOSPHERE_DEMO_MODE")
+            .ok()
+            .map(|value| matches!(value.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
         let cors_origins = env::var("MESOSPHERE_CORS_ORIGINS")
             .unwrap_or_else(|_| "*".to_string())
             .split(',')
@@ -94,15 +153,35 @@ impl AppConfig {
             log_level,
             cors_origins,
             vector_max_dim,
+            vector_cache_max_bytes,
+            vector_auto_compact_threshold,
+            quota_max_vector_items,
+            quota_max_storage_bytes,
+            quota_max_queries_per_day,
             query_max_limit,
             storage_dir,
             public_api_url,
             storage_upload_url_ttl_seconds,
             storage_max_upload_bytes,
+            storage_max_multipart_bytes,
+            storage_gc_interval_seconds,
+            storage_image_command,
+            storage_signing_secret,
+            storage_require_signed_urls,
+            demo_mode,
         })
     }
 }
 
+/// Derives a storage signing key as HMAC-SHA256(api_key, "storage-signing"), so download
+/// signatures are never computed with the raw API key.
+fn derive_storage_signing_secret(api_key: &str) -> Result<String, AppError> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(api_key.as_bytes())
+        .map_err(|error| AppError::config(format!("invalid storage signing key: {}", error)))?;
+    mac.update(b"storage-signing");
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
 fn parse_u16_with_default(name: &str, default_value: u16) -> Result<u16, AppError> {
     match env::var(name) {
         Ok(value) => value
@@ -121,6 +200,15 @@ fn parse_u32_with_default(name: &str, default_value: u32) -> Result<u32, AppErro
     }
 }
 
+fn parse_u64_with_default(name: &str, default_value: u64) -> Result<u64, AppError> {
+    match env::var(name) {
+        Ok(value) => value
+            .parse::<u64>()
+            .map_err(|_| AppError::config(format!("{} must be a valid u64", name))),
+        Err(_) => Ok(default_value),
+    }
+}
+
 fn parse_usize_with_default(name: &str, default_value: usize) -> Result<usize, AppError> {
     match env::var(name) {
         Ok(value) => value
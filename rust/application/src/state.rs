@@ -4,6 +4,12 @@ use sqlx::MySqlPool;
 
 use crate::config::AppConfig;
 
+/// Stable, non-secret identifier of the API key that authenticated a request.
+///
+/// Inserted into request extensions by the API key middleware.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyId(pub String);
+
 /// Shared state injected into all request handlers.
 #[derive(Clone)]
 pub struct AppState {
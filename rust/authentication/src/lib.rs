@@ -2,13 +2,15 @@ use axum::extract::State;
 use axum::http::{HeaderValue, Request};
 use axum::middleware::Next;
 use axum::response::Response;
-use mesosphere_application::state::AppState;
+use mesosphere_application::state::{ApiKeyId, AppState};
 use mesosphere_errors::AppError;
+use sha2::{Digest, Sha256};
 
-/// Ensures every protected request includes the configured `X-API-Key`.
+/// Ensures every protected request includes the configured `X-API-Key` and tags it with
+/// the key's `ApiKeyId`.
 pub async fn require_api_key(
     State(state): State<AppState>,
-    request: Request<axum::body::Body>,
+    mut request: Request<axum::body::Body>,
     next: Next,
 ) -> Result<Response, AppError> {
     let provided_key = request
@@ -22,9 +24,16 @@ pub async fn require_api_key(
         return Err(AppError::unauthorized("invalid API key"));
     }
 
+    let key_id = api_key_id(provided_key.as_bytes());
+    request.extensions_mut().insert(key_id);
     Ok(next.run(request).await)
 }
 
+/// Derives the identifier recorded for an API key: the first 16 hex digits of its SHA-256.
+pub fn api_key_id(key: &[u8]) -> ApiKeyId {
+    ApiKeyId(hex::encode(&Sha256::digest(key)[..8]))
+}
+
 #[cfg(test)]
 mod tests {
     use axum::body::Body;
@@ -49,11 +58,22 @@ mod tests {
             log_level: "debug".to_string(),
             cors_origins: vec!["*".to_string()],
             vector_max_dim: 4096,
+            vector_cache_max_bytes: 0,
+            vector_auto_compact_threshold: 0,
+            quota_max_vector_items: 0,
+            quota_max_storage_bytes: 0,
+            quota_max_queries_per_day: 0,
             query_max_limit: 100,
             storage_dir: "./mesosphere-storage".to_string(),
             public_api_url: "http://localhost:8000".to_string(),
             storage_upload_url_ttl_seconds: 900,
             storage_max_upload_bytes: 25 * 1024 * 1024,
+            storage_max_multipart_bytes: 5 * 1024 * 1024 * 1024,
+            storage_gc_interval_seconds: 0,
+            storage_image_command: String::new(),
+            storage_signing_secret: "test-key".to_string(),
+            storage_require_signed_urls: false,
+            demo_mode: false,
         };
         let pool = MySqlPoolOptions::new()
             .connect_lazy(&config.mysql_url)
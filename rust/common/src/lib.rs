@@ -1,4 +1,7 @@
-﻿/// Shared API contracts and envelopes.
+﻿// The OpenAPI document is one `json!` literal that outgrows the default macro depth.
+#![recursion_limit = "256"]
+
+/// Shared API contracts and envelopes.
 pub mod api;
 /// Shared middleware helpers.
 pub mod middleware;
@@ -0,0 +1,15 @@
+use axum::http::{HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+
+/// Response header flagging a backend that serves generated demo data.
+pub const DEMO_MODE_HEADER: &str = "x-mesosphere-demo";
+
+/// Marks every response with `x-mesosphere-demo: true` so clients can show a demo banner.
+pub async fn mark_demo_mode(request: Request<axum::body::Body>, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    response
+        .headers_mut()
+        .insert(DEMO_MODE_HEADER, HeaderValue::from_static("true"));
+    response
+}
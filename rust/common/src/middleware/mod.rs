@@ -1,2 +1,4 @@
-﻿/// Request ID propagation middleware.
+﻿/// Demo-mode response marker.
+pub mod demo;
+/// Request ID propagation middleware.
 pub mod request_id;
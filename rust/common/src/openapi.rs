@@ -14,6 +14,16 @@ pub async fn openapi_json() -> Json<Value> {
             {"url": "http://localhost:8000"}
         ],
         "components": {
+            "schemas": {
+                "MetadataFilter": {
+                    "type": "object",
+                    "description": "Vector metadata filter passed as `where`. Keys are ANDed; a key maps to a scalar (equality) or an operator object with `$eq`, `$ne`, `$gt`, `$gte`, `$lt`, `$lte`, `$in`, `$nin`, `$like` (SQL pattern), `$between` ([low, high]) or `$is_null` (boolean). `$and` / `$or` take arrays of filters and `$not` takes one filter, nested up to 8 levels. Dotted keys address nested metadata."
+                },
+                "RuntimeWhereClause": {
+                    "type": "object",
+                    "description": "Row filter passed as `where` to runtime function `get`, `first`, `aggregate` and `update` steps. Keys are system columns (`_id`, `_created_at`, `_updated_at`, compared as strings) or payload fields, ANDed; a key maps to a scalar (equality) or an operator object with `$eq`, `$ne`, `$gt`, `$gte`, `$lt`, `$lte`, `$in`, `$nin`, `$like` (SQL pattern), `$between` ([low, high]) or `$is_null` (boolean; a missing payload field counts as null). `$and` / `$or` take arrays of clauses and `$not` takes one clause, nested up to 8 levels."
+                }
+            },
             "securitySchemes": {
                 "ApiKeyAuth": {
                     "type": "apiKey",
@@ -27,17 +37,50 @@ pub async fn openapi_json() -> Json<Value> {
             "/healthz": {"get": {"summary": "Health check"}},
             "/readyz": {"get": {"summary": "Readiness check"}},
             "/v1/functions/stream": {"get": {"summary": "Stream function call events (SSE)"}},
-            "/v1/functions/call": {"post": {"summary": "Execute a runtime function"}},
-            "/v1/functions/deploy": {"post": {"summary": "Deploy TypeScript functions manifest (local/cloud)"}},
+            "/v1/functions/tables/stream": {"get": {"summary": "Stream committed row changes from function writes (SSE `table.changed` events)", "parameters": [{"name": "tables", "in": "query", "required": false, "schema": {"type": "string"}, "description": "Comma-separated table names to watch"}]}},
+            "/v1/metrics": {"get": {"summary": "Per-route request counts and p50/p95/p99 latencies since startup"}},
+            "/v1/functions/call": {"post": {"summary": "Execute a runtime function; `where` clauses in its table steps follow the `RuntimeWhereClause` schema"}},
+            "/v1/functions/batch": {"post": {"summary": "Execute several runtime functions in one transaction; calls marked `optional` run in savepoints and report their errors instead of aborting the batch"}},
+            "/v1/functions/deploy": {"post": {"summary": "Deploy TypeScript functions manifest (local/cloud); `?dry_run=true` returns the DDL statements and warnings without applying them", "parameters": [{"name": "dry_run", "in": "query", "required": false, "schema": {"type": "boolean", "default": false}}]}},
+            "/v1/functions/rollback": {"post": {"summary": "Swap the active functions manifest with the one the last deploy replaced; `?dry_run=true` returns the plan without applying it", "parameters": [{"name": "dry_run", "in": "query", "required": false, "schema": {"type": "boolean", "default": false}}]}},
+            "/v1/admin/schema/tables/{table}": {"delete": {"summary": "Archive a runtime table: rename it into the archive namespace and mark it inactive, keeping its rows", "parameters": [{"name": "mode", "in": "query", "required": false, "schema": {"type": "string", "enum": ["archive"], "default": "archive"}}]}},
+            "/v1/admin/schema/tables/{table}/restore": {"post": {"summary": "Restore an archived runtime table under its original name"}},
             "/v1/storage/upload": {"post": {"summary": "Upload binary file content with one-time token header", "parameters": [{"name": "X-Upload-Token", "description": "One-time upload token from `ctx.storage.createUploadUrl()` / `ctx.storage.generateUploadUrl()`. Tokens are single-use and expire shortly after issuance.", "in": "header", "required": true, "schema": {"type": "string", "format": "uuid"}}]}},
-            "/v1/storage/files/{storage_id}": {"get": {"summary": "Fetch uploaded file bytes by storage id"}},
+            "/v1/storage/multipart/init": {"post": {"summary": "Start (or resume) a multipart upload for the storage id behind a one-time upload token; parts live for 24 hours", "parameters": [{"name": "X-Upload-Token", "in": "header", "required": true, "schema": {"type": "string", "format": "uuid"}}]}},
+            "/v1/storage/multipart/{upload_id}": {"get": {"summary": "List the parts received so far so an interrupted upload can resume"}},
+            "/v1/storage/multipart/{upload_id}/parts/{part_number}": {"put": {"summary": "Upload or replace one part (1-10000); an optional X-Part-SHA256 header is verified against the received bytes", "parameters": [{"name": "X-Part-SHA256", "in": "header", "required": false, "schema": {"type": "string"}}]}},
+            "/v1/storage/multipart/{upload_id}/complete": {"post": {"summary": "Assemble contiguous parts into the stored file; optional body `{\"parts\": [{\"part_number\": 1, \"sha256\": \"...\"}]}` must match the received parts"}},
+            "/v1/storage/files/{storage_id}": {"get": {"summary": "Fetch uploaded file bytes by storage id; the content is streamed and checked against its recorded SHA-256 (returned as X-Content-SHA256), and a mismatch aborts the transfer"}},
+            "/v1/storage/{storage_id}": {"get": {"summary": "Fetch a stored file; images accept `w`, `h` (64, 128, 256, 512, 1024 or 2048, shrink only) and `format` (webp, png, jpeg) to resize or re-encode; every variant is cached on disk", "parameters": [{"name": "w", "in": "query", "required": false, "schema": {"type": "integer"}}, {"name": "h", "in": "query", "required": false, "schema": {"type": "integer"}}, {"name": "format", "in": "query", "required": false, "schema": {"type": "string", "enum": ["webp", "png", "jpeg"]}}]}},
+            "/v1/storage/{storage_id}/sign": {"post": {"summary": "Create an expiring HMAC-signed download URL that works without the API key; optional body `{\"expires_in_seconds\": 900}` (max 7 days)"}},
+            "/v1/storage/gc": {"post": {"summary": "Expire stale upload tokens, drop file records whose file is gone and delete unreferenced files"}},
             "/v1/vector/collections": {"post": {"summary": "Create vector collection"}, "get": {"summary": "List vector collections"}},
             "/v1/vector/collections/{name}": {"delete": {"summary": "Delete vector collection"}},
-            "/v1/vector/collections/{name}/items/add": {"post": {"summary": "Add vector items"}},
+            "/v1/vector/collections/{name}/export": {"get": {"summary": "Stream a vector collection as JSONL (id, embedding, document, metadata per line)"}},
+            "/v1/vector/compact": {"post": {"summary": "Purge expired items, then OPTIMIZE and ANALYZE the vector tables; returns each step's outcome"}},
+            "/v1/usage": {"get": {"summary": "Vector items, estimated storage bytes and query counts recorded for the calling API key, with the configured quotas"}},
+            "/v1/vector/collections/{name}/import": {"post": {"summary": "Import a streamed JSONL vector collection snapshot, upserting by id in chunks"}},
+            "/v1/vector/collections/{name}/items/bulk": {"post": {"summary": "Stream NDJSON items (id, document, metadata, embedding per line) into the collection in chunked transactions and return a load summary"}},
+            "/v1/vector/collections/{name}/items/add": {"post": {"summary": "Add vector items; items without an embedding are embedded from their document when server-side embedding is configured. The request commits in one transaction using multi-row inserts of `chunk_size` rows (default 500, max 4000). Items without an id get a random UUID, or a content-derived id with `id_mode: content_hash` so re-adding identical content is idempotent; returns the ids in request order"}},
             "/v1/vector/collections/{name}/items/update": {"post": {"summary": "Update vector items"}},
-            "/v1/vector/collections/{name}/items/delete": {"post": {"summary": "Delete vector items"}},
-            "/v1/vector/collections/{name}/items/get": {"post": {"summary": "Get vector items"}},
-            "/v1/vector/collections/{name}/query": {"post": {"summary": "Query vector items"}}
+            "/v1/vector/collections/{name}/items/upsert": {"post": {"summary": "Insert or replace vector items in one transaction"}},
+            "/v1/vector/collections/{name}/items/delete": {"post": {"summary": "Delete vector items by ids and/or a `where` metadata filter in one statement; returns the affected row count"}},
+            "/v1/vector/collections/{name}/items/get": {"post": {"summary": "Get vector items; optional `limit`/`offset` page the matches in SQL"}},
+            "/v1/vector/collections/{name}/items/get-ordered": {"post": {"summary": "Get vector items by id in request order with missing ids reported"}},
+            "/v1/vector/collections/{name}/items/purge-expired": {"post": {"summary": "Delete vector items whose ttl_seconds has elapsed"}},
+            "/v1/vector/collections/{name}/items/scroll": {"post": {"summary": "Scroll vector items with a stable id cursor"}},
+            "/v1/vector/collections/{name}/items/exists": {"post": {"summary": "Check whether a vector item id exists"}},
+            "/v1/vector/collections/{name}/items/count": {"post": {"summary": "Count vector items matching an optional metadata filter"}},
+            "/v1/vector/collections/{name}/stats": {"get": {"summary": "Collection statistics: item count, average and last embedding dimension, approximate bytes, timestamps and top metadata keys"}},
+            "/v1/vector/collections/{name}/namespaces": {"get": {"summary": "List the namespaces of a collection with their live item counts"}},
+            "/v1/vector/collections/{name}/namespaces/{namespace}": {"delete": {"summary": "Delete every item in one namespace"}},
+            "/v1/vector/collections/{name}/facets": {"post": {"summary": "Count distinct metadata values per key"}},
+            "/v1/vector/collections/{name}/query": {"post": {"summary": "Query vector items by query_embeddings, or by query_texts embedded on the server; `explain: true` adds an `explain` object with the search strategy (always `exact`), candidate source (cache or database), candidates scanned, filtered and scored, and load/score/total timings in milliseconds"}},
+            "/v1/vector/collections/{name}/query/scan": {"post": {"summary": "Score vector items page by page with a stable id cursor"}},
+            "/v1/vector/collections/{name}/search/text": {"post": {"summary": "Full-text search over vector item documents"}},
+            "/v1/vector/collections/{name}/query/hybrid": {"post": {"summary": "Query vector items blending cosine similarity with full-text document relevance"}},
+            "/v1/vector/collections/{name}/query/by-ids": {"post": {"summary": "Query vector items by the centroid of stored item ids"}},
+            "/v1/vector/collections/{name}/recommend": {"post": {"summary": "Recommend vector items from positive and negative example ids"}}
         }
     }))
 }
@@ -26,6 +26,10 @@ pub enum AppError {
     Validation(String),
     #[error("unauthorized: {0}")]
     Unauthorized(String),
+    #[error("forbidden: {0}")]
+    Forbidden(String),
+    #[error("too many requests: {0}")]
+    TooManyRequests(String),
     #[error("not found: {0}")]
     NotFound(String),
     #[error("database error: {0}")]
@@ -50,6 +54,16 @@ impl AppError {
         Self::Unauthorized(message.into())
     }
 
+    /// Creates a forbidden error.
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::Forbidden(message.into())
+    }
+
+    /// Creates a too-many-requests error.
+    pub fn too_many_requests(message: impl Into<String>) -> Self {
+        Self::TooManyRequests(message.into())
+    }
+
     /// Creates a not-found error.
     pub fn not_found(message: impl Into<String>) -> Self {
         Self::NotFound(message.into())
@@ -67,6 +81,8 @@ impl AppError {
             }
             Self::Validation(_) => StatusCode::BAD_REQUEST,
             Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
             Self::NotFound(_) => StatusCode::NOT_FOUND,
         }
     }
@@ -76,6 +92,8 @@ impl AppError {
             Self::Config(_) => "ConfigError",
             Self::Validation(_) => "ValidationError",
             Self::Unauthorized(_) => "UnauthorizedError",
+            Self::Forbidden(_) => "ForbiddenError",
+            Self::TooManyRequests(_) => "TooManyRequestsError",
             Self::NotFound(_) => "NotFoundError",
             Self::Database(_) => "DatabaseError",
             Self::Internal(_) => "InternalError",
@@ -87,6 +105,8 @@ impl AppError {
             Self::Config(_) => "CONFIG_ERROR",
             Self::Validation(_) => "VALIDATION_ERROR",
             Self::Unauthorized(_) => "UNAUTHORIZED",
+            Self::Forbidden(_) => "FORBIDDEN",
+            Self::TooManyRequests(_) => "TOO_MANY_REQUESTS",
             Self::NotFound(_) => "NOT_FOUND",
             Self::Database(_) => "DATABASE_ERROR",
             Self::Internal(_) => "INTERNAL_ERROR",
@@ -98,6 +118,8 @@ impl AppError {
             Self::Config(_) => "Server configuration is invalid or incomplete.",
             Self::Validation(_) => "Request payload failed validation checks.",
             Self::Unauthorized(_) => "Authentication failed or API key is missing.",
+            Self::Forbidden(_) => "The API key's quota does not allow this operation.",
+            Self::TooManyRequests(_) => "The API key's request quota is exhausted for now.",
             Self::NotFound(_) => "Requested resource or function endpoint was not found.",
             Self::Database(_) => "Database operation failed while processing the request.",
             Self::Internal(_) => "Unexpected internal server error.",
@@ -0,0 +1,484 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use serde_json::{json, Value};
+use tokio::task::JoinSet;
+
+/// Usage printed by `--help` and on invalid arguments.
+const USAGE: &str = "\
+Load-tests a running Mesosphere backend with a mixed relational/vector workload.
+
+Usage: cargo run --release --example bench -- [options]
+
+Options:
+  --url <url>               Backend base URL (default http://127.0.0.1:8000)
+  --api-key <key>           API key (default $MESOSPHERE_API_KEY)
+  --concurrency <n>         Concurrent workers (default 8)
+  --requests <n>            Total operations to run (default 1000)
+  --dimension <n>           Embedding dimension (default 128)
+  --seed-items <n>          Items inserted before the run (default 1000)
+  --mix <op=weight,...>     Operation weights (default add=1,query=4,filtered_query=2,get=2,count=1)
+  --function <module.name>  Runtime function called by the `function` operation
+  --function-args <json>    Arguments for --function (default {})
+  --max-error-rate <ratio>  Exit non-zero when the overall error rate exceeds this (default 0.01)
+  --keep-collection         Keep the benchmark collection instead of deleting it";
+
+/// Items written per `add` operation.
+const ADD_BATCH_SIZE: usize = 10;
+/// Distinct values of the `bucket` metadata key used by filtered queries.
+const METADATA_BUCKETS: u32 = 16;
+
+/// One kind of request issued by the benchmark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Operation {
+    Add,
+    Query,
+    FilteredQuery,
+    Get,
+    Count,
+    Function,
+}
+
+impl Operation {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "add" => Ok(Self::Add),
+            "query" => Ok(Self::Query),
+            "filtered_query" => Ok(Self::FilteredQuery),
+            "get" => Ok(Self::Get),
+            "count" => Ok(Self::Count),
+            "function" => Ok(Self::Function),
+            other => Err(format!("unknown operation '{}'", other)),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Query => "query",
+            Self::FilteredQuery => "filtered_query",
+            Self::Get => "get",
+            Self::Count => "count",
+            Self::Function => "function",
+        }
+    }
+}
+
+/// Benchmark settings parsed from the command line.
+#[derive(Debug, Clone)]
+struct BenchConfig {
+    url: String,
+    api_key: String,
+    concurrency: usize,
+    requests: usize,
+    dimension: usize,
+    seed_items: usize,
+    mix: Vec<(Operation, u32)>,
+    function: Option<String>,
+    function_args: Value,
+    max_error_rate: f64,
+    keep_collection: bool,
+}
+
+impl BenchConfig {
+    fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut config = Self {
+            url: "http://127.0.0.1:8000".to_string(),
+            api_key: std::env::var("MESOSPHERE_API_KEY").unwrap_or_default(),
+            concurrency: 8,
+            requests: 1000,
+            dimension: 128,
+            seed_items: 1000,
+            mix: parse_mix("add=1,query=4,filtered_query=2,get=2,count=1")?,
+            function: None,
+            function_args: json!({}),
+            max_error_rate: 0.01,
+            keep_collection: false,
+        };
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            if flag == "--keep-collection" {
+                config.keep_collection = true;
+                continue;
+            }
+            if flag == "--help" || flag == "-h" {
+                return Err(USAGE.to_string());
+            }
+            let value = args
+                .next()
+                .ok_or_else(|| format!("{} requires a value", flag))?;
+            let number = |value: &str| {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| format!("{} must be a non-negative integer", flag))
+            };
+            match flag.as_str() {
+                "--url" => config.url = value.trim_end_matches('/').to_string(),
+                "--api-key" => config.api_key = value,
+                "--concurrency" => config.concurrency = number(&value)?.max(1),
+                "--requests" => config.requests = number(&value)?,
+                "--dimension" => config.dimension = number(&value)?.max(1),
+                "--seed-items" => config.seed_items = number(&value)?,
+                "--mix" => config.mix = parse_mix(&value)?,
+                "--function" => config.function = Some(value),
+                "--function-args" => {
+                    config.function_args = serde_json::from_str(&value)
+                        .map_err(|error| format!("--function-args is not JSON: {}", error))?
+                }
+                "--max-error-rate" => {
+                    config.max_error_rate = value
+                        .parse::<f64>()
+                        .map_err(|_| "--max-error-rate must be a number".to_string())?
+                }
+                other => return Err(format!("unknown option '{}'\n\n{}", other, USAGE)),
+            }
+        }
+        if config.api_key.is_empty() {
+            return Err("an API key is required (--api-key or MESOSPHERE_API_KEY)".to_string());
+        }
+        let uses_function = config
+            .mix
+            .iter()
+            .any(|(operation, _)| *operation == Operation::Function);
+        if uses_function && config.function.is_none() {
+            return Err("the `function` operation requires --function".to_string());
+        }
+        Ok(config)
+    }
+}
+
+/// Parses `op=weight` pairs; zero weights are dropped.
+fn parse_mix(value: &str) -> Result<Vec<(Operation, u32)>, String> {
+    let mut mix = Vec::new();
+    for pair in value.split(',').filter(|pair| !pair.trim().is_empty()) {
+        let (name, weight) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("mix entry '{}' must look like op=weight", pair))?;
+        let weight = weight
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| format!("mix weight for '{}' must be an integer", name))?;
+        if weight > 0 {
+            mix.push((Operation::parse(name.trim())?, weight));
+        }
+    }
+    if mix.is_empty() {
+        return Err("the operation mix is empty".to_string());
+    }
+    Ok(mix)
+}
+
+/// Picks an operation with probability proportional to its weight.
+fn pick_operation(mix: &[(Operation, u32)], roll: u32) -> Operation {
+    let mut remaining = roll;
+    for (operation, weight) in mix {
+        if remaining < *weight {
+            return *operation;
+        }
+        remaining -= weight;
+    }
+    mix[mix.len() - 1].0
+}
+
+/// Latencies and failures recorded for one operation.
+#[derive(Debug, Default)]
+struct OperationStats {
+    latencies: Vec<Duration>,
+    errors: usize,
+}
+
+impl OperationStats {
+    fn merge(&mut self, other: OperationStats) {
+        self.latencies.extend(other.latencies);
+        self.errors += other.errors;
+    }
+}
+
+/// Returns the nearest-rank percentile of sorted latencies.
+fn percentile(sorted: &[Duration], percent: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((percent / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// HTTP client bound to one backend and benchmark collection.
+struct BenchClient {
+    http: reqwest::Client,
+    config: BenchConfig,
+    collection: String,
+}
+
+impl BenchClient {
+    async fn call(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Value,
+    ) -> Result<Value, String> {
+        let response = self
+            .http
+            .request(method, format!("{}/v1{}", self.config.url, path))
+            .header("X-API-Key", &self.config.api_key)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|error| error.to_string())?;
+        let status = response.status();
+        let payload = response.json::<Value>().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            return Err(format!("{} {}", status, payload));
+        }
+        Ok(payload.get("data").cloned().unwrap_or(Value::Null))
+    }
+
+    fn collection_path(&self, suffix: &str) -> String {
+        format!("/vector/collections/{}{}", self.collection, suffix)
+    }
+
+    fn random_items(&self, count: usize) -> Value {
+        let mut rng = rand::thread_rng();
+        let items = (0..count)
+            .map(|_| {
+                json!({
+                    "embedding": random_embedding(&mut rng, self.config.dimension),
+                    "document": "benchmark item",
+                    "metadata": {"bucket": rng.gen_range(0..METADATA_BUCKETS)},
+                })
+            })
+            .collect::<Vec<Value>>();
+        json!({ "items": items })
+    }
+
+    async fn run(&self, operation: Operation, ids: &[String]) -> Result<(), String> {
+        let post = reqwest::Method::POST;
+        let body = {
+            let mut rng = rand::thread_rng();
+            match operation {
+                Operation::Add => self.random_items(ADD_BATCH_SIZE),
+                Operation::Query => json!({
+                    "query_embeddings": [random_embedding(&mut rng, self.config.dimension)],
+                    "n_results": 10,
+                }),
+                Operation::FilteredQuery => json!({
+                    "query_embeddings": [random_embedding(&mut rng, self.config.dimension)],
+                    "n_results": 10,
+                    "where": {"bucket": rng.gen_range(0..METADATA_BUCKETS)},
+                }),
+                Operation::Get => {
+                    let picked = (0..ADD_BATCH_SIZE.min(ids.len()))
+                        .map(|_| ids[rng.gen_range(0..ids.len())].clone())
+                        .collect::<Vec<String>>();
+                    json!({ "ids": picked })
+                }
+                Operation::Count => json!({}),
+                Operation::Function => json!({
+                    "endpoint": self.config.function,
+                    "args": self.config.function_args,
+                }),
+            }
+        };
+        let path = match operation {
+            Operation::Add => self.collection_path("/items/add"),
+            Operation::Query | Operation::FilteredQuery => self.collection_path("/query"),
+            Operation::Get => self.collection_path("/items/get"),
+            Operation::Count => self.collection_path("/items/count"),
+            Operation::Function => "/functions/call".to_string(),
+        };
+        self.call(post, &path, body).await.map(|_| ())
+    }
+}
+
+fn random_embedding(rng: &mut impl Rng, dimension: usize) -> Vec<f32> {
+    (0..dimension).map(|_| rng.gen_range(-1.0..1.0)).collect()
+}
+
+/// Creates and seeds the benchmark collection, returning the seeded ids.
+async fn prepare(client: &BenchClient) -> Result<Vec<String>, String> {
+    client
+        .call(
+            reqwest::Method::POST,
+            "/vector/collections",
+            json!({
+                "name": client.collection,
+                "embedding_dimension": client.config.dimension,
+            }),
+        )
+        .await?;
+    let mut ids = Vec::with_capacity(client.config.seed_items);
+    let mut remaining = client.config.seed_items;
+    while remaining > 0 {
+        let batch = remaining.min(500);
+        let added = client
+            .call(
+                reqwest::Method::POST,
+                &client.collection_path("/items/add"),
+                client.random_items(batch),
+            )
+            .await?;
+        ids.extend(
+            added
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|id| id.as_str().map(str::to_string)),
+        );
+        remaining -= batch;
+    }
+    Ok(ids)
+}
+
+/// Runs `config.requests` operations across `config.concurrency` workers.
+async fn run_workload(
+    client: Arc<BenchClient>,
+    ids: Arc<Vec<String>>,
+) -> BTreeMap<Operation, OperationStats> {
+    let issued = Arc::new(AtomicUsize::new(0));
+    let total_weight = client
+        .config
+        .mix
+        .iter()
+        .map(|(_, weight)| weight)
+        .sum::<u32>();
+    let mut workers = JoinSet::new();
+    for _ in 0..client.config.concurrency {
+        let (client, ids, issued) = (client.clone(), ids.clone(), issued.clone());
+        workers.spawn(async move {
+            let mut stats = BTreeMap::<Operation, OperationStats>::new();
+            while issued.fetch_add(1, Ordering::Relaxed) < client.config.requests {
+                let roll = rand::thread_rng().gen_range(0..total_weight);
+                let mut operation = pick_operation(&client.config.mix, roll);
+                if operation == Operation::Get && ids.is_empty() {
+                    operation = Operation::Count;
+                }
+                let started = Instant::now();
+                let outcome = client.run(operation, &ids).await;
+                let entry = stats.entry(operation).or_default();
+                entry.latencies.push(started.elapsed());
+                if let Err(error) = outcome {
+                    entry.errors += 1;
+                    eprintln!("{} failed: {}", operation.as_str(), error);
+                }
+            }
+            stats
+        });
+    }
+    let mut merged = BTreeMap::<Operation, OperationStats>::new();
+    while let Some(stats) = workers.join_next().await {
+        for (operation, stats) in stats.unwrap_or_default() {
+            merged.entry(operation).or_default().merge(stats);
+        }
+    }
+    merged
+}
+
+fn print_report(stats: &mut BTreeMap<Operation, OperationStats>, elapsed: Duration) -> f64 {
+    let millis = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    println!(
+        "{:<16} {:>8} {:>8} {:>8} {:>9} {:>9} {:>9} {:>9}",
+        "operation", "count", "errors", "err%", "p50 ms", "p90 ms", "p99 ms", "max ms"
+    );
+    let (mut total, mut errors) = (0_usize, 0_usize);
+    for (operation, stats) in stats.iter_mut() {
+        stats.latencies.sort();
+        let count = stats.latencies.len();
+        total += count;
+        errors += stats.errors;
+        println!(
+            "{:<16} {:>8} {:>8} {:>7.2}% {:>9.2} {:>9.2} {:>9.2} {:>9.2}",
+            operation.as_str(),
+            count,
+            stats.errors,
+            100.0 * stats.errors as f64 / count.max(1) as f64,
+            millis(percentile(&stats.latencies, 50.0)),
+            millis(percentile(&stats.latencies, 90.0)),
+            millis(percentile(&stats.latencies, 99.0)),
+            millis(stats.latencies.last().copied().unwrap_or_default()),
+        );
+    }
+    let error_rate = errors as f64 / total.max(1) as f64;
+    println!(
+        "\n{} operations in {:.2}s ({:.1} ops/s), error rate {:.2}%",
+        total,
+        elapsed.as_secs_f64(),
+        total as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        100.0 * error_rate
+    );
+    error_rate
+}
+
+/// Drives a mixed workload against a running backend and reports latency percentiles.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv::dotenv().ok();
+    let config = match BenchConfig::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+    let client = Arc::new(BenchClient {
+        http: reqwest::Client::new(),
+        collection: format!("bench_{}", uuid::Uuid::new_v4().simple()),
+        config,
+    });
+
+    println!(
+        "seeding {} items into '{}'...",
+        client.config.seed_items, client.collection
+    );
+    let ids = Arc::new(prepare(&client).await?);
+    println!(
+        "running {} operations with {} workers...\n",
+        client.config.requests, client.config.concurrency
+    );
+    let started = Instant::now();
+    let mut stats = run_workload(client.clone(), ids).await;
+    let error_rate = print_report(&mut stats, started.elapsed());
+
+    if !client.config.keep_collection {
+        let path = client.collection_path("");
+        if let Err(error) = client.call(reqwest::Method::DELETE, &path, json!({})).await {
+            eprintln!("failed to delete '{}': {}", client.collection, error);
+        }
+    }
+    if error_rate > client.config.max_error_rate {
+        eprintln!(
+            "error rate {:.2}% exceeds the allowed {:.2}%",
+            100.0 * error_rate,
+            100.0 * client.config.max_error_rate
+        );
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{parse_mix, percentile, pick_operation, BenchConfig, Operation};
+
+    #[test]
+    fn mix_weights_and_percentiles() {
+        let mix = parse_mix("add=1, query=3,count=0").unwrap();
+        assert_eq!(mix, vec![(Operation::Add, 1), (Operation::Query, 3)]);
+        assert_eq!(pick_operation(&mix, 0), Operation::Add);
+        assert_eq!(pick_operation(&mix, 3), Operation::Query);
+        assert!(parse_mix("scan=1").is_err());
+        assert!(parse_mix("count=0").is_err());
+
+        let latencies = (1..=100).map(Duration::from_millis).collect::<Vec<_>>();
+        assert_eq!(percentile(&latencies, 50.0), Duration::from_millis(50));
+        assert_eq!(percentile(&latencies, 99.0), Duration::from_millis(99));
+        assert_eq!(percentile(&[], 99.0), Duration::ZERO);
+
+        let args = ["--api-key", "k", "--mix", "function=1"].map(String::from);
+        assert!(BenchConfig::from_args(args).is_err());
+    }
+}
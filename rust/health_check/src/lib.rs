@@ -2,6 +2,8 @@ use axum::routing::get;
 use axum::{Json, Router};
 use serde::Serialize;
 use mesosphere_application::state::AppState;
+use mesosphere_common::api::envelope::ApiEnvelope;
+use mesosphere_metrics::{route_latency_snapshot, RouteLatencySummary};
 
 /// Simple liveness/readiness response payload.
 #[derive(Debug, Serialize)]
@@ -16,6 +18,11 @@ pub fn router() -> Router<AppState> {
         .route("/readyz", get(ready))
 }
 
+/// Registers the per-route latency metrics endpoint (mounted behind API key auth).
+pub fn metrics_router() -> Router<AppState> {
+    Router::new().route("/metrics", get(metrics))
+}
+
 async fn health() -> Json<HealthResponse> {
     Json(HealthResponse { status: "ok" })
 }
@@ -23,3 +30,7 @@ async fn health() -> Json<HealthResponse> {
 async fn ready() -> Json<HealthResponse> {
     Json(HealthResponse { status: "ready" })
 }
+
+async fn metrics() -> Json<ApiEnvelope<Vec<RouteLatencySummary>>> {
+    Json(ApiEnvelope::ok(route_latency_snapshot()))
+}
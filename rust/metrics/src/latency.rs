@@ -0,0 +1,102 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+/// Number of most recent samples kept per route for percentile estimates.
+const MAX_SAMPLES_PER_ROUTE: usize = 1024;
+
+static ROUTE_LATENCIES: Lazy<Mutex<BTreeMap<(String, String), RouteLatency>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+#[derive(Default)]
+struct RouteLatency {
+    request_count: u64,
+    error_count: u64,
+    samples: VecDeque<u64>,
+}
+
+/// Latency summary for one method and route template.
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteLatencySummary {
+    /// HTTP method.
+    pub method: String,
+    /// Route template (for example `/v1/vector/collections/:name/query`).
+    pub route: String,
+    /// Requests observed since startup.
+    pub request_count: u64,
+    /// Requests that ended with a 5xx status.
+    pub error_count: u64,
+    /// Median latency over the recent window, in milliseconds.
+    pub p50_ms: u64,
+    /// 95th percentile latency over the recent window, in milliseconds.
+    pub p95_ms: u64,
+    /// 99th percentile latency over the recent window, in milliseconds.
+    pub p99_ms: u64,
+}
+
+/// Records one request latency for the in-memory per-route percentiles.
+pub fn record_route_latency(method: &str, route: &str, status: u16, duration_ms: u64) {
+    let Ok(mut routes) = ROUTE_LATENCIES.lock() else {
+        return;
+    };
+    let entry = routes
+        .entry((route.to_string(), method.to_string()))
+        .or_default();
+    entry.request_count += 1;
+    if status >= 500 {
+        entry.error_count += 1;
+    }
+    if entry.samples.len() == MAX_SAMPLES_PER_ROUTE {
+        entry.samples.pop_front();
+    }
+    entry.samples.push_back(duration_ms);
+}
+
+/// Returns latency summaries for every route observed since startup.
+pub fn route_latency_snapshot() -> Vec<RouteLatencySummary> {
+    let Ok(routes) = ROUTE_LATENCIES.lock() else {
+        return Vec::new();
+    };
+    routes
+        .iter()
+        .map(|((route, method), latency)| {
+            let mut sorted = latency.samples.iter().copied().collect::<Vec<u64>>();
+            sorted.sort_unstable();
+            RouteLatencySummary {
+                method: method.clone(),
+                route: route.clone(),
+                request_count: latency.request_count,
+                error_count: latency.error_count,
+                p50_ms: percentile(&sorted, 50.0),
+                p95_ms: percentile(&sorted, 95.0),
+                p99_ms: percentile(&sorted, 99.0),
+            }
+        })
+        .collect()
+}
+
+/// Nearest-rank percentile over ascending samples; zero when there are no samples.
+fn percentile(sorted: &[u64], percentile: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((percentile / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::percentile;
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let samples = (1..=100).collect::<Vec<u64>>();
+        assert_eq!(percentile(&samples, 50.0), 50);
+        assert_eq!(percentile(&samples, 95.0), 95);
+        assert_eq!(percentile(&samples, 99.0), 99);
+        assert_eq!(percentile(&[7], 99.0), 7);
+        assert_eq!(percentile(&[], 50.0), 0);
+    }
+}
@@ -9,6 +9,10 @@ use mesosphere_errors::AppError;
 use mesosphere_google_cloud_utils::default_cloud_run_settings;
 use tracing::{debug, info, warn};
 
+mod latency;
+
+pub use latency::{record_route_latency, route_latency_snapshot, RouteLatencySummary};
+
 static POSTHOG_CLIENT: OnceCell<PostHogClient> = OnceCell::new();
 
 /// Runtime configuration for PostHog analytics capture.
@@ -11,6 +11,9 @@ pub async fn run_bootstrap_migrations(pool: &MySqlPool) -> Result<(), AppError>
             table_name VARCHAR(255) PRIMARY KEY,
             signature TEXT NOT NULL,
             managed BOOLEAN NOT NULL DEFAULT TRUE,
+            active BOOLEAN NOT NULL DEFAULT TRUE,
+            archived_as VARCHAR(64) NULL,
+            archived_at DATETIME(6) NULL,
             updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6)
         )
         "#,
@@ -49,6 +52,10 @@ pub async fn run_bootstrap_migrations(pool: &MySqlPool) -> Result<(), AppError>
             id CHAR(36) PRIMARY KEY,
             name VARCHAR(255) NOT NULL UNIQUE,
             metadata JSON NULL,
+            metadata_schema JSON NULL,
+            quantization VARCHAR(16) NOT NULL DEFAULT 'none',
+            embedding_model VARCHAR(255) NULL,
+            embedding_dimension INT UNSIGNED NULL,
             _created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
             _updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6)
         )
@@ -62,11 +69,16 @@ pub async fn run_bootstrap_migrations(pool: &MySqlPool) -> Result<(), AppError>
         CREATE TABLE IF NOT EXISTS vector_items (
             id CHAR(36) PRIMARY KEY,
             collection_id CHAR(36) NOT NULL,
+            namespace VARCHAR(255) NOT NULL DEFAULT '',
+            parent_id VARCHAR(255) NULL,
             embedding_blob LONGBLOB NOT NULL,
             embedding_dim INT NOT NULL,
             embedding_norm DOUBLE NOT NULL,
             document TEXT NULL,
             metadata JSON NULL,
+            uri VARCHAR(2048) NULL,
+            data JSON NULL,
+            expires_at DATETIME(6) NULL,
             _created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
             _updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
             CONSTRAINT fk_vector_items_collection
@@ -85,6 +97,7 @@ pub async fn run_bootstrap_migrations(pool: &MySqlPool) -> Result<(), AppError>
             content_type VARCHAR(255) NOT NULL DEFAULT 'application/octet-stream',
             byte_size BIGINT UNSIGNED NOT NULL DEFAULT 0,
             file_path TEXT NOT NULL,
+            content_sha256 CHAR(64) NULL,
             _created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
             _updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6)
         )
@@ -109,6 +122,59 @@ pub async fn run_bootstrap_migrations(pool: &MySqlPool) -> Result<(), AppError>
     .execute(&mut *transaction)
     .await?;
 
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS _storage_multipart_uploads (
+            upload_id CHAR(36) PRIMARY KEY,
+            storage_id CHAR(36) NOT NULL,
+            token CHAR(36) NOT NULL,
+            content_type VARCHAR(255) NOT NULL DEFAULT 'application/octet-stream',
+            expires_at DATETIME(6) NOT NULL,
+            _created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+            UNIQUE KEY uq_storage_multipart_uploads_storage (storage_id),
+            CONSTRAINT fk_storage_multipart_uploads_storage
+                FOREIGN KEY (storage_id) REFERENCES _storage_files(id)
+                ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS _storage_multipart_parts (
+            upload_id CHAR(36) NOT NULL,
+            part_number INT NOT NULL,
+            byte_size BIGINT UNSIGNED NOT NULL,
+            content_sha256 CHAR(64) NOT NULL,
+            _created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+            PRIMARY KEY (upload_id, part_number),
+            CONSTRAINT fk_storage_multipart_parts_upload
+                FOREIGN KEY (upload_id) REFERENCES _storage_multipart_uploads(upload_id)
+                ON DELETE CASCADE
+        )
+        "#,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS _mesosphere_usage (
+            key_id VARCHAR(64) PRIMARY KEY,
+            vector_items BIGINT NOT NULL DEFAULT 0,
+            storage_bytes BIGINT NOT NULL DEFAULT 0,
+            query_count BIGINT NOT NULL DEFAULT 0,
+            query_day DATE NULL,
+            queries_today BIGINT NOT NULL DEFAULT 0,
+            updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6)
+        )
+        "#,
+    )
+    .execute(&mut *transaction)
+    .await?;
+
     sqlx::query(
         r#"
         CREATE TABLE IF NOT EXISTS _functions_deployments (
@@ -122,6 +188,78 @@ pub async fn run_bootstrap_migrations(pool: &MySqlPool) -> Result<(), AppError>
     .execute(&mut *transaction)
     .await?;
 
+    ensure_column(
+        &mut transaction,
+        "_mesosphere_schema_meta",
+        "active",
+        "BOOLEAN NOT NULL DEFAULT TRUE AFTER managed",
+    )
+    .await?;
+
+    ensure_column(
+        &mut transaction,
+        "_mesosphere_schema_meta",
+        "archived_as",
+        "VARCHAR(64) NULL AFTER active",
+    )
+    .await?;
+
+    ensure_column(
+        &mut transaction,
+        "_mesosphere_schema_meta",
+        "archived_at",
+        "DATETIME(6) NULL AFTER archived_as",
+    )
+    .await?;
+
+    ensure_column(
+        &mut transaction,
+        "vector_collections",
+        "metadata_schema",
+        "JSON NULL AFTER metadata",
+    )
+    .await?;
+
+    ensure_column(
+        &mut transaction,
+        "vector_collections",
+        "quantization",
+        "VARCHAR(16) NOT NULL DEFAULT 'none' AFTER metadata_schema",
+    )
+    .await?;
+
+    ensure_column(
+        &mut transaction,
+        "vector_collections",
+        "embedding_model",
+        "VARCHAR(255) NULL AFTER quantization",
+    )
+    .await?;
+
+    ensure_column(
+        &mut transaction,
+        "vector_collections",
+        "embedding_dimension",
+        "INT UNSIGNED NULL AFTER embedding_model",
+    )
+    .await?;
+
+    ensure_column(
+        &mut transaction,
+        "vector_items",
+        "uri",
+        "VARCHAR(2048) NULL AFTER metadata",
+    )
+    .await?;
+
+    ensure_column(
+        &mut transaction,
+        "vector_items",
+        "data",
+        "JSON NULL AFTER uri",
+    )
+    .await?;
+
     let index_exists = sqlx::query_scalar::<_, i64>(
         r#"
         SELECT COUNT(1)
@@ -166,6 +304,135 @@ pub async fn run_bootstrap_migrations(pool: &MySqlPool) -> Result<(), AppError>
         .await?;
     }
 
+    ensure_column(
+        &mut transaction,
+        "vector_items",
+        "expires_at",
+        "DATETIME(6) NULL AFTER metadata",
+    )
+    .await?;
+
+    ensure_index(
+        &mut transaction,
+        "vector_items",
+        "idx_vector_items_expires_at",
+        "CREATE INDEX idx_vector_items_expires_at ON vector_items(collection_id, expires_at)",
+    )
+    .await?;
+
+    ensure_column(
+        &mut transaction,
+        "vector_items",
+        "parent_id",
+        "VARCHAR(255) NULL AFTER collection_id",
+    )
+    .await?;
+
+    ensure_index(
+        &mut transaction,
+        "vector_items",
+        "idx_vector_items_parent_id",
+        "CREATE INDEX idx_vector_items_parent_id ON vector_items(collection_id, parent_id)",
+    )
+    .await?;
+
+    ensure_column(
+        &mut transaction,
+        "vector_items",
+        "namespace",
+        "VARCHAR(255) NOT NULL DEFAULT '' AFTER collection_id",
+    )
+    .await?;
+
+    ensure_index(
+        &mut transaction,
+        "vector_items",
+        "idx_vector_items_namespace",
+        "CREATE INDEX idx_vector_items_namespace ON vector_items(collection_id, namespace)",
+    )
+    .await?;
+
+    ensure_index(
+        &mut transaction,
+        "vector_items",
+        "idx_vector_items_document_fulltext",
+        "CREATE FULLTEXT INDEX idx_vector_items_document_fulltext ON vector_items(document)",
+    )
+    .await?;
+
+    ensure_column(
+        &mut transaction,
+        "_storage_files",
+        "content_sha256",
+        "CHAR(64) NULL AFTER file_path",
+    )
+    .await?;
+
+    ensure_index(
+        &mut transaction,
+        "_storage_files",
+        "idx_storage_files_content_sha256",
+        "CREATE INDEX idx_storage_files_content_sha256 ON _storage_files(content_sha256, byte_size)",
+    )
+    .await?;
+
     transaction.commit().await?;
     Ok(())
 }
+
+/// Adds a column to a table created by an earlier bootstrap when it is missing.
+async fn ensure_column(
+    connection: &mut sqlx::MySqlConnection,
+    table_name: &str,
+    column_name: &str,
+    definition: &str,
+) -> Result<(), AppError> {
+    let column_exists = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(1)
+        FROM information_schema.columns
+        WHERE table_schema = DATABASE()
+          AND table_name = ?
+          AND column_name = ?
+        "#,
+    )
+    .bind(table_name)
+    .bind(column_name)
+    .fetch_one(&mut *connection)
+    .await?;
+    if column_exists == 0 {
+        sqlx::query(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table_name, column_name, definition
+        ))
+        .execute(&mut *connection)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Creates an index with the given DDL when no index of that name exists yet.
+async fn ensure_index(
+    connection: &mut sqlx::MySqlConnection,
+    table_name: &str,
+    index_name: &str,
+    create_sql: &str,
+) -> Result<(), AppError> {
+    let index_exists = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(1)
+        FROM information_schema.statistics
+        WHERE table_schema = DATABASE()
+          AND table_name = ?
+          AND index_name = ?
+        "#,
+    )
+    .bind(table_name)
+    .bind(index_name)
+    .fetch_one(&mut *connection)
+    .await?;
+    if index_exists == 0 {
+        sqlx::query(create_sql).execute(&mut *connection).await?;
+    }
+    Ok(())
+}
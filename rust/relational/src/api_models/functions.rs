@@ -31,6 +31,43 @@ pub struct FunctionCallResponse {
     pub result: Value,
 }
 
+/// One call in a function batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionBatchCall {
+    /// Function call to run.
+    #[serde(flatten)]
+    pub call: FunctionCallRequest,
+    /// Run the call in a savepoint so its failure is reported instead of aborting the batch.
+    #[serde(default)]
+    pub optional: bool,
+}
+
+/// Request payload for running several function calls in one transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionBatchRequest {
+    /// Calls to run, in order.
+    pub calls: Vec<FunctionBatchCall>,
+}
+
+/// Outcome of one call in a function batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionBatchResult {
+    /// Endpoint that was called.
+    pub endpoint: String,
+    /// Value returned by the call; `null` when it failed.
+    pub result: Value,
+    /// Error of an optional call whose changes were rolled back.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response payload for a committed function batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionBatchResponse {
+    /// Per-call outcomes, in request order.
+    pub results: Vec<FunctionBatchResult>,
+}
+
 /// Source file uploaded by the CLI deploy command.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionDeploySourceFile {
@@ -50,6 +87,14 @@ pub struct FunctionDeployRequest {
     pub files: Vec<FunctionDeploySourceFile>,
 }
 
+/// Query parameters for function deployment.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FunctionDeployQuery {
+    /// Report the deploy plan without applying it.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
 /// Response payload after deploying a new manifest.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionDeployResponse {
@@ -57,4 +102,10 @@ pub struct FunctionDeployResponse {
     pub deployed_functions: usize,
     /// Deployment mode echoed from request metadata.
     pub mode: String,
+    /// Whether the deploy was only planned.
+    pub dry_run: bool,
+    /// DDL statements run by the deploy (or that would run, for a dry run).
+    pub statements: Vec<String>,
+    /// Destructive or surprising changes worth reviewing.
+    pub warnings: Vec<String>,
 }
@@ -1,4 +1,6 @@
 /// Functions endpoint request/response models.
 pub mod functions;
+/// Schema administration request/response models.
+pub mod schema;
 /// Storage endpoint request/response models.
 pub mod storage;
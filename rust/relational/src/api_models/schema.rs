@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// Query parameters for deleting a runtime table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteTableQuery {
+    /// Deletion mode. Only `archive` (the default) is supported; rows are never dropped.
+    #[serde(default)]
+    pub mode: Option<String>,
+}
+
+/// State of a runtime table after an archive or restore.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaTableResponse {
+    /// Runtime table name.
+    pub table: String,
+    /// Whether the table is live under its own name.
+    pub active: bool,
+    /// Archive table holding the rows while inactive.
+    pub archived_as: Option<String>,
+}
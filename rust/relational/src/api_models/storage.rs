@@ -5,5 +5,69 @@ use serde::{Deserialize, Serialize};
 pub struct StorageUploadResponse {
     /// Stable storage object id (compatible with value.id("_storage")).
     pub storage_id: String,
+    /// Hex SHA-256 of the uploaded content, returned on download as `X-Content-SHA256`.
+    pub sha256: String,
 }
 
+
+/// Optional request body for signing a storage download URL.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageSignRequest {
+    /// URL lifetime in seconds (defaults to 15 minutes, at most 7 days).
+    #[serde(default)]
+    pub expires_in_seconds: Option<u64>,
+}
+
+/// Signed download URL that works without the API key until it expires.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageSignedUrlResponse {
+    /// Download URL carrying `expires` and `signature` query parameters.
+    pub url: String,
+    /// Expiry as Unix seconds.
+    pub expires_at: i64,
+}
+
+/// One stored part of a multipart upload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageMultipartPart {
+    /// 1-based part number.
+    pub part_number: u32,
+    /// Part size in bytes.
+    pub byte_size: u64,
+    /// Hex SHA-256 of the part.
+    pub sha256: String,
+}
+
+/// State of a multipart upload, returned by init and status so clients can resume.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageMultipartUploadResponse {
+    /// Multipart upload id.
+    pub upload_id: String,
+    /// Storage object id the upload completes into.
+    pub storage_id: String,
+    /// Largest accepted part in bytes.
+    pub max_part_bytes: u64,
+    /// Largest accepted total size in bytes.
+    pub max_total_bytes: u64,
+    /// Time after which unfinished uploads are discarded.
+    pub expires_at: String,
+    /// Parts stored so far, by part number.
+    pub parts: Vec<StorageMultipartPart>,
+}
+
+/// Part checksum a client expects when completing a multipart upload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageMultipartExpectedPart {
+    /// 1-based part number.
+    pub part_number: u32,
+    /// Hex SHA-256 the stored part must match.
+    pub sha256: String,
+}
+
+/// Optional body for completing a multipart upload.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageMultipartCompleteRequest {
+    /// When sent, exactly these parts must be stored with matching checksums.
+    #[serde(default)]
+    pub parts: Option<Vec<StorageMultipartExpectedPart>>,
+}
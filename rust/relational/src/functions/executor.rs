@@ -1,16 +1,39 @@
 use std::collections::{BTreeMap, BTreeSet};
 
+use serde::Serialize;
 use serde_json::{Map, Value};
 use sqlx::{MySql, MySqlPool};
 use uuid::Uuid;
 
 use crate::functions::manifest::{FunctionKind, FunctionsManifest, ManifestFunction, ManifestStep};
 use crate::repositories::relational_repo::{
-    OrderByClause, RelationalQueryOptions, RelationalRepository,
+    OrderByClause, RelationalAggregateOptions, RelationalQueryOptions, RelationalRepository,
 };
 use mesosphere_common::contracts::field_types::{FieldDefinition, FieldType};
 use mesosphere_errors::AppError;
 
+/// Row change made by a write step, published once its transaction commits.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableChange {
+    /// Table the rows belong to.
+    pub table: String,
+    /// Step that made the change (`insert`, `update` or `upsert`).
+    pub operation: String,
+    /// Affected row ids; empty for `update`, which reports only a count.
+    pub ids: Vec<String>,
+    /// Number of affected rows.
+    pub count: u64,
+}
+
+/// Result of a function call with the row changes it committed.
+#[derive(Debug, Clone)]
+pub struct FunctionExecution {
+    /// Function return value.
+    pub result: Value,
+    /// Changes made by write steps, in step order; empty for queries.
+    pub changes: Vec<TableChange>,
+}
+
 /// Executes a runtime-defined function call and returns its JSON result.
 pub async fn execute_manifest_function(
     pool: &MySqlPool,
@@ -20,7 +43,7 @@ pub async fn execute_manifest_function(
     manifest: &FunctionsManifest,
     endpoint: &str,
     args: Map<String, Value>,
-) -> Result<Value, AppError> {
+) -> Result<FunctionExecution, AppError> {
     let function = manifest.functions.get(endpoint).ok_or_else(|| {
         AppError::not_found(format!("function endpoint '{}' not found", endpoint))
     })?;
@@ -30,16 +53,22 @@ pub async fn execute_manifest_function(
 
     match function.kind {
         FunctionKind::Query => {
-            execute_steps_without_transaction(
+            // Every read step sees the same InnoDB snapshot, even while writers commit.
+            let mut transaction = pool
+                .begin_with("START TRANSACTION WITH CONSISTENT SNAPSHOT, READ ONLY")
+                .await?;
+            let result = execute_steps_with_transaction(
                 pool,
                 &repository,
                 function,
                 &validated_args,
-                true,
+                &mut transaction,
                 public_api_url,
                 storage_upload_url_ttl_seconds,
             )
-            .await
+            .await;
+            let _ = transaction.rollback().await;
+            result
         }
         FunctionKind::Mutation => {
             let mut transaction = pool.begin().await?;
@@ -68,104 +97,204 @@ pub async fn execute_manifest_function(
     }
 }
 
-/// Ensures backing tables used by function steps exist.
+/// Executes a function inside a transaction owned by the caller, which decides whether
+/// to commit; used to run several calls atomically.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_function_in_transaction(
+    pool: &MySqlPool,
+    max_query_limit: u32,
+    public_api_url: &str,
+    storage_upload_url_ttl_seconds: u32,
+    manifest: &FunctionsManifest,
+    endpoint: &str,
+    args: Map<String, Value>,
+    transaction: &mut sqlx::Transaction<'_, MySql>,
+) -> Result<FunctionExecution, AppError> {
+    let function = manifest.functions.get(endpoint).ok_or_else(|| {
+        AppError::not_found(format!("function endpoint '{}' not found", endpoint))
+    })?;
+
+    let validated_args = validate_args(&function.args, &args)?;
+    let repository = RelationalRepository::new(pool.clone(), max_query_limit);
+    execute_steps_with_transaction(
+        pool,
+        &repository,
+        function,
+        &validated_args,
+        transaction,
+        public_api_url,
+        storage_upload_url_ttl_seconds,
+    )
+    .await
+}
+
+/// Ensures backing tables used by function steps exist. Archived tables are skipped, so a
+/// call never recreates an empty table under an archived name.
 pub async fn ensure_runtime_tables(
     pool: &MySqlPool,
     max_query_limit: u32,
     manifest: &FunctionsManifest,
 ) -> Result<(), AppError> {
     let repository = RelationalRepository::new(pool.clone(), max_query_limit);
-    let mut tables = BTreeSet::<String>::new();
+    let archived = repository.archived_tables().await?;
+    for table in runtime_tables(manifest) {
+        if !archived.contains(&table) {
+            repository.ensure_table(&table).await?;
+        }
+    }
 
-    for function in manifest.functions.values() {
-        for step in &function.steps {
-            if matches!(step.op.as_str(), "get" | "first" | "insert") {
-                if let Some(table) = step.payload.get("table").and_then(Value::as_str) {
-                    tables.insert(table.to_string());
-                }
+    Ok(())
+}
+
+/// DDL and warnings produced by deploying a manifest.
+#[derive(Debug, Clone, Default)]
+pub struct DeployPlan {
+    /// Statements the deploy runs, in order.
+    pub statements: Vec<String>,
+    /// Changes worth reviewing before the deploy is applied.
+    pub warnings: Vec<String>,
+}
+
+/// Computes the DDL and warnings for deploying `manifest` over the `active` one
+/// without applying anything.
+pub async fn plan_runtime_tables(
+    pool: &MySqlPool,
+    max_query_limit: u32,
+    manifest: &FunctionsManifest,
+    active: Option<&FunctionsManifest>,
+) -> Result<DeployPlan, AppError> {
+    let repository = RelationalRepository::new(pool.clone(), max_query_limit);
+    let mut plan = DeployPlan::default();
+    let archived = repository.archived_tables().await?;
+    for table in runtime_tables(manifest) {
+        if archived.contains(&table) {
+            plan.warnings.push(format!(
+                "table '{}' is archived; steps that use it fail until it is restored",
+                table
+            ));
+            continue;
+        }
+        if repository.table_exists(&table).await? {
+            continue;
+        }
+        plan.statements.push(RelationalRepository::create_table_sql(&table)?);
+    }
+    if let Some(active) = active {
+        plan.warnings.extend(removal_warnings(active, manifest));
+    }
+    Ok(plan)
+}
+
+fn runtime_tables(manifest: &FunctionsManifest) -> BTreeSet<String> {
+    manifest
+        .functions
+        .values()
+        .flat_map(function_tables)
+        .collect()
+}
+
+/// Tables named by the table steps of one function.
+fn function_tables(function: &ManifestFunction) -> BTreeSet<String> {
+    let mut tables = BTreeSet::<String>::new();
+    for step in &function.steps {
+        if matches!(
+            step.op.as_str(),
+            "get" | "first" | "aggregate" | "insert" | "insertMany" | "update" | "upsert"
+        ) {
+            if let Some(table) = step.payload.get("table").and_then(Value::as_str) {
+                tables.insert(table.to_string());
             }
         }
     }
+    tables
+}
 
-    for table in tables {
-        repository.ensure_table(&table).await?;
+/// Rejects a function whose steps use an archived table, instead of failing on a missing table.
+async fn reject_archived_tables(
+    repository: &RelationalRepository,
+    function: &ManifestFunction,
+) -> Result<(), AppError> {
+    let tables = function_tables(function);
+    if tables.is_empty() {
+        return Ok(());
     }
+    let archived = repository.archived_tables().await?;
+    match tables.iter().find(|table| archived.contains(*table)) {
+        Some(table) => Err(AppError::validation(format!(
+            "table '{}' is archived; restore it before calling functions that use it",
+            table
+        ))),
+        None => Ok(()),
+    }
+}
 
-    Ok(())
+/// Warns about endpoints and tables the active manifest uses but the next one drops.
+fn removal_warnings(active: &FunctionsManifest, next: &FunctionsManifest) -> Vec<String> {
+    let mut warnings = active
+        .functions
+        .keys()
+        .filter(|endpoint| !next.functions.contains_key(*endpoint))
+        .map(|endpoint| format!("function '{}' would be removed", endpoint))
+        .collect::<Vec<String>>();
+    let next_tables = runtime_tables(next);
+    warnings.extend(runtime_tables(active).difference(&next_tables).map(|table| {
+        format!(
+            "table '{}' would no longer be used by any function; its rows are kept",
+            table
+        )
+    }));
+    warnings
 }
 
 struct RuntimeContext {
     args: Map<String, Value>,
     vars: BTreeMap<String, Value>,
+    changes: Vec<TableChange>,
+    read_only: bool,
 }
 
 impl RuntimeContext {
-    fn new(args: Map<String, Value>) -> Self {
+    fn new(args: Map<String, Value>, read_only: bool) -> Self {
         Self {
             args,
             vars: BTreeMap::new(),
+            changes: Vec::new(),
+            read_only,
         }
     }
+
+    fn record_change(&mut self, table: String, operation: &str, ids: Vec<String>, count: u64) {
+        self.changes.push(TableChange {
+            table,
+            operation: operation.to_string(),
+            ids,
+            count,
+        });
+    }
 }
 
-async fn execute_steps_without_transaction(
+async fn execute_steps_with_transaction(
     pool: &MySqlPool,
     repository: &RelationalRepository,
     function: &ManifestFunction,
     args: &Map<String, Value>,
-    read_only: bool,
+    transaction: &mut sqlx::Transaction<'_, MySql>,
     public_api_url: &str,
     storage_upload_url_ttl_seconds: u32,
-) -> Result<Value, AppError> {
-    let mut context = RuntimeContext::new(args.clone());
+) -> Result<FunctionExecution, AppError> {
+    reject_archived_tables(repository, function).await?;
+    let read_only = matches!(function.kind, FunctionKind::Query);
+    let mut context = RuntimeContext::new(args.clone(), read_only);
     let mut last_result = Value::Null;
 
     for step in &function.steps {
-        if read_only && is_write_step(step) {
+        if context.read_only && is_write_step(step) {
             return Err(AppError::validation(format!(
                 "query function cannot execute '{}' step",
                 step.op
             )));
         }
 
-        let step_result =
-            execute_step_without_transaction(
-                pool,
-                repository,
-                read_only,
-                &mut context,
-                step,
-                public_api_url,
-                storage_upload_url_ttl_seconds,
-            )
-            .await?;
-
-        if let Some(into) = &step.into {
-            context.vars.insert(into.clone(), step_result.clone());
-        }
-        last_result = step_result;
-
-        if step.op == "return" {
-            return Ok(last_result);
-        }
-    }
-
-    Ok(last_result)
-}
-
-async fn execute_steps_with_transaction(
-    pool: &MySqlPool,
-    repository: &RelationalRepository,
-    function: &ManifestFunction,
-    args: &Map<String, Value>,
-    transaction: &mut sqlx::Transaction<'_, MySql>,
-    public_api_url: &str,
-    storage_upload_url_ttl_seconds: u32,
-) -> Result<Value, AppError> {
-    let mut context = RuntimeContext::new(args.clone());
-    let mut last_result = Value::Null;
-
-    for step in &function.steps {
         let step_result =
             execute_step_with_transaction(
                 pool,
@@ -184,89 +313,21 @@ async fn execute_steps_with_transaction(
         last_result = step_result;
 
         if step.op == "return" {
-            return Ok(last_result);
+            break;
         }
     }
 
-    Ok(last_result)
+    Ok(FunctionExecution {
+        result: last_result,
+        changes: context.changes,
+    })
 }
 
 fn is_write_step(step: &ManifestStep) -> bool {
-    matches!(step.op.as_str(), "insert" | "storageGenerateUploadUrl")
-}
-
-async fn execute_step_without_transaction(
-    pool: &MySqlPool,
-    repository: &RelationalRepository,
-    read_only: bool,
-    context: &mut RuntimeContext,
-    step: &ManifestStep,
-    public_api_url: &str,
-    storage_upload_url_ttl_seconds: u32,
-) -> Result<Value, AppError> {
-    match step.op.as_str() {
-        "get" => {
-            let (table, options) = parse_query_step(step, context)?;
-            let rows = repository.query(&table, options).await?;
-            Ok(Value::Array(rows))
-        }
-        "first" => {
-            let (table, options) = parse_query_step(step, context)?;
-            let row = repository.first(&table, options).await?;
-            Ok(row.unwrap_or(Value::Null))
-        }
-        "insert" => {
-            ensure_write_allowed(read_only, "insert")?;
-            let table = required_string_param(step, "table", context)?;
-            let payload = required_json_param(step, "value", context)?;
-            let id = repository.insert(&table, &payload).await?;
-            Ok(Value::String(id))
-        }
-        "storageGenerateUploadUrl" => {
-            ensure_write_allowed(read_only, "storageGenerateUploadUrl")?;
-            let url = create_upload_url(
-                pool,
-                public_api_url,
-                storage_upload_url_ttl_seconds,
-                None,
-            )
-            .await?;
-            Ok(Value::String(url))
-        }
-        "storageGetUrl" => {
-            let storage_id = required_string_param(step, "storageId", context)?;
-            let Some(storage_id) = normalize_storage_id(&storage_id) else {
-                return Ok(Value::Null);
-            };
-            let exists = storage_file_exists(pool, &storage_id, None).await?;
-            if !exists {
-                return Ok(Value::Null);
-            }
-            Ok(Value::String(build_storage_file_url(
-                public_api_url,
-                &storage_id,
-            )))
-        }
-        "assert" => {
-            let condition = required_json_param(step, "condition", context)?;
-            let message = required_literal_string(step, "message")?;
-            if !is_truthy(&condition) {
-                return Err(AppError::validation(message));
-            }
-            Ok(Value::Bool(true))
-        }
-        "setVar" => {
-            let name = required_literal_string(step, "name")?;
-            let value = required_json_param(step, "value", context)?;
-            context.vars.insert(name, value.clone());
-            Ok(value)
-        }
-        "return" => required_json_param(step, "value", context),
-        _ => Err(AppError::validation(format!(
-            "unsupported function step op '{}'",
-            step.op
-        ))),
-    }
+    matches!(
+        step.op.as_str(),
+        "insert" | "insertMany" | "update" | "upsert" | "storageGenerateUploadUrl"
+    )
 }
 
 async fn execute_step_with_transaction(
@@ -293,15 +354,78 @@ async fn execute_step_with_transaction(
                 .await?;
             Ok(row.unwrap_or(Value::Null))
         }
+        "aggregate" => {
+            let table = required_string_param(step, "table", context)?;
+            let aggregates = required_json_param(step, "aggregates", context)?;
+            let aggregates = serde_json::from_value::<BTreeMap<String, Value>>(aggregates)
+                .map_err(|error| {
+                    AppError::validation(format!(
+                        "step 'aggregate' payload 'aggregates' must be an object: {}",
+                        error
+                    ))
+                })?;
+            let options = RelationalAggregateOptions {
+                where_clause: optional_json_param(step, "where", context)?,
+                group_by: optional_select_param(step, "groupBy", context)?,
+                aggregates,
+                limit: optional_u32_param(step, "limit", context)?,
+            };
+            let rows = repository
+                .aggregate_in_transaction(transaction, &table, options)
+                .await?;
+            Ok(Value::Array(rows))
+        }
         "insert" => {
+            ensure_write_allowed(context.read_only, "insert")?;
             let table = required_string_param(step, "table", context)?;
             let payload = required_json_param(step, "value", context)?;
             let id = repository
                 .insert_in_transaction(transaction, &table, &payload)
                 .await?;
+            context.record_change(table, "insert", vec![id.clone()], 1);
+            Ok(Value::String(id))
+        }
+        "insertMany" => {
+            ensure_write_allowed(context.read_only, "insertMany")?;
+            let table = required_string_param(step, "table", context)?;
+            let values = required_json_param(step, "values", context)?;
+            let values = values.as_array().ok_or_else(|| {
+                AppError::validation("step 'insertMany' payload 'values' must be an array")
+            })?;
+            let ids = repository
+                .insert_many_in_transaction(transaction, &table, values)
+                .await?;
+            if !ids.is_empty() {
+                context.record_change(table, "insert", ids.clone(), ids.len() as u64);
+            }
+            Ok(Value::from(ids))
+        }
+        "update" => {
+            ensure_write_allowed(context.read_only, "update")?;
+            let table = required_string_param(step, "table", context)?;
+            let where_clause = required_json_param(step, "where", context)?;
+            let changes = required_json_param(step, "value", context)?;
+            let updated = repository
+                .update_in_transaction(transaction, &table, &where_clause, &changes)
+                .await?;
+            if updated > 0 {
+                context.record_change(table, "update", Vec::new(), updated);
+            }
+            Ok(Value::from(updated))
+        }
+        "upsert" => {
+            ensure_write_allowed(context.read_only, "upsert")?;
+            let table = required_string_param(step, "table", context)?;
+            let unique = required_field_list_param(step, "unique", context)?;
+            let payload = required_json_param(step, "value", context)?;
+            let id = repository
+                .upsert_in_transaction(transaction, &table, &unique, &payload)
+                .await?;
+            context.record_change(table, "upsert", vec![id.clone()], 1);
             Ok(Value::String(id))
         }
         "storageGenerateUploadUrl" => {
+            ensure_write_allowed(context.read_only, "storageGenerateUploadUrl")?;
             let url = create_upload_url(
                 pool,
                 public_api_url,
@@ -528,6 +652,7 @@ fn parse_query_step(
     let order_by = optional_order_by_param(step, "orderBy", context)?;
     let limit = optional_u32_param(step, "limit", context)?;
     let offset = optional_u32_param(step, "offset", context)?;
+    let select = optional_select_param(step, "select", context)?;
     Ok((
         table,
         RelationalQueryOptions {
@@ -535,6 +660,7 @@ fn parse_query_step(
             order_by,
             limit,
             offset,
+            select,
         },
     ))
 }
@@ -606,6 +732,36 @@ fn optional_u32_param(
     Ok(Some(number as u32))
 }
 
+fn optional_select_param(
+    step: &ManifestStep,
+    name: &str,
+    context: &RuntimeContext,
+) -> Result<Vec<String>, AppError> {
+    let Some(value) = optional_json_param(step, name, context)? else {
+        return Ok(Vec::new());
+    };
+    serde_json::from_value::<Vec<String>>(value).map_err(|error| {
+        AppError::validation(format!(
+            "step '{}' payload '{}' must be an array of field names: {}",
+            step.op, name, error
+        ))
+    })
+}
+
+fn required_field_list_param(
+    step: &ManifestStep,
+    name: &str,
+    context: &RuntimeContext,
+) -> Result<Vec<String>, AppError> {
+    let value = required_json_param(step, name, context)?;
+    serde_json::from_value::<Vec<String>>(value).map_err(|error| {
+        AppError::validation(format!(
+            "step '{}' payload '{}' must be an array of field names: {}",
+            step.op, name, error
+        ))
+    })
+}
+
 fn optional_order_by_param(
     step: &ManifestStep,
     name: &str,
@@ -841,8 +997,10 @@ fn validate_arg_value(
 #[cfg(test)]
 mod tests {
     use super::{
-        build_storage_file_url, evaluate_expression, normalize_storage_id, validate_args,
+        build_storage_file_url, evaluate_expression, normalize_storage_id, removal_warnings,
+        validate_args,
     };
+    use crate::functions::manifest::FunctionsManifest;
     use serde_json::{json, Map, Value};
     use mesosphere_common::contracts::field_types::{FieldDefinition, FieldType};
     use std::collections::BTreeMap;
@@ -899,7 +1057,7 @@ mod tests {
 
         let result = validate_args(&schema, &args);
         assert!(result.is_err());
-        let message = result.err().expect("validation error").to_string();
+        let message = result.expect_err("validation error").to_string();
         assert!(message.contains("unknown function arg 'email'"));
     }
 
@@ -916,4 +1074,29 @@ mod tests {
             "https://api.example.com/v1/storage/files/ab%2Fc%3Fd%23e"
         );
     }
+
+    #[test]
+    fn removal_warnings_report_dropped_functions_and_tables() {
+        let manifest = |functions: Value| -> FunctionsManifest {
+            serde_json::from_value(json!({ "version": 1, "functions": functions }))
+                .expect("manifest should parse")
+        };
+        let active = manifest(json!({
+            "tasks.list": { "kind": "query", "steps": [{ "op": "get", "table": "tasks" }] },
+            "notes.add": { "kind": "mutation", "steps": [{ "op": "insert", "table": "notes" }] }
+        }));
+        let next = manifest(json!({
+            "tasks.list": { "kind": "query", "steps": [{ "op": "get", "table": "tasks" }] }
+        }));
+
+        assert_eq!(
+            removal_warnings(&active, &next),
+            vec![
+                "function 'notes.add' would be removed".to_string(),
+                "table 'notes' would no longer be used by any function; its rows are kept"
+                    .to_string(),
+            ]
+        );
+        assert!(removal_warnings(&next, &active).is_empty());
+    }
 }
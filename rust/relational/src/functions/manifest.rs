@@ -274,7 +274,7 @@ fn parse_file_exports_from_text(
             .map(|m| m.as_str())
             .ok_or_else(|| AppError::internal("missing helper".to_string()))?;
         let open_brace = cursor + full.end() - 1;
-        let (options, close_brace) = extract_braced_block(&text, open_brace).map_err(|error| {
+        let (options, close_brace) = extract_braced_block(text, open_brace).map_err(|error| {
             AppError::validation(format!(
                 "invalid function '{}' in '{}': {}",
                 export_name,
@@ -414,7 +414,12 @@ fn compile_handler(
     let decl_re = Regex::new(r"^(?:const|let)\s+([A-Za-z_][A-Za-z0-9_]*)\s*=\s*([\s\S]+)$")
         .map_err(|e| AppError::internal(e.to_string()))?;
     let read_re = Regex::new(&format!(
-        r#"^await\s+{}\.db\.read\(\s*["']([^"']+)["']\s*\)\.collect\(\s*\)$"#,
+        r#"^await\s+{}\.db\s*\.read\(\s*["']([^"']+)["']\s*\)([\s\S]*)\.collect\(\s*\)$"#,
+        regex::escape(ctx_name)
+    ))
+    .map_err(|e| AppError::internal(e.to_string()))?;
+    let aggregate_re = Regex::new(&format!(
+        r#"^await\s+{}\.db\s*\.read\(\s*["']([^"']+)["']\s*\)([\s\S]*)\.aggregate\(([\s\S]+)\)$"#,
         regex::escape(ctx_name)
     ))
     .map_err(|e| AppError::internal(e.to_string()))?;
@@ -423,11 +428,21 @@ fn compile_handler(
         regex::escape(ctx_name)
     ))
     .map_err(|e| AppError::internal(e.to_string()))?;
+    let insert_many_re = Regex::new(&format!(
+        r#"^await\s+{}\.db\.insertMany\(\s*["']([^"']+)["']\s*,\s*([\s\S]+)\)$"#,
+        regex::escape(ctx_name)
+    ))
+    .map_err(|e| AppError::internal(e.to_string()))?;
     let get_re = Regex::new(&format!(
         r#"^await\s+{}\.db\.get\(\s*["']([^"']+)["']\s*,\s*([\s\S]+)\)$"#,
         regex::escape(ctx_name)
     ))
     .map_err(|e| AppError::internal(e.to_string()))?;
+    let write_re = Regex::new(&format!(
+        r#"^await\s+{}\.db\.(patch|upsert)\(\s*["']([^"']+)["']\s*,\s*([\s\S]+)\)$"#,
+        regex::escape(ctx_name)
+    ))
+    .map_err(|e| AppError::internal(e.to_string()))?;
     let get_no_await_re = Regex::new(&format!(
         r#"^{}\.db\.get\(\s*["']([^"']+)["']\s*,\s*([\s\S]+)\)$"#,
         regex::escape(ctx_name)
@@ -477,7 +492,8 @@ fn compile_handler(
                     .get(1)
                     .map(|m| m.as_str().to_string())
                     .ok_or_else(|| AppError::validation("invalid read expression".to_string()))?;
-                let mut payload = BTreeMap::new();
+                let chain = read_caps.get(2).map(|m| m.as_str()).unwrap_or_default();
+                let mut payload = compile_read_chain(chain, args_name, &vars)?;
                 payload.insert("table".to_string(), Value::String(table));
                 steps.push(ManifestStep {
                     op: "get".to_string(),
@@ -488,6 +504,56 @@ fn compile_handler(
                 continue;
             }
 
+            if let Some(aggregate_caps) = aggregate_re.captures(&initializer) {
+                let table = aggregate_caps
+                    .get(1)
+                    .map(|m| m.as_str().to_string())
+                    .ok_or_else(|| AppError::validation("invalid aggregate table".to_string()))?;
+                let chain = aggregate_caps
+                    .get(2)
+                    .map(|m| m.as_str())
+                    .unwrap_or_default();
+                let mut payload = compile_read_chain(chain, args_name, &vars)?;
+                if payload.contains_key("orderBy") || payload.contains_key("offset") {
+                    return Err(AppError::validation(format!(
+                        "function '{}' aggregate reads support only filter and take",
+                        endpoint
+                    )));
+                }
+                let arguments = aggregate_caps
+                    .get(3)
+                    .map(|m| split_top_level(m.as_str(), ','))
+                    .unwrap_or_default();
+                let (aggregates_expr, group_by_expr) = match arguments.as_slice() {
+                    [aggregates] => (aggregates, None),
+                    [aggregates, group_by] => (aggregates, Some(group_by)),
+                    _ => {
+                        return Err(AppError::validation(format!(
+                            "function '{}' aggregate expects (aggregates, groupBy?)",
+                            endpoint
+                        )));
+                    }
+                };
+                payload.insert("table".to_string(), Value::String(table));
+                payload.insert(
+                    "aggregates".to_string(),
+                    compile_expression(aggregates_expr, args_name, &vars)?,
+                );
+                if let Some(group_by_expr) = group_by_expr {
+                    payload.insert(
+                        "groupBy".to_string(),
+                        compile_expression(group_by_expr, args_name, &vars)?,
+                    );
+                }
+                steps.push(ManifestStep {
+                    op: "aggregate".to_string(),
+                    into: Some(name.clone()),
+                    payload,
+                });
+                vars.insert(name);
+                continue;
+            }
+
             if let Some(insert_caps) = insert_re.captures(&initializer) {
                 let table = insert_caps
                     .get(1)
@@ -512,6 +578,69 @@ fn compile_handler(
                 continue;
             }
 
+            if let Some(insert_many_caps) = insert_many_re.captures(&initializer) {
+                let table = insert_many_caps
+                    .get(1)
+                    .map(|m| m.as_str().to_string())
+                    .ok_or_else(|| AppError::validation("invalid insertMany table".to_string()))?;
+                let values_expr = insert_many_caps
+                    .get(2)
+                    .map(|m| m.as_str().trim())
+                    .ok_or_else(|| AppError::validation("invalid insertMany values".to_string()))?;
+                let mut payload = BTreeMap::new();
+                payload.insert("table".to_string(), Value::String(table));
+                payload.insert(
+                    "values".to_string(),
+                    compile_expression(values_expr, args_name, &vars)?,
+                );
+                steps.push(ManifestStep {
+                    op: "insertMany".to_string(),
+                    into: Some(name.clone()),
+                    payload,
+                });
+                vars.insert(name);
+                continue;
+            }
+
+            if let Some(write_caps) = write_re.captures(&initializer) {
+                let method = write_caps.get(1).map(|m| m.as_str()).unwrap_or_default();
+                let table = write_caps
+                    .get(2)
+                    .map(|m| m.as_str().to_string())
+                    .ok_or_else(|| AppError::validation(format!("invalid {} table", method)))?;
+                let arguments = write_caps
+                    .get(3)
+                    .map(|m| split_top_level(m.as_str(), ','))
+                    .unwrap_or_default();
+                let [target_expr, value_expr] = arguments.as_slice() else {
+                    return Err(AppError::validation(format!(
+                        "function '{}' {} expects a table name and two arguments",
+                        endpoint, method
+                    )));
+                };
+                let target = compile_expression(target_expr, args_name, &vars)?;
+                let mut payload = BTreeMap::new();
+                payload.insert("table".to_string(), Value::String(table));
+                payload.insert(
+                    "value".to_string(),
+                    compile_expression(value_expr, args_name, &vars)?,
+                );
+                let op = if method == "patch" {
+                    payload.insert("where".to_string(), json!({ "_id": { "$eq": target } }));
+                    "update"
+                } else {
+                    payload.insert("unique".to_string(), target);
+                    "upsert"
+                };
+                steps.push(ManifestStep {
+                    op: op.to_string(),
+                    into: Some(name.clone()),
+                    payload,
+                });
+                vars.insert(name);
+                continue;
+            }
+
             if let Some(get_caps) = get_re.captures(&initializer) {
                 let table = get_caps
                     .get(1)
@@ -705,6 +834,65 @@ fn compile_handler(
     Ok(steps)
 }
 
+/// Compiles the cursor calls between `read(table)` and `.collect()` into query step
+/// options: `filter(where)` (repeated filters are ANDed), `order(field, direction?)`,
+/// `take(limit)` and `skip(offset)`.
+fn compile_read_chain(
+    chain: &str,
+    args_name: &str,
+    vars: &BTreeSet<String>,
+) -> Result<BTreeMap<String, Value>, AppError> {
+    let mut filters = Vec::<Value>::new();
+    let mut order_by = Vec::<Value>::new();
+    let mut payload = BTreeMap::new();
+    for call in split_top_level(chain, '.') {
+        let call = call.trim();
+        if call.is_empty() {
+            continue;
+        }
+        if let Some(argument) = parse_call_argument(call, "filter") {
+            filters.push(compile_expression(argument, args_name, vars)?);
+        } else if let Some(arguments) = parse_call_argument(call, "order") {
+            let arguments = split_top_level(arguments, ',');
+            let field = compile_expression(&arguments[0], args_name, vars)?;
+            let direction = match arguments.get(1) {
+                Some(direction) => compile_expression(direction, args_name, vars)?,
+                None => Value::String("asc".to_string()),
+            };
+            order_by.push(json!({ "field": field, "direction": direction }));
+        } else if let Some(argument) = parse_call_argument(call, "take") {
+            payload.insert(
+                "limit".to_string(),
+                compile_expression(argument, args_name, vars)?,
+            );
+        } else if let Some(argument) = parse_call_argument(call, "skip") {
+            payload.insert(
+                "offset".to_string(),
+                compile_expression(argument, args_name, vars)?,
+            );
+        } else {
+            return Err(AppError::validation(format!(
+                "unsupported read cursor call '.{}'",
+                call
+            )));
+        }
+    }
+
+    match filters.len() {
+        0 => {}
+        1 => {
+            payload.insert("where".to_string(), filters.remove(0));
+        }
+        _ => {
+            payload.insert("where".to_string(), json!({ "$and": filters }));
+        }
+    }
+    if !order_by.is_empty() {
+        payload.insert("orderBy".to_string(), Value::Array(order_by));
+    }
+    Ok(payload)
+}
+
 fn compile_expression(
     expression: &str,
     args_name: &str,
@@ -833,7 +1021,7 @@ fn parse_property_name(value: &str) -> Result<String, String> {
     if let Some(text) = parse_string_literal(trimmed) {
         return Ok(text);
     }
-    let identifier_re = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").map_err(|e| e.to_string())?;
+    let identifier_re = Regex::new(r"^[A-Za-z_$][A-Za-z0-9_$]*$").map_err(|e| e.to_string())?;
     if identifier_re.is_match(trimmed) {
         return Ok(trimmed.to_string());
     }
@@ -1019,6 +1207,8 @@ fn extract_braced_block(input: &str, open_brace: usize) -> Result<(String, usize
 
 #[cfg(test)]
 mod tests {
+    use serde_json::json;
+
     use super::compile_handler;
 
     fn assert_reserved_return_slot_error(body: &str) {
@@ -1031,6 +1221,133 @@ mod tests {
         );
     }
 
+    #[test]
+    fn compile_patch_and_upsert_into_write_steps() {
+        let steps = compile_handler(
+            r#"
+            const updated = await ctx.db.patch("users", args.id, { name: args.name });
+            const id = await ctx.db.upsert("users", ["email"], { email: args.email, name: args.name });
+            return id;
+            "#,
+            "ctx",
+            "args",
+            "users:save",
+        )
+        .expect("handler should compile");
+
+        assert_eq!(steps[0].op, "update");
+        assert_eq!(
+            steps[0].payload["where"],
+            json!({ "_id": { "$eq": "$arg.id" } })
+        );
+        assert_eq!(steps[0].payload["value"], json!({ "name": "$arg.name" }));
+        assert_eq!(steps[1].op, "upsert");
+        assert_eq!(steps[1].payload["unique"], json!(["email"]));
+        assert_eq!(steps[1].into.as_deref(), Some("id"));
+
+        assert!(compile_handler(
+            r#"const n = await ctx.db.patch("users", { name: args.name });"#,
+            "ctx",
+            "args",
+            "users:save",
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn compile_insert_many_into_batch_insert_step() {
+        let steps = compile_handler(
+            r#"const ids = await ctx.db.insertMany("users", args.users); return ids;"#,
+            "ctx",
+            "args",
+            "users:import",
+        )
+        .expect("handler should compile");
+        assert_eq!(steps[0].op, "insertMany");
+        assert_eq!(steps[0].payload["values"], json!("$arg.users"));
+    }
+
+    #[test]
+    fn compile_read_chain_into_query_options() {
+        let steps = compile_handler(
+            r#"
+            const people = await ctx.db
+                .read("people")
+                .filter({ age: { $gt: args.minAge } })
+                .filter({ name: { $like: "A%" } })
+                .order("age", "desc")
+                .skip(100)
+                .take(50)
+                .collect();
+            return people;
+            "#,
+            "ctx",
+            "args",
+            "people:list",
+        )
+        .expect("handler should compile");
+
+        assert_eq!(steps[0].op, "get");
+        assert_eq!(
+            steps[0].payload["where"],
+            json!({ "$and": [
+                { "age": { "$gt": "$arg.minAge" } },
+                { "name": { "$like": "A%" } }
+            ] })
+        );
+        assert_eq!(
+            steps[0].payload["orderBy"],
+            json!([{ "field": "age", "direction": "desc" }])
+        );
+        assert_eq!(steps[0].payload["limit"], json!(50));
+        assert_eq!(steps[0].payload["offset"], json!(100));
+
+        assert!(compile_handler(
+            r#"const rows = await ctx.db.read("people").first().collect();"#,
+            "ctx",
+            "args",
+            "people:list",
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn compile_aggregate_read_with_group_by() {
+        let steps = compile_handler(
+            r#"
+            const totals = await ctx.db
+                .read("orders")
+                .filter({ status: { $ne: "void" } })
+                .aggregate({ orders: { $count: "*" }, revenue: { $sum: "amount" } }, ["status"]);
+            return totals;
+            "#,
+            "ctx",
+            "args",
+            "orders:totals",
+        )
+        .expect("handler should compile");
+
+        assert_eq!(steps[0].op, "aggregate");
+        assert_eq!(steps[0].payload["table"], json!("orders"));
+        assert_eq!(
+            steps[0].payload["aggregates"],
+            json!({ "orders": { "$count": "*" }, "revenue": { "$sum": "amount" } })
+        );
+        assert_eq!(steps[0].payload["groupBy"], json!(["status"]));
+        assert_eq!(
+            steps[0].payload["where"],
+            json!({ "status": { "$ne": "void" } })
+        );
+
+        assert!(compile_handler(
+            r#"const n = await ctx.db.read("orders").order("amount").aggregate({ n: { $count: "*" } });"#,
+            "ctx",
+            "args",
+            "orders:totals",
+        )
+        .is_err());
+    }
+
     #[test]
     fn reject_reserved_return_slot_for_return_db_get() {
         assert_reserved_return_slot_error(
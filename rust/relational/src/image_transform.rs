@@ -0,0 +1,331 @@
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use mesosphere_errors::AppError;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+
+/// Widths and heights a transformation may request. Every output is kept in the on-disk
+/// cache, so the public endpoint can render at most a few variants per image.
+const CACHED_SIZES: [u32; 6] = [64, 128, 256, 512, 1024, 2048];
+/// ImageMagick processes allowed to run at once across all requests.
+const MAX_CONCURRENT_TRANSFORMS: usize = 2;
+/// Permits for [`MAX_CONCURRENT_TRANSFORMS`]; renders beyond it wait for a running one.
+static TRANSFORM_PERMITS: Semaphore = Semaphore::const_new(MAX_CONCURRENT_TRANSFORMS);
+/// Directory under the storage directory holding cached transformation output.
+pub const CACHE_DIR: &str = "transforms";
+/// ImageMagick resource caps, passed ahead of the input so decoding is bounded too.
+const RESOURCE_LIMITS: [(&str, &str); 5] = [
+    ("memory", "256MiB"),
+    ("map", "512MiB"),
+    ("disk", "1GiB"),
+    ("area", "64MP"),
+    ("time", "20"),
+];
+/// Wall-clock budget after which the ImageMagick process is killed.
+const TRANSFORM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Output encoding of a transformed image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// WebP.
+    Webp,
+    /// PNG.
+    Png,
+    /// JPEG.
+    Jpeg,
+}
+
+impl ImageFormat {
+    fn parse(value: &str) -> Result<Self, AppError> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "webp" => Ok(Self::Webp),
+            "png" => Ok(Self::Png),
+            "jpeg" | "jpg" => Ok(Self::Jpeg),
+            other => Err(AppError::validation(format!(
+                "image format '{}' is not supported (use webp, png or jpeg)",
+                other
+            ))),
+        }
+    }
+
+    fn from_content_type(content_type: &str) -> Option<Self> {
+        match content_type.split(';').next()?.trim() {
+            "image/webp" => Some(Self::Webp),
+            "image/png" => Some(Self::Png),
+            "image/jpeg" => Some(Self::Jpeg),
+            _ => None,
+        }
+    }
+
+    /// Extension and ImageMagick coder name.
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Webp => "webp",
+            Self::Png => "png",
+            Self::Jpeg => "jpeg",
+        }
+    }
+
+    /// MIME type served for this format.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Webp => "image/webp",
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+        }
+    }
+}
+
+/// Requested resize and re-encode of a stored image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageTransform {
+    /// Maximum output width.
+    pub width: Option<u32>,
+    /// Maximum output height.
+    pub height: Option<u32>,
+    /// Output encoding.
+    pub format: ImageFormat,
+}
+
+impl ImageTransform {
+    /// Builds a transformation from `w`/`h`/`format` query values, or `None` when none are set.
+    ///
+    /// Without `format` the stored image's own format is kept.
+    pub fn from_query(
+        width: Option<u32>,
+        height: Option<u32>,
+        format: Option<&str>,
+        content_type: &str,
+    ) -> Result<Option<Self>, AppError> {
+        if width.is_none() && height.is_none() && format.is_none() {
+            return Ok(None);
+        }
+        if !content_type.starts_with("image/") {
+            return Err(AppError::validation(format!(
+                "only images can be transformed, this object is '{}'",
+                content_type
+            )));
+        }
+        for (name, value) in [("w", width), ("h", height)] {
+            if value.is_some_and(|value| !CACHED_SIZES.contains(&value)) {
+                return Err(AppError::validation(format!(
+                    "{} must be one of {}",
+                    name,
+                    CACHED_SIZES.map(|size| size.to_string()).join(", ")
+                )));
+            }
+        }
+        let format = match format {
+            Some(format) => ImageFormat::parse(format)?,
+            None => ImageFormat::from_content_type(content_type).unwrap_or(ImageFormat::Webp),
+        };
+        Ok(Some(Self {
+            width,
+            height,
+            format,
+        }))
+    }
+
+    /// Cache file name for `storage_id` (`<id>-<w>x<h>.<ext>`).
+    fn cache_file_name(&self, storage_id: &str) -> String {
+        format!(
+            "{}-{}x{}.{}",
+            storage_id,
+            self.width.unwrap_or(0),
+            self.height.unwrap_or(0),
+            self.format.extension()
+        )
+    }
+
+    /// ImageMagick geometry that only ever shrinks and keeps the aspect ratio.
+    fn geometry(&self) -> Option<String> {
+        match (self.width, self.height) {
+            (None, None) => None,
+            (width, height) => Some(format!(
+                "{}x{}>",
+                width.map(|value| value.to_string()).unwrap_or_default(),
+                height.map(|value| value.to_string()).unwrap_or_default()
+            )),
+        }
+    }
+}
+
+/// ImageMagick coder for `source`, picked from its magic bytes rather than the stored content
+/// type so uploads cannot reach script-capable coders such as SVG, MVG, MSL or PostScript.
+fn input_coder(source: &[u8]) -> Option<&'static str> {
+    if source.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("png")
+    } else if source.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("jpeg")
+    } else if source.starts_with(b"GIF87a") || source.starts_with(b"GIF89a") {
+        Some("gif")
+    } else if source.len() >= 12 && source.starts_with(b"RIFF") && &source[8..12] == b"WEBP" {
+        Some("webp")
+    } else {
+        None
+    }
+}
+
+/// Storage id a cache file was rendered from.
+pub fn cached_storage_id(file_name: &str) -> Option<&str> {
+    file_name.rsplit_once('-').map(|(storage_id, _)| storage_id)
+}
+
+/// Returns the transformed image from the disk cache, rendering it on a miss. At most
+/// [`MAX_CONCURRENT_TRANSFORMS`] renders run at once.
+pub async fn transform_image(
+    command: &str,
+    storage_dir: &Path,
+    storage_id: &str,
+    transform: ImageTransform,
+    source: Vec<u8>,
+) -> Result<Vec<u8>, AppError> {
+    if command.trim().is_empty() {
+        return Err(AppError::validation(
+            "image transformations are disabled on this deployment",
+        ));
+    }
+    let coder = input_coder(&source).ok_or_else(|| {
+        AppError::validation("only PNG, JPEG, GIF and WebP images can be transformed")
+    })?;
+    let cache_path = storage_dir
+        .join(CACHE_DIR)
+        .join(transform.cache_file_name(storage_id));
+    if let Ok(bytes) = fs::read(&cache_path).await {
+        return Ok(bytes);
+    }
+
+    let _permit = TRANSFORM_PERMITS
+        .acquire()
+        .await
+        .map_err(|_| AppError::internal("image transformations are unavailable"))?;
+    // A request queued for the same variant may have rendered it while this one waited.
+    if let Ok(bytes) = fs::read(&cache_path).await {
+        return Ok(bytes);
+    }
+    let output = run_command(command, &transform, coder, source).await?;
+    write_cache(&cache_path, &output).await;
+    Ok(output)
+}
+
+async fn run_command(
+    command: &str,
+    transform: &ImageTransform,
+    coder: &str,
+    source: Vec<u8>,
+) -> Result<Vec<u8>, AppError> {
+    let mut process = Command::new(command);
+    for (resource, value) in RESOURCE_LIMITS {
+        process.arg("-limit").arg(resource).arg(value);
+    }
+    // `[0]` keeps only the first frame of animated input.
+    process
+        .arg(format!("{}:-[0]", coder))
+        .arg("-auto-orient")
+        .arg("-strip");
+    if let Some(geometry) = transform.geometry() {
+        process.arg("-resize").arg(geometry);
+    }
+    let mut child = process
+        .arg(format!("{}:-", transform.format.extension()))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|error| {
+            AppError::internal(format!(
+                "failed to start image command '{}': {}",
+                command, error
+            ))
+        })?;
+    // Write on another task so a full stdout pipe cannot deadlock the child.
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| AppError::internal("image command stdin is unavailable"))?;
+    let writer = tokio::spawn(async move { stdin.write_all(&source).await });
+    // Dropping the timed-out future drops the child, which kills it.
+    let output = tokio::time::timeout(TRANSFORM_TIMEOUT, child.wait_with_output()).await;
+    writer.abort();
+    let output = output
+        .map_err(|_| {
+            AppError::validation(format!(
+                "image transformation took longer than {} seconds",
+                TRANSFORM_TIMEOUT.as_secs()
+            ))
+        })?
+        .map_err(|error| {
+            AppError::internal(format!("image command '{}' failed: {}", command, error))
+        })?;
+    if !output.status.success() {
+        return Err(AppError::validation(format!(
+            "image could not be transformed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// Best-effort cache write through a temporary file; failures only cost a re-render.
+async fn write_cache(cache_path: &Path, bytes: &[u8]) {
+    let Some(cache_dir) = cache_path.parent() else {
+        return;
+    };
+    if fs::create_dir_all(cache_dir).await.is_err() {
+        return;
+    }
+    let temp_path = cache_path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+    if fs::write(&temp_path, bytes).await.is_ok()
+        && fs::rename(&temp_path, cache_path).await.is_err()
+    {
+        let _ = fs::remove_file(&temp_path).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cached_storage_id, input_coder, ImageFormat, ImageTransform};
+
+    #[test]
+    fn transforms_are_validated_and_common_sizes_cached() {
+        assert_eq!(
+            ImageTransform::from_query(None, None, None, "image/png").unwrap(),
+            None
+        );
+        let thumbnail = ImageTransform::from_query(Some(256), None, Some("webp"), "image/png")
+            .unwrap()
+            .unwrap();
+        assert_eq!(thumbnail.geometry().as_deref(), Some("256x>"));
+        let cache_file_name = thumbnail.cache_file_name("a-b");
+        assert_eq!(cache_file_name, "a-b-256x0.webp");
+        assert_eq!(cached_storage_id(&cache_file_name), Some("a-b"));
+
+        let boxed = ImageTransform::from_query(Some(512), Some(128), None, "image/jpeg")
+            .unwrap()
+            .unwrap();
+        assert_eq!(boxed.format, ImageFormat::Jpeg);
+        assert_eq!(boxed.cache_file_name("abc"), "abc-512x128.jpeg");
+
+        assert!(ImageTransform::from_query(Some(300), Some(200), None, "image/jpeg").is_err());
+        assert!(ImageTransform::from_query(None, Some(4096), None, "image/png").is_err());
+        assert!(ImageTransform::from_query(Some(0), None, None, "image/png").is_err());
+        assert!(ImageTransform::from_query(Some(128), None, Some("gif"), "image/png").is_err());
+        assert!(ImageTransform::from_query(Some(128), None, None, "application/pdf").is_err());
+    }
+
+    #[test]
+    fn input_coder_follows_magic_bytes_not_content_type() {
+        assert_eq!(input_coder(b"\x89PNG\r\n\x1a\n...."), Some("png"));
+        assert_eq!(input_coder(&[0xFF, 0xD8, 0xFF, 0xE0]), Some("jpeg"));
+        assert_eq!(input_coder(b"GIF89a...."), Some("gif"));
+        assert_eq!(input_coder(b"RIFF\0\0\0\0WEBPVP8 "), Some("webp"));
+        assert_eq!(input_coder(b"<svg width=\"1\"/>"), None);
+        assert_eq!(input_coder(b"%!PS-Adobe-3.0"), None);
+        assert_eq!(input_coder(b"RIFF\0\0\0\0WAVE"), None);
+    }
+}
@@ -2,7 +2,13 @@
 pub mod api_models;
 /// Function loading and execution.
 pub mod functions;
+/// On-the-fly resizing and re-encoding of stored images.
+pub mod image_transform;
 /// Relational repositories.
 pub mod repositories;
 /// Axum route handlers for function APIs.
 pub mod routes;
+/// Expiring HMAC signatures for storage download URLs.
+pub mod storage_signing;
+/// Upload-token expiry and orphan garbage collection for stored files.
+pub mod storage_gc;
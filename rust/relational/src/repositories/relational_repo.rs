@@ -1,4 +1,5 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::OnceLock;
 
 use chrono::NaiveDateTime;
 use regex::Regex;
@@ -33,8 +34,36 @@ pub struct RelationalQueryOptions {
     pub limit: Option<u32>,
     /// Optional offset.
     pub offset: Option<u32>,
+    /// Optional projection; empty returns every field.
+    pub select: Vec<String>,
 }
 
+/// Options for runtime function aggregate reads.
+#[derive(Debug, Clone, Default)]
+pub struct RelationalAggregateOptions {
+    /// Optional filter JSON.
+    pub where_clause: Option<Value>,
+    /// Fields to group by; empty aggregates the whole table into one row.
+    pub group_by: Vec<String>,
+    /// Output name to aggregate spec, e.g. `{"$sum": "amount"}` or `{"$count": "*"}`.
+    pub aggregates: BTreeMap<String, Value>,
+    /// Optional limit on returned groups.
+    pub limit: Option<u32>,
+}
+
+/// Columns stored outside `_payload` on every runtime table.
+const SYSTEM_FIELDS: [&str; 3] = ["_id", "_created_at", "_updated_at"];
+/// Prefix of archived tables; runtime table names must start with a letter, so
+/// functions cannot address them.
+const ARCHIVE_TABLE_PREFIX: &str = "_archive_";
+/// MySQL identifier length limit.
+const MAX_TABLE_NAME_LENGTH: usize = 64;
+/// Rows per multi-row `INSERT`; two placeholders per row keeps statements well under
+/// MySQL's 65,535 placeholder limit.
+const INSERT_MANY_CHUNK_SIZE: usize = 1_000;
+/// Deepest `$and`/`$or` nesting accepted in a where clause.
+const MAX_WHERE_DEPTH: usize = 8;
+
 #[derive(Clone)]
 pub struct RelationalRepository {
     pool: MySqlPool,
@@ -59,13 +88,18 @@ impl RelationalRepository {
     /// Creates the backing table when it does not exist.
     #[instrument(skip(self), fields(table = table_name))]
     pub async fn ensure_table(&self, table_name: &str) -> Result<(), AppError> {
+        let sql = Self::create_table_sql(table_name)?;
+        sqlx::query(&sql).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Returns the DDL `ensure_table` runs for a table.
+    pub fn create_table_sql(table_name: &str) -> Result<String, AppError> {
         validate_table_name(table_name)?;
-        let sql = format!(
+        Ok(format!(
             "CREATE TABLE IF NOT EXISTS `{}` (\n                `_id` VARCHAR(36) NOT NULL PRIMARY KEY,\n                `_created_at` DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),\n                `_updated_at` DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),\n                `_payload` JSON NOT NULL\n            ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci",
             table_name
-        );
-        sqlx::query(&sql).execute(&self.pool).await?;
-        Ok(())
+        ))
     }
 
     /// Inserts one row and returns generated `_id`.
@@ -114,6 +148,104 @@ impl RelationalRepository {
         Ok(row_id)
     }
 
+    /// Merges `changes` into the payload of rows matching `where_clause` inside a
+    /// transaction and returns the number of rows updated.
+    ///
+    /// Changes follow JSON merge-patch rules, so a `null` value removes the field.
+    pub async fn update_in_transaction(
+        &self,
+        transaction: &mut Transaction<'_, MySql>,
+        table_name: &str,
+        where_clause: &Value,
+        changes: &Value,
+    ) -> Result<u64, AppError> {
+        let changes = require_changes_payload(changes)?;
+        let (sql, params) = build_update_sql(table_name, where_clause)?;
+
+        let mut query = sqlx::query(&sql).bind(sqlx::types::Json(changes));
+        for param in &params {
+            query = bind_param(query, param);
+        }
+        let result = query.execute(&mut **transaction).await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Updates the row whose `unique_fields` match `value`, or inserts `value` when no
+    /// row matches, inside a transaction. Returns the row `_id`.
+    ///
+    /// The lookup locks matching rows with `FOR UPDATE`; an existing row is merged
+    /// with `value` the same way `update_in_transaction` merges changes.
+    pub async fn upsert_in_transaction(
+        &self,
+        transaction: &mut Transaction<'_, MySql>,
+        table_name: &str,
+        unique_fields: &[String],
+        value: &Value,
+    ) -> Result<String, AppError> {
+        let payload = require_changes_payload(value)?;
+        let (sql, params) = build_unique_lookup_sql(table_name, unique_fields, &payload)?;
+
+        let mut query = sqlx::query_scalar::<_, String>(&sql);
+        for param in &params {
+            query = match param {
+                BoundParam::String(value) => query.bind(value.clone()),
+                BoundParam::U32(value) => query.bind(*value),
+            };
+        }
+        let existing = query.fetch_optional(&mut **transaction).await?;
+
+        match existing {
+            Some(row_id) => {
+                let where_clause = serde_json::json!({ "_id": row_id });
+                self.update_in_transaction(transaction, table_name, &where_clause, &payload)
+                    .await?;
+                Ok(row_id)
+            }
+            None => {
+                self.insert_in_transaction(transaction, table_name, &payload)
+                    .await
+            }
+        }
+    }
+
+    /// Inserts every row inside a transaction and returns the generated `_id`s in input
+    /// order. All rows are validated before anything is written.
+    pub async fn insert_many_in_transaction(
+        &self,
+        transaction: &mut Transaction<'_, MySql>,
+        table_name: &str,
+        values: &[Value],
+    ) -> Result<Vec<String>, AppError> {
+        validate_table_name(table_name)?;
+        let payloads = values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                require_object_payload(value).map_err(|_| {
+                    AppError::validation(format!("insert row {} must be a JSON object", index))
+                })
+            })
+            .collect::<Result<Vec<Value>, AppError>>()?;
+        let row_ids = payloads
+            .iter()
+            .map(|_| Uuid::new_v4().to_string())
+            .collect::<Vec<String>>();
+
+        for (ids, payloads) in row_ids
+            .chunks(INSERT_MANY_CHUNK_SIZE)
+            .zip(payloads.chunks(INSERT_MANY_CHUNK_SIZE))
+        {
+            let sql = insert_many_sql(table_name, ids.len());
+            let mut query = sqlx::query(&sql);
+            for (id, payload) in ids.iter().zip(payloads) {
+                query = query.bind(id).bind(sqlx::types::Json(payload));
+            }
+            query.execute(&mut **transaction).await?;
+        }
+
+        Ok(row_ids)
+    }
+
     /// Queries rows from a table using supported filters, sorting, and paging.
     #[instrument(skip(self, options), fields(table = table_name))]
     pub async fn query(
@@ -129,7 +261,7 @@ impl RelationalRepository {
             query = bind_param(query, param);
         }
         let rows = query.fetch_all(&self.pool).await?;
-        map_rows(rows)
+        map_rows(rows, &options.select)
     }
 
     /// Queries rows inside an existing transaction.
@@ -147,7 +279,7 @@ impl RelationalRepository {
             query = bind_param(query, param);
         }
         let rows = query.fetch_all(&mut **transaction).await?;
-        map_rows(rows)
+        map_rows(rows, &options.select)
     }
 
     /// Returns first row from a query.
@@ -174,10 +306,184 @@ impl RelationalRepository {
             .await?;
         Ok(rows.pop())
     }
+
+    /// Computes aggregates inside a transaction; returns one row per group with the
+    /// group fields and every aggregate output.
+    pub async fn aggregate_in_transaction(
+        &self,
+        transaction: &mut Transaction<'_, MySql>,
+        table_name: &str,
+        options: RelationalAggregateOptions,
+    ) -> Result<Vec<Value>, AppError> {
+        let (sql, params) = build_aggregate_sql(table_name, &options, self.max_query_limit, 100)?;
+
+        let mut query = sqlx::query_scalar::<_, sqlx::types::Json<Value>>(&sql);
+        for param in &params {
+            query = match param {
+                BoundParam::String(value) => query.bind(value.clone()),
+                BoundParam::U32(value) => query.bind(*value),
+            };
+        }
+        let rows = query.fetch_all(&mut **transaction).await?;
+        Ok(rows.into_iter().map(|row| row.0).collect())
+    }
+
+    /// Renames a table into the archive namespace and marks it inactive in
+    /// `_mesosphere_schema_meta`, keeping every row. Returns the archive table name.
+    #[instrument(skip(self), fields(table = table_name))]
+    pub async fn archive_table(&self, table_name: &str) -> Result<String, AppError> {
+        let archive_name = archive_table_name(table_name)?;
+        if !self.table_exists(table_name).await? {
+            return Err(AppError::not_found(format!(
+                "table '{}' not found",
+                table_name
+            )));
+        }
+        if self.table_exists(&archive_name).await? {
+            return Err(AppError::validation(format!(
+                "table '{}' already has an archived copy; restore it before archiving again",
+                table_name
+            )));
+        }
+
+        // RENAME TABLE commits implicitly, so the archive is recorded before it runs and the
+        // record is reverted when it fails. restore_table also finds the copy by name.
+        sqlx::query(
+            r#"
+            INSERT INTO _mesosphere_schema_meta (table_name, signature, active, archived_as, archived_at)
+            VALUES (?, '', FALSE, ?, UTC_TIMESTAMP(6))
+            ON DUPLICATE KEY UPDATE
+                active = FALSE,
+                archived_as = VALUES(archived_as),
+                archived_at = VALUES(archived_at)
+            "#,
+        )
+        .bind(table_name)
+        .bind(&archive_name)
+        .execute(&self.pool)
+        .await?;
+        let renamed = sqlx::query(&format!(
+            "RENAME TABLE `{}` TO `{}`",
+            table_name, archive_name
+        ))
+        .execute(&self.pool)
+        .await;
+        if let Err(error) = renamed {
+            self.mark_active(table_name).await?;
+            return Err(error.into());
+        }
+        Ok(archive_name)
+    }
+
+    /// Moves an archived table back under its original name and marks it active.
+    ///
+    /// The archive copy is located by its `_archive_` name, so a table whose archive record
+    /// is missing can still be restored, and an archive interrupted before its rename only
+    /// needs its flag cleared.
+    #[instrument(skip(self), fields(table = table_name))]
+    pub async fn restore_table(&self, table_name: &str) -> Result<(), AppError> {
+        let archive_name = archive_table_name(table_name)?;
+        let has_archive = self.table_exists(&archive_name).await?;
+        let has_live = self.table_exists(table_name).await?;
+        match (has_archive, has_live) {
+            (true, true) => {
+                return Err(AppError::validation(format!(
+                    "table '{}' was recreated after archiving; rename or drop it before restoring",
+                    table_name
+                )));
+            }
+            (true, false) => {
+                sqlx::query(&format!(
+                    "RENAME TABLE `{}` TO `{}`",
+                    archive_name, table_name
+                ))
+                .execute(&self.pool)
+                .await?;
+            }
+            (false, true) if self.is_archived(table_name).await? => {}
+            (false, _) => {
+                return Err(AppError::not_found(format!(
+                    "table '{}' is not archived",
+                    table_name
+                )));
+            }
+        }
+        self.mark_active(table_name).await
+    }
+
+    /// Clears the archive record of a table.
+    async fn mark_active(&self, table_name: &str) -> Result<(), AppError> {
+        sqlx::query(
+            r#"
+            UPDATE _mesosphere_schema_meta
+            SET active = TRUE, archived_as = NULL, archived_at = NULL
+            WHERE table_name = ?
+            "#,
+        )
+        .bind(table_name)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the names of all archived tables.
+    pub async fn archived_tables(&self) -> Result<BTreeSet<String>, AppError> {
+        let tables = sqlx::query_scalar::<_, String>(
+            "SELECT table_name FROM _mesosphere_schema_meta WHERE active = FALSE",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(tables.into_iter().collect())
+    }
+
+    /// Returns whether the table is currently archived.
+    pub async fn is_archived(&self, table_name: &str) -> Result<bool, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(1)
+            FROM _mesosphere_schema_meta
+            WHERE table_name = ? AND active = FALSE
+            "#,
+        )
+        .bind(table_name)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count > 0)
+    }
+
+    /// Returns whether a table exists in the current database.
+    pub async fn table_exists(&self, table_name: &str) -> Result<bool, AppError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(1)
+            FROM information_schema.tables
+            WHERE table_schema = DATABASE() AND table_name = ?
+            "#,
+        )
+        .bind(table_name)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count > 0)
+    }
+}
+
+fn archive_table_name(table_name: &str) -> Result<String, AppError> {
+    validate_table_name(table_name)?;
+    let archive_name = format!("{}{}", ARCHIVE_TABLE_PREFIX, table_name);
+    if archive_name.len() > MAX_TABLE_NAME_LENGTH {
+        return Err(AppError::validation(format!(
+            "table name '{}' is too long to archive",
+            table_name
+        )));
+    }
+    Ok(archive_name)
 }
 
 fn validate_table_name(table_name: &str) -> Result<(), AppError> {
-    let regex = Regex::new(r"^[a-zA-Z][a-zA-Z0-9_]*$")
+    static TABLE_NAME_REGEX: OnceLock<Result<Regex, regex::Error>> = OnceLock::new();
+    let regex = TABLE_NAME_REGEX
+        .get_or_init(|| Regex::new(r"^[a-zA-Z][a-zA-Z0-9_]*$"))
+        .as_ref()
         .map_err(|error| AppError::internal(format!("failed to build table regex: {}", error)))?;
     if !regex.is_match(table_name) {
         return Err(AppError::validation(format!(
@@ -195,6 +501,89 @@ fn require_object_payload(value: &Value) -> Result<Value, AppError> {
     Err(AppError::validation("insert payload must be a JSON object"))
 }
 
+fn insert_many_sql(table_name: &str, rows: usize) -> String {
+    format!(
+        "INSERT INTO `{}` (`_id`, `_payload`) VALUES {}",
+        table_name,
+        vec!["(?, ?)"; rows].join(", ")
+    )
+}
+
+/// Validates an update or upsert payload: a JSON object without system fields.
+fn require_changes_payload(value: &Value) -> Result<Value, AppError> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| AppError::validation("update payload must be a JSON object"))?;
+    for field in object.keys() {
+        validate_field_name(field)?;
+        if SYSTEM_FIELDS.contains(&field.as_str()) {
+            return Err(AppError::validation(format!(
+                "system field '{}' cannot be updated",
+                field
+            )));
+        }
+    }
+    Ok(value.clone())
+}
+
+/// Builds the merge-patch `UPDATE`; the first placeholder is the changes document.
+fn build_update_sql(
+    table_name: &str,
+    where_clause: &Value,
+) -> Result<(String, Vec<BoundParam>), AppError> {
+    validate_table_name(table_name)?;
+    let (where_sql, params) = compile_where_clause(where_clause)?;
+    Ok((
+        format!(
+            "UPDATE `{}` SET `_payload` = JSON_MERGE_PATCH(`_payload`, ?) WHERE {}",
+            table_name, where_sql
+        ),
+        params,
+    ))
+}
+
+/// Builds the locking lookup an upsert uses to find the row matching the unique fields.
+fn build_unique_lookup_sql(
+    table_name: &str,
+    unique_fields: &[String],
+    payload: &Value,
+) -> Result<(String, Vec<BoundParam>), AppError> {
+    validate_table_name(table_name)?;
+    if unique_fields.is_empty() {
+        return Err(AppError::validation(
+            "upsert requires at least one unique field",
+        ));
+    }
+
+    let mut conditions = Vec::<String>::new();
+    let mut params = Vec::<BoundParam>::new();
+    for field in unique_fields {
+        validate_field_name(field)?;
+        let value = payload
+            .get(field)
+            .filter(|value| !value.is_null())
+            .ok_or_else(|| {
+                AppError::validation(format!(
+                    "upsert payload is missing unique field '{}'",
+                    field
+                ))
+            })?;
+        conditions.push(format!(
+            "JSON_EXTRACT(`_payload`, '$.{}') = CAST(? AS JSON)",
+            field
+        ));
+        params.push(BoundParam::String(value.to_string()));
+    }
+    Ok((
+        format!(
+            "SELECT `_id` FROM `{}` WHERE {} LIMIT 1 FOR UPDATE",
+            table_name,
+            conditions.join(" AND ")
+        ),
+        params,
+    ))
+}
+
 fn build_query_sql(
     table_name: &str,
     options: &RelationalQueryOptions,
@@ -204,7 +593,8 @@ fn build_query_sql(
     validate_table_name(table_name)?;
 
     let mut sql = format!(
-        "SELECT `_id`, `_created_at`, `_updated_at`, `_payload` FROM `{}`",
+        "SELECT `_id`, `_created_at`, `_updated_at`, {} FROM `{}`",
+        compile_select(&options.select)?,
         table_name
     );
     let mut params = Vec::<BoundParam>::new();
@@ -234,37 +624,375 @@ fn build_query_sql(
     Ok((sql, params))
 }
 
+/// Builds an aggregate query that returns each group as one JSON object column.
+///
+/// Payload fields are summed, averaged and compared as numbers; `_created_at` and
+/// `_updated_at` keep their column type, so `$min`/`$max` return timestamps.
+fn build_aggregate_sql(
+    table_name: &str,
+    options: &RelationalAggregateOptions,
+    max_query_limit: u32,
+    default_limit: u32,
+) -> Result<(String, Vec<BoundParam>), AppError> {
+    validate_table_name(table_name)?;
+    if options.aggregates.is_empty() {
+        return Err(AppError::validation(
+            "aggregate requires at least one aggregate output",
+        ));
+    }
+
+    let mut pairs = Vec::<String>::new();
+    let mut group_columns = Vec::<String>::new();
+    for field in &options.group_by {
+        validate_field_name(field)?;
+        let column = field_column(field);
+        pairs.push(format!("'{}', {}", field, column));
+        group_columns.push(column);
+    }
+    for (name, spec) in &options.aggregates {
+        validate_field_name(name)?;
+        if options.group_by.contains(name) {
+            return Err(AppError::validation(format!(
+                "aggregate output '{}' collides with a group-by field",
+                name
+            )));
+        }
+        pairs.push(format!("'{}', {}", name, compile_aggregate(name, spec)?));
+    }
+
+    let mut sql = format!(
+        "SELECT JSON_OBJECT({}) AS `_row` FROM `{}`",
+        pairs.join(", "),
+        table_name
+    );
+    let mut params = Vec::<BoundParam>::new();
+    if let Some(where_clause) = &options.where_clause {
+        let (where_sql, mut where_params) = compile_where_clause(where_clause)?;
+        sql.push_str(" WHERE ");
+        sql.push_str(&where_sql);
+        params.append(&mut where_params);
+    }
+    if !group_columns.is_empty() {
+        let grouped = group_columns.join(", ");
+        sql.push_str(&format!(" GROUP BY {} ORDER BY {}", grouped, grouped));
+    }
+
+    let limit = options.limit.unwrap_or(default_limit).min(max_query_limit);
+    sql.push_str(" LIMIT ?");
+    params.push(BoundParam::U32(limit));
+    Ok((sql, params))
+}
+
+/// Compiles one `{"$op": field}` aggregate spec.
+fn compile_aggregate(name: &str, spec: &Value) -> Result<String, AppError> {
+    let invalid = || {
+        AppError::validation(format!(
+            "aggregate '{}' must be one of {{\"$count\": \"*\"|field}}, \
+             {{\"$sum\"|\"$avg\"|\"$min\"|\"$max\": field}}",
+            name
+        ))
+    };
+    let (operator, field) = spec
+        .as_object()
+        .filter(|object| object.len() == 1)
+        .and_then(|object| object.iter().next())
+        .ok_or_else(invalid)?;
+    let field = field.as_str().ok_or_else(invalid)?;
+    if operator == "$count" && field == "*" {
+        return Ok("COUNT(*)".to_string());
+    }
+    validate_field_name(field)?;
+    let column = field_column(field);
+    let numeric = if SYSTEM_FIELDS.contains(&field) {
+        column.clone()
+    } else {
+        format!("CAST({} AS DOUBLE)", column)
+    };
+    match operator.as_str() {
+        "$count" => Ok(format!("COUNT({})", column)),
+        "$sum" => Ok(format!("SUM({})", numeric)),
+        "$avg" => Ok(format!("AVG({})", numeric)),
+        "$min" => Ok(format!("MIN({})", numeric)),
+        "$max" => Ok(format!("MAX({})", numeric)),
+        _ => Err(invalid()),
+    }
+}
+
+/// SQL expression reading a system column or payload field.
+fn field_column(field: &str) -> String {
+    if SYSTEM_FIELDS.contains(&field) {
+        format!("`{}`", field)
+    } else {
+        format!("JSON_EXTRACT(`_payload`, '$.{}')", field)
+    }
+}
+
+/// Builds the `_payload` column expression, narrowed to the selected payload fields.
+///
+/// Field names are restricted to identifiers, so they are inlined as JSON keys and paths.
+/// Selected fields missing from a row come back as `null`.
+fn compile_select(select: &[String]) -> Result<String, AppError> {
+    if select.is_empty() {
+        return Ok("`_payload`".to_string());
+    }
+
+    let mut pairs = Vec::<String>::new();
+    for field in select {
+        validate_field_name(field)?;
+        if SYSTEM_FIELDS.contains(&field.as_str()) {
+            continue;
+        }
+        pairs.push(format!(
+            "'{}', JSON_EXTRACT(`_payload`, '$.{}')",
+            field, field
+        ));
+    }
+    Ok(format!("JSON_OBJECT({}) AS `_payload`", pairs.join(", ")))
+}
+
+fn validate_field_name(field: &str) -> Result<(), AppError> {
+    static FIELD_NAME_REGEX: OnceLock<Result<Regex, regex::Error>> = OnceLock::new();
+    let regex = FIELD_NAME_REGEX
+        .get_or_init(|| Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$"))
+        .as_ref()
+        .map_err(|error| AppError::internal(format!("failed to build field regex: {}", error)))?;
+    if !regex.is_match(field) {
+        return Err(AppError::validation(format!(
+            "invalid field name '{}'",
+            field
+        )));
+    }
+    Ok(())
+}
+
+/// Compiles a runtime where clause into SQL.
+///
+/// Each key is a field (system column or payload field) matched against a value or an
+/// operator object (`$eq`, `$ne`, `$gt`, `$gte`, `$lt`, `$lte`, `$in`, `$nin`, `$like`,
+/// `$between`, `$is_null`); keys are ANDed, `$and`/`$or` take arrays of nested clauses and
+/// `$not` takes one nested clause, up to `MAX_WHERE_DEPTH` levels deep.
 fn compile_where_clause(where_clause: &Value) -> Result<(String, Vec<BoundParam>), AppError> {
     let object = where_clause
         .as_object()
         .ok_or_else(|| AppError::validation("where clause must be an object"))?;
+    let mut params = Vec::<BoundParam>::new();
+    let sql = compile_where_object(object, 0, &mut params)?;
+    Ok((sql, params))
+}
 
-    if object.len() != 1 || !object.contains_key("_id") {
-        return Err(AppError::validation(
-            "only '_id' filters are supported in runtime function where clauses",
-        ));
+fn compile_where_object(
+    object: &Map<String, Value>,
+    depth: usize,
+    params: &mut Vec<BoundParam>,
+) -> Result<String, AppError> {
+    if depth > MAX_WHERE_DEPTH {
+        return Err(AppError::validation(format!(
+            "where clause nesting exceeds the maximum depth of {}",
+            MAX_WHERE_DEPTH
+        )));
+    }
+    if object.is_empty() {
+        return Ok("1 = 1".to_string());
     }
 
-    let id_selector = object
-        .get("_id")
-        .ok_or_else(|| AppError::validation("missing _id selector"))?;
-    let id = parse_id_selector(id_selector)?;
-    Ok(("`_id` = ?".to_string(), vec![BoundParam::String(id)]))
+    let mut clauses = Vec::<String>::new();
+    for (key, selector) in object {
+        if key == "$and" || key == "$or" {
+            let members = selector.as_array().ok_or_else(|| {
+                AppError::validation(format!("'{}' clause must be an array of objects", key))
+            })?;
+            if members.is_empty() {
+                clauses.push(if key == "$and" { "1 = 1" } else { "1 = 0" }.to_string());
+                continue;
+            }
+            let mut compiled = Vec::<String>::with_capacity(members.len());
+            for member in members {
+                let member = member.as_object().ok_or_else(|| {
+                    AppError::validation(format!("'{}' clause must be an array of objects", key))
+                })?;
+                compiled.push(format!(
+                    "({})",
+                    compile_where_object(member, depth + 1, params)?
+                ));
+            }
+            let joiner = if key == "$and" { " AND " } else { " OR " };
+            clauses.push(format!("({})", compiled.join(joiner)));
+            continue;
+        }
+        if key == "$not" {
+            let inner = selector
+                .as_object()
+                .ok_or_else(|| AppError::validation("'$not' clause must be an object"))?;
+            clauses.push(format!(
+                "NOT ({})",
+                compile_where_object(inner, depth + 1, params)?
+            ));
+            continue;
+        }
+
+        let target = WhereTarget::new(key)?;
+        match selector {
+            Value::Object(operators) => {
+                if operators.is_empty() {
+                    return Err(AppError::validation(format!(
+                        "filter for '{}' cannot be an empty object",
+                        key
+                    )));
+                }
+                for (operator, operand) in operators {
+                    clauses.push(target.compile(operator, operand, params)?);
+                }
+            }
+            _ => clauses.push(target.compile("$eq", selector, params)?),
+        }
+    }
+    Ok(clauses.join(" AND "))
 }
 
-fn parse_id_selector(selector: &Value) -> Result<String, AppError> {
-    if let Some(id) = selector.as_str() {
-        return Ok(id.to_string());
+/// Field a where clause filters on: a system column compared as text, or a payload
+/// field compared as JSON.
+struct WhereTarget<'a> {
+    field: &'a str,
+    column: String,
+    system: bool,
+}
+
+impl<'a> WhereTarget<'a> {
+    fn new(field: &'a str) -> Result<Self, AppError> {
+        validate_field_name(field)?;
+        Ok(Self {
+            field,
+            column: field_column(field),
+            system: SYSTEM_FIELDS.contains(&field),
+        })
     }
 
-    let map = selector
-        .as_object()
-        .ok_or_else(|| AppError::validation("_id filter must be a string or {_id: {$eq: ...}}"))?;
-    let id = map
-        .get("$eq")
-        .and_then(Value::as_str)
-        .ok_or_else(|| AppError::validation("_id filter supports only '$eq' string operator"))?;
-    Ok(id.to_string())
+    fn compile(
+        &self,
+        operator: &str,
+        operand: &Value,
+        params: &mut Vec<BoundParam>,
+    ) -> Result<String, AppError> {
+        let column = &self.column;
+        match operator {
+            "$eq" | "$gt" | "$gte" | "$lt" | "$lte" => {
+                let sql_operator = match operator {
+                    "$eq" => "=",
+                    "$gt" => ">",
+                    "$gte" => ">=",
+                    "$lt" => "<",
+                    _ => "<=",
+                };
+                let placeholder = self.bind(operand, params)?;
+                Ok(format!("{} {} {}", column, sql_operator, placeholder))
+            }
+            "$ne" => {
+                let placeholder = self.bind(operand, params)?;
+                Ok(format!("NOT ({} <=> {})", column, placeholder))
+            }
+            "$in" | "$nin" => {
+                let values = operand.as_array().ok_or_else(|| {
+                    AppError::validation(format!(
+                        "'{}' filter for '{}' must be an array",
+                        operator, self.field
+                    ))
+                })?;
+                let negated = operator == "$nin";
+                if values.is_empty() {
+                    return Ok(if negated { "1 = 1" } else { "1 = 0" }.to_string());
+                }
+                // MySQL does not support IN() over JSON values, so members are ORed.
+                let mut alternatives = Vec::<String>::with_capacity(values.len());
+                for value in values {
+                    let placeholder = self.bind(value, params)?;
+                    alternatives.push(format!("{} <=> {}", column, placeholder));
+                }
+                let joined = alternatives.join(" OR ");
+                Ok(if negated {
+                    format!("NOT ({})", joined)
+                } else {
+                    format!("({})", joined)
+                })
+            }
+            "$like" => {
+                let pattern = operand.as_str().ok_or_else(|| {
+                    AppError::validation(format!(
+                        "'$like' filter for '{}' must be a string pattern",
+                        self.field
+                    ))
+                })?;
+                params.push(BoundParam::String(pattern.to_string()));
+                if self.system {
+                    Ok(format!("{} LIKE ?", column))
+                } else {
+                    Ok(format!("JSON_UNQUOTE({}) LIKE ?", column))
+                }
+            }
+            "$between" => {
+                let bounds = operand
+                    .as_array()
+                    .filter(|bounds| bounds.len() == 2)
+                    .ok_or_else(|| {
+                        AppError::validation(format!(
+                            "'$between' filter for '{}' must be a [low, high] array",
+                            self.field
+                        ))
+                    })?;
+                let low = self.bind(&bounds[0], params)?;
+                let high = self.bind(&bounds[1], params)?;
+                Ok(format!(
+                    "({} >= {} AND {} <= {})",
+                    column, low, column, high
+                ))
+            }
+            "$is_null" => {
+                let is_null = operand.as_bool().ok_or_else(|| {
+                    AppError::validation(format!(
+                        "'$is_null' filter for '{}' must be true or false",
+                        self.field
+                    ))
+                })?;
+                if self.system {
+                    let test = if is_null { "IS NULL" } else { "IS NOT NULL" };
+                    return Ok(format!("{} {}", column, test));
+                }
+                // A payload field is null when it is missing or holds a JSON null.
+                let null_check = format!("({} IS NULL OR JSON_TYPE({}) = 'NULL')", column, column);
+                Ok(if is_null {
+                    null_check
+                } else {
+                    format!("NOT {}", null_check)
+                })
+            }
+            _ => Err(AppError::validation(format!(
+                "unsupported where operator '{}' for '{}'",
+                operator, self.field
+            ))),
+        }
+    }
+
+    /// Binds one operand and returns its placeholder.
+    fn bind(
+        &self,
+        operand: &Value,
+        params: &mut Vec<BoundParam>,
+    ) -> Result<&'static str, AppError> {
+        if self.system {
+            let text = operand.as_str().ok_or_else(|| {
+                AppError::validation(format!("filters on '{}' require string values", self.field))
+            })?;
+            params.push(BoundParam::String(text.to_string()));
+            return Ok("?");
+        }
+        if operand.is_object() || operand.is_array() {
+            return Err(AppError::validation(format!(
+                "filter values for '{}' must be scalars",
+                self.field
+            )));
+        }
+        params.push(BoundParam::String(operand.to_string()));
+        Ok("CAST(? AS JSON)")
+    }
 }
 
 fn compile_order_by(order_by: &[OrderByClause]) -> Result<String, AppError> {
@@ -272,12 +1000,8 @@ fn compile_order_by(order_by: &[OrderByClause]) -> Result<String, AppError> {
 
     for entry in order_by {
         let field = entry.field.as_str();
-        if field != "_id" && field != "_created_at" && field != "_updated_at" {
-            return Err(AppError::validation(format!(
-                "unsupported orderBy field '{}'",
-                field
-            )));
-        }
+        validate_field_name(field)
+            .map_err(|_| AppError::validation(format!("unsupported orderBy field '{}'", field)))?;
 
         let direction = entry
             .direction
@@ -295,7 +1019,7 @@ fn compile_order_by(order_by: &[OrderByClause]) -> Result<String, AppError> {
             }
         };
 
-        parts.push(format!("`{}` {}", field, sql_direction));
+        parts.push(format!("{} {}", field_column(field), sql_direction));
     }
 
     Ok(parts.join(", "))
@@ -311,8 +1035,20 @@ fn bind_param<'q>(
     }
 }
 
-fn map_rows(rows: Vec<MySqlRow>) -> Result<Vec<Value>, AppError> {
-    rows.into_iter().map(map_row).collect()
+fn map_rows(rows: Vec<MySqlRow>, select: &[String]) -> Result<Vec<Value>, AppError> {
+    rows.into_iter()
+        .map(|row| {
+            let mut value = map_row(row)?;
+            if !select.is_empty() {
+                if let Value::Object(object) = &mut value {
+                    object.retain(|key, _| {
+                        !SYSTEM_FIELDS.contains(&key.as_str()) || select.contains(key)
+                    });
+                }
+            }
+            Ok(value)
+        })
+        .collect()
 }
 
 fn map_row(row: MySqlRow) -> Result<Value, AppError> {
@@ -340,3 +1076,200 @@ fn map_row(row: MySqlRow) -> Result<Value, AppError> {
 
     Ok(Value::Object(output.into_iter().collect()))
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{archive_table_name, build_query_sql, OrderByClause, RelationalQueryOptions};
+    use super::{build_aggregate_sql, insert_many_sql, RelationalAggregateOptions};
+    use super::{build_unique_lookup_sql, build_update_sql, require_changes_payload};
+    use super::{compile_where_clause, MAX_WHERE_DEPTH};
+
+    #[test]
+    fn select_projects_payload_fields_into_json_object() {
+        let options = RelationalQueryOptions {
+            select: vec!["_id".to_string(), "title".to_string()],
+            ..RelationalQueryOptions::default()
+        };
+        let (sql, _) = build_query_sql("posts", &options, 500, 100).expect("sql");
+        assert_eq!(
+            sql,
+            "SELECT `_id`, `_created_at`, `_updated_at`, \
+             JSON_OBJECT('title', JSON_EXTRACT(`_payload`, '$.title')) AS `_payload` \
+             FROM `posts` LIMIT ?"
+        );
+
+        let invalid = RelationalQueryOptions {
+            select: vec!["title'), x".to_string()],
+            ..RelationalQueryOptions::default()
+        };
+        assert!(build_query_sql("posts", &invalid, 500, 100).is_err());
+    }
+
+    #[test]
+    fn archive_names_use_prefix_and_respect_identifier_limit() {
+        assert_eq!(archive_table_name("posts").unwrap(), "_archive_posts");
+        assert!(archive_table_name("_archive_posts").is_err());
+        assert!(archive_table_name(&"a".repeat(56)).is_err());
+    }
+
+    #[test]
+    fn update_and_upsert_sql_validate_fields() {
+        let (sql, params) = build_update_sql("posts", &json!({ "_id": "abc" })).expect("sql");
+        assert_eq!(
+            sql,
+            "UPDATE `posts` SET `_payload` = JSON_MERGE_PATCH(`_payload`, ?) WHERE `_id` = ?"
+        );
+        assert_eq!(params.len(), 1);
+        assert!(build_update_sql("posts", &json!({ "title": { "$regex": "x" } })).is_err());
+
+        let payload = json!({ "slug": "hello", "team": 3, "title": "Hello" });
+        let unique = vec!["slug".to_string(), "team".to_string()];
+        let (sql, params) = build_unique_lookup_sql("posts", &unique, &payload).expect("sql");
+        assert_eq!(
+            sql,
+            "SELECT `_id` FROM `posts` WHERE \
+             JSON_EXTRACT(`_payload`, '$.slug') = CAST(? AS JSON) AND \
+             JSON_EXTRACT(`_payload`, '$.team') = CAST(? AS JSON) LIMIT 1 FOR UPDATE"
+        );
+        assert_eq!(params.len(), 2);
+        assert!(build_unique_lookup_sql("posts", &[], &payload).is_err());
+        assert!(build_unique_lookup_sql("posts", &["author".to_string()], &payload).is_err());
+
+        assert!(require_changes_payload(&json!({ "title": "x" })).is_ok());
+        assert!(require_changes_payload(&json!({ "_id": "x" })).is_err());
+        assert!(require_changes_payload(&json!(["title"])).is_err());
+    }
+
+    #[test]
+    fn where_clause_compiles_operators_and_payload_ordering() {
+        let options = RelationalQueryOptions {
+            where_clause: Some(json!({
+                "age": { "$gt": 30, "$lte": 65 },
+                "$or": [{ "name": { "$like": "A%" } }, { "tags": { "$in": ["x", "y"] } }],
+                "_created_at": { "$between": ["2024-01-01", "2025-01-01"] }
+            })),
+            order_by: vec![OrderByClause {
+                field: "age".to_string(),
+                direction: Some("desc".to_string()),
+            }],
+            limit: Some(50),
+            offset: Some(100),
+            ..RelationalQueryOptions::default()
+        };
+        let (sql, params) = build_query_sql("people", &options, 500, 100).expect("sql");
+        assert_eq!(
+            sql,
+            "SELECT `_id`, `_created_at`, `_updated_at`, `_payload` FROM `people` WHERE \
+             ((JSON_UNQUOTE(JSON_EXTRACT(`_payload`, '$.name')) LIKE ?) OR \
+             ((JSON_EXTRACT(`_payload`, '$.tags') <=> CAST(? AS JSON) OR \
+             JSON_EXTRACT(`_payload`, '$.tags') <=> CAST(? AS JSON)))) AND \
+             (`_created_at` >= ? AND `_created_at` <= ?) AND \
+             JSON_EXTRACT(`_payload`, '$.age') > CAST(? AS JSON) AND \
+             JSON_EXTRACT(`_payload`, '$.age') <= CAST(? AS JSON) \
+             ORDER BY JSON_EXTRACT(`_payload`, '$.age') DESC LIMIT ? OFFSET ?"
+        );
+        assert_eq!(params.len(), 9);
+
+        for invalid in [
+            json!({ "age": { "$regex": "x" } }),
+            json!({ "_id": 5 }),
+            json!({ "age": { "$between": [1] } }),
+            json!({ "bad-field": 1 }),
+            json!({ "age": { "$is_null": "yes" } }),
+            json!({ "$not": [{ "age": 1 }] }),
+        ] {
+            let options = RelationalQueryOptions {
+                where_clause: Some(invalid),
+                ..RelationalQueryOptions::default()
+            };
+            assert!(build_query_sql("people", &options, 500, 100).is_err());
+        }
+    }
+
+    #[test]
+    fn where_clause_compiles_not_and_is_null_with_a_depth_limit() {
+        let (sql, params) = compile_where_clause(&json!({
+            "$not": { "status": { "$in": ["void", "draft"] } },
+            "deleted_at": { "$is_null": true },
+            "owner": { "$is_null": false },
+            "_updated_at": { "$is_null": false }
+        }))
+        .expect("where clause should compile");
+        assert_eq!(
+            sql,
+            "NOT ((JSON_EXTRACT(`_payload`, '$.status') <=> CAST(? AS JSON) OR \
+             JSON_EXTRACT(`_payload`, '$.status') <=> CAST(? AS JSON))) AND \
+             `_updated_at` IS NOT NULL AND \
+             (JSON_EXTRACT(`_payload`, '$.deleted_at') IS NULL OR \
+             JSON_TYPE(JSON_EXTRACT(`_payload`, '$.deleted_at')) = 'NULL') AND \
+             NOT (JSON_EXTRACT(`_payload`, '$.owner') IS NULL OR \
+             JSON_TYPE(JSON_EXTRACT(`_payload`, '$.owner')) = 'NULL')"
+        );
+        assert_eq!(params.len(), 2);
+
+        let mut nested = json!({ "age": 1 });
+        for _ in 0..MAX_WHERE_DEPTH {
+            nested = json!({ "$not": nested });
+        }
+        assert!(compile_where_clause(&nested).is_ok());
+        assert!(compile_where_clause(&json!({ "$not": nested })).is_err());
+    }
+
+    #[test]
+    fn aggregate_sql_groups_and_validates_specs() {
+        let options = RelationalAggregateOptions {
+            where_clause: Some(json!({ "status": { "$ne": "void" } })),
+            group_by: vec!["status".to_string()],
+            aggregates: [
+                ("orders".to_string(), json!({ "$count": "*" })),
+                ("revenue".to_string(), json!({ "$sum": "amount" })),
+                ("first".to_string(), json!({ "$min": "_created_at" })),
+            ]
+            .into_iter()
+            .collect(),
+            limit: None,
+        };
+        let (sql, params) = build_aggregate_sql("orders", &options, 500, 100).expect("sql");
+        assert_eq!(
+            sql,
+            "SELECT JSON_OBJECT('status', JSON_EXTRACT(`_payload`, '$.status'), \
+             'first', MIN(`_created_at`), 'orders', COUNT(*), \
+             'revenue', SUM(CAST(JSON_EXTRACT(`_payload`, '$.amount') AS DOUBLE))) AS `_row` \
+             FROM `orders` WHERE NOT (JSON_EXTRACT(`_payload`, '$.status') <=> CAST(? AS JSON)) \
+             GROUP BY JSON_EXTRACT(`_payload`, '$.status') \
+             ORDER BY JSON_EXTRACT(`_payload`, '$.status') LIMIT ?"
+        );
+        assert_eq!(params.len(), 2);
+
+        for spec in [
+            json!({ "$median": "amount" }),
+            json!({ "$sum": "*" }),
+            json!({ "$sum": "amount", "$avg": "amount" }),
+            json!("count"),
+        ] {
+            let options = RelationalAggregateOptions {
+                aggregates: [("value".to_string(), spec)].into_iter().collect(),
+                ..RelationalAggregateOptions::default()
+            };
+            assert!(build_aggregate_sql("orders", &options, 500, 100).is_err());
+        }
+        let collision = RelationalAggregateOptions {
+            group_by: vec!["status".to_string()],
+            aggregates: [("status".to_string(), json!({ "$count": "*" }))]
+                .into_iter()
+                .collect(),
+            ..RelationalAggregateOptions::default()
+        };
+        assert!(build_aggregate_sql("orders", &collision, 500, 100).is_err());
+    }
+
+    #[test]
+    fn insert_many_sql_binds_two_placeholders_per_row() {
+        assert_eq!(
+            insert_many_sql("posts", 3),
+            "INSERT INTO `posts` (`_id`, `_payload`) VALUES (?, ?), (?, ?), (?, ?)"
+        );
+    }
+}
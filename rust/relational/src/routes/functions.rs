@@ -3,21 +3,25 @@ use std::sync::OnceLock;
 use std::time::Duration;
 
 use async_stream::stream;
-use axum::extract::{DefaultBodyLimit, State};
+use axum::extract::{DefaultBodyLimit, Query, State};
 use axum::http::{HeaderMap, HeaderValue};
 use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::IntoResponse;
 use axum::routing::{get, post};
 use axum::{Json, Router};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::Row;
 use tokio::sync::broadcast;
 
 use crate::api_models::functions::{
-    FunctionCallRequest, FunctionCallResponse, FunctionDeployRequest, FunctionDeployResponse,
+    FunctionBatchRequest, FunctionBatchResponse, FunctionBatchResult, FunctionCallRequest,
+    FunctionCallResponse, FunctionDeployQuery, FunctionDeployRequest, FunctionDeployResponse,
+};
+use crate::functions::executor::{
+    ensure_runtime_tables, execute_function_in_transaction, execute_manifest_function,
+    plan_runtime_tables, TableChange,
 };
-use crate::functions::executor::{ensure_runtime_tables, execute_manifest_function};
 use crate::functions::manifest::{
     load_functions_from_uploaded_sources, FunctionKind, FunctionsManifest,
 };
@@ -26,12 +30,17 @@ use mesosphere_common::api::envelope::ApiEnvelope;
 use mesosphere_errors::AppError;
 
 const ACTIVE_DEPLOYMENT_ID: i32 = 1;
+/// Row holding the manifest the last deploy replaced, used by rollback.
+const PREVIOUS_DEPLOYMENT_ID: i32 = 2;
+/// Temporary row id used while swapping the active and previous deployments.
+const SWAP_DEPLOYMENT_ID: i32 = 0;
 const API_KEY_HEADER: &str = "X-API-Key";
 const MAX_DEPLOY_FILES: usize = 256;
 const MAX_DEPLOY_FILE_BYTES: usize = 512 * 1024;
 const MAX_DEPLOY_TOTAL_BYTES: usize = 8 * 1024 * 1024;
 const MAX_DEPLOY_BODY_BYTES: usize = MAX_DEPLOY_TOTAL_BYTES + (1024 * 1024);
 const FUNCTIONS_STREAM_CHANNEL_CAPACITY: usize = 512;
+const MAX_BATCH_CALLS: usize = 64;
 
 #[derive(Debug, Clone, Serialize)]
 struct FunctionStreamEvent {
@@ -40,16 +49,28 @@ struct FunctionStreamEvent {
 }
 
 static FUNCTION_EVENTS: OnceLock<broadcast::Sender<FunctionStreamEvent>> = OnceLock::new();
+static TABLE_EVENTS: OnceLock<broadcast::Sender<TableChange>> = OnceLock::new();
+
+/// Query parameters for the table change stream.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct TableStreamQuery {
+    /// Comma-separated table names to watch; every table when omitted.
+    #[serde(default)]
+    tables: Option<String>,
+}
 
 /// Registers function execution endpoints.
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/functions/stream", get(stream_function_events))
+        .route("/functions/tables/stream", get(stream_table_changes))
         .route("/functions/call", post(call_function))
+        .route("/functions/batch", post(call_function_batch))
         .route(
             "/functions/deploy",
             post(deploy_functions).layer(DefaultBodyLimit::max(MAX_DEPLOY_BODY_BYTES)),
         )
+        .route("/functions/rollback", post(rollback_functions))
 }
 
 async fn stream_function_events() -> impl IntoResponse {
@@ -88,6 +109,61 @@ async fn stream_function_events() -> impl IntoResponse {
     )
 }
 
+/// Streams committed row changes from function writes, optionally limited to some tables.
+async fn stream_table_changes(Query(query): Query<TableStreamQuery>) -> impl IntoResponse {
+    let tables = query
+        .tables
+        .as_deref()
+        .map(|tables| {
+            tables
+                .split(',')
+                .map(str::trim)
+                .filter(|table| !table.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_default();
+    let mut receiver = table_event_sender().subscribe();
+    let event_stream = stream! {
+        let connected_payload = json!({
+            "status": "connected",
+            "tables": tables,
+        });
+        yield Ok::<Event, Infallible>(
+            Event::default()
+                .event("connected")
+                .data(connected_payload.to_string()),
+        );
+
+        loop {
+            match receiver.recv().await {
+                Ok(change) => {
+                    if !tables.is_empty() && !tables.contains(&change.table) {
+                        continue;
+                    }
+                    let payload = match serde_json::to_string(&change) {
+                        Ok(serialized) => serialized,
+                        Err(_) => continue,
+                    };
+                    yield Ok(Event::default().event("table.changed").data(payload));
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    // Subscribers that fell behind should refetch instead of trusting deltas.
+                    let payload = json!({ "skipped": skipped }).to_string();
+                    yield Ok(Event::default().event("table.lagged").data(payload));
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    };
+
+    Sse::new(event_stream).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("keep-alive"),
+    )
+}
+
 async fn call_function(
     State(state): State<AppState>,
     Json(request): Json<FunctionCallRequest>,
@@ -113,7 +189,7 @@ async fn call_function(
     ensure_runtime_tables(&state.pool, state.config.query_max_limit, &manifest).await?;
 
     let args = request.args_object()?;
-    let result = execute_manifest_function(
+    let execution = execute_manifest_function(
         &state.pool,
         state.config.query_max_limit,
         &state.config.public_api_url,
@@ -125,15 +201,21 @@ async fn call_function(
     .await?;
 
     if function_kind == FunctionKind::Mutation {
-        publish_function_event(&endpoint, &result);
+        publish_function_event(&endpoint, &execution.result);
+        for change in execution.changes {
+            let _ = table_event_sender().send(change);
+        }
     }
 
-    Ok(Json(ApiEnvelope::ok(FunctionCallResponse { result })))
+    Ok(Json(ApiEnvelope::ok(FunctionCallResponse {
+        result: execution.result,
+    })))
 }
 
 async fn deploy_functions(
     State(state): State<AppState>,
     headers: HeaderMap,
+    Query(query): Query<FunctionDeployQuery>,
     Json(request): Json<FunctionDeployRequest>,
 ) -> Result<Json<ApiEnvelope<FunctionDeployResponse>>, AppError> {
     ensure_api_key_header(&state, &headers)?;
@@ -152,10 +234,40 @@ async fn deploy_functions(
             "deploy payload did not contain any readFunction/writeFunction exports",
         ));
     }
+    let active = load_deployed_manifest(&state).await?;
+    let plan = plan_runtime_tables(
+        &state.pool,
+        state.config.query_max_limit,
+        &manifest,
+        active.as_ref(),
+    )
+    .await?;
+    if query.dry_run {
+        return Ok(Json(ApiEnvelope::ok(FunctionDeployResponse {
+            deployed_functions: manifest.functions.len(),
+            mode,
+            dry_run: true,
+            statements: plan.statements,
+            warnings: plan.warnings,
+        })));
+    }
     ensure_runtime_tables(&state.pool, state.config.query_max_limit, &manifest).await?;
 
     let manifest_json = serde_json::to_string(&manifest)
         .map_err(|error| AppError::internal(format!("failed to serialize manifest: {}", error)))?;
+    let mut transaction = state.pool.begin().await?;
+    sqlx::query(
+        r#"
+        REPLACE INTO _functions_deployments (id, manifest_json, deployment_mode, deployed_at)
+        SELECT ?, manifest_json, deployment_mode, deployed_at
+        FROM _functions_deployments
+        WHERE id = ?
+        "#,
+    )
+    .bind(PREVIOUS_DEPLOYMENT_ID)
+    .bind(ACTIVE_DEPLOYMENT_ID)
+    .execute(&mut *transaction)
+    .await?;
     sqlx::query(
         r#"
         INSERT INTO _functions_deployments (id, manifest_json, deployment_mode, deployed_at)
@@ -169,15 +281,174 @@ async fn deploy_functions(
     .bind(ACTIVE_DEPLOYMENT_ID)
     .bind(manifest_json)
     .bind(&mode)
-    .execute(&state.pool)
+    .execute(&mut *transaction)
     .await?;
+    transaction.commit().await?;
 
     Ok(Json(ApiEnvelope::ok(FunctionDeployResponse {
         deployed_functions: manifest.functions.len(),
         mode,
+        dry_run: false,
+        statements: plan.statements,
+        warnings: plan.warnings,
+    })))
+}
+
+/// Restores the manifest the last deploy replaced; the replaced manifest becomes the
+/// rollback target, so a second rollback re-applies it.
+async fn rollback_functions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<FunctionDeployQuery>,
+) -> Result<Json<ApiEnvelope<FunctionDeployResponse>>, AppError> {
+    ensure_api_key_header(&state, &headers)?;
+
+    let (previous, mode) = load_deployment(&state, PREVIOUS_DEPLOYMENT_ID)
+        .await?
+        .ok_or_else(|| AppError::not_found("no previous deployment to roll back to"))?;
+    let active = load_deployed_manifest(&state).await?;
+    let plan = plan_runtime_tables(
+        &state.pool,
+        state.config.query_max_limit,
+        &previous,
+        active.as_ref(),
+    )
+    .await?;
+    if query.dry_run {
+        return Ok(Json(ApiEnvelope::ok(FunctionDeployResponse {
+            deployed_functions: previous.functions.len(),
+            mode,
+            dry_run: true,
+            statements: plan.statements,
+            warnings: plan.warnings,
+        })));
+    }
+    ensure_runtime_tables(&state.pool, state.config.query_max_limit, &previous).await?;
+
+    let mut transaction = state.pool.begin().await?;
+    for (from, to) in [
+        (ACTIVE_DEPLOYMENT_ID, SWAP_DEPLOYMENT_ID),
+        (PREVIOUS_DEPLOYMENT_ID, ACTIVE_DEPLOYMENT_ID),
+        (SWAP_DEPLOYMENT_ID, PREVIOUS_DEPLOYMENT_ID),
+    ] {
+        sqlx::query("UPDATE _functions_deployments SET id = ? WHERE id = ?")
+            .bind(to)
+            .bind(from)
+            .execute(&mut *transaction)
+            .await?;
+    }
+    transaction.commit().await?;
+
+    Ok(Json(ApiEnvelope::ok(FunctionDeployResponse {
+        deployed_functions: previous.functions.len(),
+        mode,
+        dry_run: false,
+        statements: plan.statements,
+        warnings: plan.warnings,
     })))
 }
 
+/// Runs several function calls in one transaction: the batch commits only when every
+/// required call succeeds, and optional calls run in savepoints so their failures
+/// roll back just their own writes.
+async fn call_function_batch(
+    State(state): State<AppState>,
+    Json(request): Json<FunctionBatchRequest>,
+) -> Result<Json<ApiEnvelope<FunctionBatchResponse>>, AppError> {
+    if request.calls.is_empty() {
+        return Err(AppError::validation(
+            "function batch must include at least one call",
+        ));
+    }
+    if request.calls.len() > MAX_BATCH_CALLS {
+        return Err(AppError::validation(format!(
+            "function batch exceeds max call count of {}",
+            MAX_BATCH_CALLS
+        )));
+    }
+
+    let manifest = load_runtime_manifest(&state).await?;
+    ensure_runtime_tables(&state.pool, state.config.query_max_limit, &manifest).await?;
+
+    let mut transaction = state.pool.begin().await?;
+    let mut results = Vec::<FunctionBatchResult>::with_capacity(request.calls.len());
+    let mut mutations = Vec::<(String, serde_json::Value, Vec<TableChange>)>::new();
+    for (index, batch_call) in request.calls.iter().enumerate() {
+        let endpoint = batch_call.call.endpoint.trim().to_string();
+        let savepoint = format!("batch_call_{}", index);
+        if batch_call.optional {
+            sqlx::query(&format!("SAVEPOINT {}", savepoint))
+                .execute(&mut *transaction)
+                .await?;
+        }
+
+        let outcome = match batch_call.call.args_object() {
+            Ok(args) => {
+                execute_function_in_transaction(
+                    &state.pool,
+                    state.config.query_max_limit,
+                    &state.config.public_api_url,
+                    state.config.storage_upload_url_ttl_seconds,
+                    &manifest,
+                    &endpoint,
+                    args,
+                    &mut transaction,
+                )
+                .await
+            }
+            Err(error) => Err(error),
+        };
+        match outcome {
+            Ok(execution) => {
+                if batch_call.optional {
+                    sqlx::query(&format!("RELEASE SAVEPOINT {}", savepoint))
+                        .execute(&mut *transaction)
+                        .await?;
+                }
+                let is_mutation = manifest
+                    .functions
+                    .get(&endpoint)
+                    .is_some_and(|function| function.kind == FunctionKind::Mutation);
+                if is_mutation {
+                    mutations.push((
+                        endpoint.clone(),
+                        execution.result.clone(),
+                        execution.changes,
+                    ));
+                }
+                results.push(FunctionBatchResult {
+                    endpoint,
+                    result: execution.result,
+                    error: None,
+                });
+            }
+            Err(error) if batch_call.optional => {
+                sqlx::query(&format!("ROLLBACK TO SAVEPOINT {}", savepoint))
+                    .execute(&mut *transaction)
+                    .await?;
+                results.push(FunctionBatchResult {
+                    endpoint,
+                    result: serde_json::Value::Null,
+                    error: Some(error.to_string()),
+                });
+            }
+            Err(error) => {
+                let _ = transaction.rollback().await;
+                return Err(error);
+            }
+        }
+    }
+    transaction.commit().await?;
+
+    for (endpoint, result, changes) in mutations {
+        publish_function_event(&endpoint, &result);
+        for change in changes {
+            let _ = table_event_sender().send(change);
+        }
+    }
+    Ok(Json(ApiEnvelope::ok(FunctionBatchResponse { results })))
+}
+
 fn normalize_deploy_mode(mode: Option<&str>) -> Result<String, AppError> {
     let normalized = mode.unwrap_or("local").trim().to_ascii_lowercase();
     match normalized.as_str() {
@@ -246,14 +517,24 @@ async fn load_runtime_manifest(state: &AppState) -> Result<FunctionsManifest, Ap
 }
 
 async fn load_deployed_manifest(state: &AppState) -> Result<Option<FunctionsManifest>, AppError> {
+    Ok(load_deployment(state, ACTIVE_DEPLOYMENT_ID)
+        .await?
+        .map(|(manifest, _)| manifest))
+}
+
+/// Loads the manifest and deployment mode stored under `deployment_id`.
+async fn load_deployment(
+    state: &AppState,
+    deployment_id: i32,
+) -> Result<Option<(FunctionsManifest, String)>, AppError> {
     let row = sqlx::query(
         r#"
-        SELECT manifest_json
+        SELECT manifest_json, deployment_mode
         FROM _functions_deployments
         WHERE id = ?
         "#,
     )
-    .bind(ACTIVE_DEPLOYMENT_ID)
+    .bind(deployment_id)
     .fetch_optional(&state.pool)
     .await?;
 
@@ -262,13 +543,14 @@ async fn load_deployed_manifest(state: &AppState) -> Result<Option<FunctionsMani
     };
 
     let manifest_json: String = row.try_get("manifest_json")?;
+    let mode: String = row.try_get("deployment_mode")?;
     let manifest = serde_json::from_str::<FunctionsManifest>(&manifest_json).map_err(|error| {
         AppError::internal(format!(
-            "active deployed manifest is invalid JSON and must be redeployed: {}",
+            "deployed manifest is invalid JSON and must be redeployed: {}",
             error
         ))
     })?;
-    Ok(Some(manifest))
+    Ok(Some((manifest, mode)))
 }
 
 fn function_event_sender() -> &'static broadcast::Sender<FunctionStreamEvent> {
@@ -278,6 +560,13 @@ fn function_event_sender() -> &'static broadcast::Sender<FunctionStreamEvent> {
     })
 }
 
+fn table_event_sender() -> &'static broadcast::Sender<TableChange> {
+    TABLE_EVENTS.get_or_init(|| {
+        let (sender, _receiver) = broadcast::channel(FUNCTIONS_STREAM_CHANNEL_CAPACITY);
+        sender
+    })
+}
+
 fn publish_function_event(endpoint: &str, result: &serde_json::Value) {
     let _ = function_event_sender().send(FunctionStreamEvent {
         endpoint: endpoint.to_string(),
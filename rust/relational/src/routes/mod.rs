@@ -1,4 +1,8 @@
 /// Function-call endpoint.
 pub mod functions;
+/// Runtime table archive and restore endpoints.
+pub mod schema;
 /// Storage upload and file serving endpoints.
 pub mod storage;
+/// Resumable multipart storage upload endpoints.
+pub mod storage_multipart;
@@ -0,0 +1,52 @@
+use axum::extract::{Path, Query, State};
+use axum::routing::{delete, post};
+use axum::{Json, Router};
+
+use crate::api_models::schema::{DeleteTableQuery, SchemaTableResponse};
+use crate::repositories::relational_repo::RelationalRepository;
+use mesosphere_application::state::AppState;
+use mesosphere_common::api::envelope::ApiEnvelope;
+use mesosphere_errors::AppError;
+
+/// Registers schema administration endpoints.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/admin/schema/tables/:table", delete(delete_table))
+        .route("/admin/schema/tables/:table/restore", post(restore_table))
+}
+
+async fn delete_table(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+    Query(query): Query<DeleteTableQuery>,
+) -> Result<Json<ApiEnvelope<SchemaTableResponse>>, AppError> {
+    let mode = query.mode.as_deref().unwrap_or("archive");
+    if mode != "archive" {
+        return Err(AppError::validation(format!(
+            "table delete mode '{}' is invalid; expected 'archive'",
+            mode
+        )));
+    }
+    let archived_as = repository(&state).archive_table(&table).await?;
+    Ok(Json(ApiEnvelope::ok(SchemaTableResponse {
+        table,
+        active: false,
+        archived_as: Some(archived_as),
+    })))
+}
+
+async fn restore_table(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+) -> Result<Json<ApiEnvelope<SchemaTableResponse>>, AppError> {
+    repository(&state).restore_table(&table).await?;
+    Ok(Json(ApiEnvelope::ok(SchemaTableResponse {
+        table,
+        active: true,
+        archived_as: None,
+    })))
+}
+
+fn repository(state: &AppState) -> RelationalRepository {
+    RelationalRepository::new(state.pool.clone(), state.config.query_max_limit)
+}
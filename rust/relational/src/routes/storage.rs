@@ -1,8 +1,8 @@
 use std::path::{Path as FsPath, PathBuf};
 
-use axum::body::Bytes;
+use axum::body::{Body, Bytes};
 use axum::extract::{Path, Query, State};
-use axum::http::header::{CACHE_CONTROL, CONTENT_TYPE};
+use axum::http::header::{CACHE_CONTROL, CONTENT_LENGTH, CONTENT_TYPE};
 use axum::http::{HeaderMap, HeaderValue, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
@@ -12,31 +12,105 @@ use serde::Deserialize;
 use mesosphere_application::state::AppState;
 use mesosphere_common::api::envelope::ApiEnvelope;
 use mesosphere_errors::AppError;
+use sha2::{Digest, Sha256};
 use sqlx::Row;
 use tokio::fs;
+use tokio::io::AsyncReadExt;
 use uuid::Uuid;
 
-use crate::api_models::storage::StorageUploadResponse;
+use crate::api_models::storage::{
+    StorageSignRequest, StorageSignedUrlResponse, StorageUploadResponse,
+};
+use crate::image_transform::{transform_image, ImageTransform};
+use crate::storage_gc::{collect_storage_garbage, StorageGcReport};
+use crate::storage_signing::{
+    sign_storage_download, verify_storage_download, DEFAULT_SIGNED_URL_TTL_SECONDS,
+    MAX_SIGNED_URL_TTL_SECONDS,
+};
 
 const UPLOAD_TOKEN_HEADER: &str = "X-Upload-Token";
+const CONTENT_SHA256_HEADER: &str = "X-Content-SHA256";
+/// Bytes read from disk per chunk of a streamed download.
+const DOWNLOAD_CHUNK_BYTES: usize = 64 * 1024;
 
 /// Registers public storage endpoints.
 /// Upload accepts one-time token authentication via `?token=<uuid>` or `X-Upload-Token`.
+/// Images can be fetched resized or re-encoded with `?w=`, `?h=` and `?format=`.
+/// Downloads carrying `?expires=&signature=` from `/storage/:id/sign` are verified.
 pub fn public_router() -> Router<AppState> {
     Router::new()
         .route("/storage/files/:storage_id", get(get_storage_file))
+        .route("/storage/:storage_id", get(get_storage_file))
         .route("/storage/upload", post(upload_storage_file))
+        .merge(super::storage_multipart::router())
 }
 
 /// Registers protected storage endpoints.
-/// Kept for compatibility and future authenticated storage routes.
 pub fn protected_router() -> Router<AppState> {
     Router::new()
+        .route("/storage/gc", post(run_storage_gc))
+        .route("/storage/:storage_id/sign", post(sign_storage_url))
+}
+
+async fn sign_storage_url(
+    State(state): State<AppState>,
+    Path(storage_id): Path<String>,
+    request: Option<Json<StorageSignRequest>>,
+) -> Result<Json<ApiEnvelope<StorageSignedUrlResponse>>, AppError> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let ttl_seconds = request
+        .expires_in_seconds
+        .unwrap_or(DEFAULT_SIGNED_URL_TTL_SECONDS);
+    if ttl_seconds == 0 || ttl_seconds > MAX_SIGNED_URL_TTL_SECONDS {
+        return Err(AppError::validation(format!(
+            "expires_in_seconds must be between 1 and {}",
+            MAX_SIGNED_URL_TTL_SECONDS
+        )));
+    }
+    let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(1) FROM _storage_files WHERE id = ?")
+        .bind(&storage_id)
+        .fetch_one(&state.pool)
+        .await?;
+    if exists == 0 {
+        return Err(AppError::not_found(format!(
+            "storage object '{}' not found",
+            storage_id
+        )));
+    }
+
+    let expires_at = Utc::now().timestamp() + ttl_seconds as i64;
+    let signature =
+        sign_storage_download(&state.config.storage_signing_secret, &storage_id, expires_at)?;
+    let base = state.config.public_api_url.trim().trim_end_matches('/');
+    Ok(Json(ApiEnvelope::ok(StorageSignedUrlResponse {
+        url: format!(
+            "{}/v1/storage/files/{}?expires={}&signature={}",
+            base, storage_id, expires_at, signature
+        ),
+        expires_at,
+    })))
+}
+
+async fn run_storage_gc(
+    State(state): State<AppState>,
+) -> Result<Json<ApiEnvelope<StorageGcReport>>, AppError> {
+    let storage_dir = PathBuf::from(state.config.storage_dir.as_str());
+    let report = collect_storage_garbage(&state.pool, &storage_dir).await?;
+    Ok(Json(ApiEnvelope::ok(report)))
 }
 
 #[derive(Debug, Deserialize)]
-struct UploadTokenQuery {
-    token: Option<String>,
+pub(crate) struct UploadTokenQuery {
+    pub(crate) token: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StorageFileQuery {
+    w: Option<u32>,
+    h: Option<u32>,
+    format: Option<String>,
+    expires: Option<i64>,
+    signature: Option<String>,
 }
 
 async fn upload_storage_file(
@@ -99,6 +173,7 @@ async fn upload_storage_file(
     }
 
     let content_type = extract_content_type(&headers);
+    let content_sha256 = hex::encode(Sha256::digest(&body));
     let storage_dir = PathBuf::from(state.config.storage_dir.as_str());
     fs::create_dir_all(&storage_dir).await.map_err(|error| {
         AppError::internal(format!(
@@ -115,7 +190,13 @@ async fn upload_storage_file(
             storage_id
         )));
     }
-    let final_file_path = storage_dir.join(format!("{}.bin", storage_id));
+    // Identical content already on disk is shared instead of written again.
+    let duplicate_filename =
+        find_stored_duplicate(&state, &storage_dir, &content_sha256, body.len()).await?;
+    let deduplicated = duplicate_filename.is_some();
+    let final_file_path = storage_dir.join(
+        duplicate_filename.unwrap_or_else(|| format!("{}.bin", storage_id)),
+    );
     let stored_filename = final_file_path
         .file_name()
         .and_then(|file_name| file_name.to_str())
@@ -127,29 +208,33 @@ async fn upload_storage_file(
             ))
         })?;
     let temp_file_path = storage_dir.join(format!("{}.{}.bin.tmp", storage_id, Uuid::new_v4()));
-    fs::write(&temp_file_path, &body).await.map_err(|error| {
-        AppError::internal(format!(
-            "failed to write temporary storage file '{}': {}",
-            temp_file_path.display(),
-            error
-        ))
-    })?;
+    if !deduplicated {
+        fs::write(&temp_file_path, &body).await.map_err(|error| {
+            AppError::internal(format!(
+                "failed to write temporary storage file '{}': {}",
+                temp_file_path.display(),
+                error
+            ))
+        })?;
+    }
 
     let db_result: Result<(), AppError> = async {
         sqlx::query(
             r#"
-            INSERT INTO _storage_files (id, content_type, byte_size, file_path)
-            VALUES (?, ?, ?, ?)
+            INSERT INTO _storage_files (id, content_type, byte_size, file_path, content_sha256)
+            VALUES (?, ?, ?, ?, ?)
             ON DUPLICATE KEY UPDATE
                 content_type = VALUES(content_type),
                 byte_size = VALUES(byte_size),
-                file_path = VALUES(file_path)
+                file_path = VALUES(file_path),
+                content_sha256 = VALUES(content_sha256)
             "#,
         )
         .bind(&storage_id)
         .bind(&content_type)
         .bind(body.len() as u64)
         .bind(&stored_filename)
+        .bind(&content_sha256)
         .execute({
             let tx = transaction
                 .as_mut()
@@ -168,16 +253,18 @@ async fn upload_storage_file(
             })
             .await?;
 
-        fs::rename(&temp_file_path, &final_file_path)
-            .await
-            .map_err(|error| {
-                AppError::internal(format!(
-                    "failed to finalize storage file '{}' from '{}': {}",
-                    final_file_path.display(),
-                    temp_file_path.display(),
-                    error
-                ))
-            })?;
+        if !deduplicated {
+            fs::rename(&temp_file_path, &final_file_path)
+                .await
+                .map_err(|error| {
+                    AppError::internal(format!(
+                        "failed to finalize storage file '{}' from '{}': {}",
+                        final_file_path.display(),
+                        temp_file_path.display(),
+                        error
+                    ))
+                })?;
+        }
 
         let tx = transaction
             .take()
@@ -201,12 +288,14 @@ async fn upload_storage_file(
                 cleanup_error
             ));
         }
-        if let Err(cleanup_error) = remove_file_if_exists(&final_file_path).await {
-            cleanup_failures.push(format!(
-                "failed to remove finalized file '{}': {}",
-                final_file_path.display(),
-                cleanup_error
-            ));
+        if !deduplicated {
+            if let Err(cleanup_error) = remove_file_if_exists(&final_file_path).await {
+                cleanup_failures.push(format!(
+                    "failed to remove finalized file '{}': {}",
+                    final_file_path.display(),
+                    cleanup_error
+                ));
+            }
         }
         if cleanup_failures.is_empty() {
             return Err(error);
@@ -218,16 +307,72 @@ async fn upload_storage_file(
         )));
     }
 
-    Ok(Json(ApiEnvelope::ok(StorageUploadResponse { storage_id })))
+    Ok(Json(ApiEnvelope::ok(StorageUploadResponse {
+        storage_id,
+        sha256: content_sha256,
+    })))
+}
+
+/// Returns the file name of an uploaded object with the same content, when its file still exists.
+async fn find_stored_duplicate(
+    state: &AppState,
+    storage_dir: &FsPath,
+    content_sha256: &str,
+    byte_size: usize,
+) -> Result<Option<String>, AppError> {
+    let file_name = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT file_path
+        FROM _storage_files
+        WHERE content_sha256 = ? AND byte_size = ?
+        LIMIT 1
+        "#,
+    )
+    .bind(content_sha256)
+    .bind(byte_size as u64)
+    .fetch_optional(&state.pool)
+    .await?;
+    let Some(file_name) = file_name else {
+        return Ok(None);
+    };
+    if file_name.contains('/') || file_name.contains('\\') || file_name.contains("..") {
+        return Ok(None);
+    }
+    let exists = fs::try_exists(storage_dir.join(&file_name))
+        .await
+        .unwrap_or(false);
+    Ok(exists.then_some(file_name))
 }
 
 async fn get_storage_file(
     State(state): State<AppState>,
     Path(storage_id): Path<String>,
+    Query(query): Query<StorageFileQuery>,
 ) -> Result<Response, AppError> {
+    match (query.expires, query.signature.as_deref()) {
+        (Some(expires), Some(signature)) => verify_storage_download(
+            &state.config.storage_signing_secret,
+            &storage_id,
+            expires,
+            signature,
+            Utc::now().timestamp(),
+        )?,
+        (None, None) if !state.config.storage_require_signed_urls => {}
+        (None, None) => {
+            return Err(AppError::unauthorized(
+                "storage downloads require a signed URL",
+            ))
+        }
+        _ => {
+            return Err(AppError::unauthorized(
+                "signed storage URLs need both expires and signature",
+            ))
+        }
+    }
+
     let row = sqlx::query(
         r#"
-        SELECT content_type, file_path
+        SELECT content_type, file_path, content_sha256
         FROM _storage_files
         WHERE id = ?
         "#,
@@ -245,6 +390,9 @@ async fn get_storage_file(
 
     let content_type: String = row.try_get("content_type")?;
     let stored_filename: String = row.try_get("file_path")?;
+    let content_sha256: Option<String> = row.try_get("content_sha256")?;
+    let transform =
+        ImageTransform::from_query(query.w, query.h, query.format.as_deref(), &content_type)?;
     if stored_filename.contains('/') || stored_filename.contains('\\') || stored_filename.contains("..")
     {
         return Err(AppError::internal(format!(
@@ -252,8 +400,9 @@ async fn get_storage_file(
             stored_filename
         )));
     }
-    let file_path = PathBuf::from(state.config.storage_dir.as_str()).join(&stored_filename);
-    let bytes = fs::read(&file_path).await.map_err(|error| {
+    let storage_dir = PathBuf::from(state.config.storage_dir.as_str());
+    let file_path = storage_dir.join(&stored_filename);
+    let read_error = |error: std::io::Error| {
         if error.kind() == std::io::ErrorKind::NotFound {
             AppError::not_found(format!("storage object '{}' file is missing", storage_id))
         } else {
@@ -263,9 +412,45 @@ async fn get_storage_file(
                 error
             ))
         }
-    })?;
+    };
+
+    let (mut response, content_type, content_sha256) = match transform {
+        Some(transform) => {
+            let bytes = fs::read(&file_path).await.map_err(read_error)?;
+            if let Some(expected) = &content_sha256 {
+                if hex::encode(Sha256::digest(&bytes)) != *expected {
+                    return Err(AppError::internal(format!(
+                        "storage object '{}' failed SHA-256 integrity verification",
+                        storage_id
+                    )));
+                }
+            }
+            let bytes = transform_image(
+                &state.config.storage_image_command,
+                &storage_dir,
+                &storage_id,
+                transform,
+                bytes,
+            )
+            .await?;
+            (
+                (StatusCode::OK, bytes).into_response(),
+                transform.format.content_type().to_string(),
+                None,
+            )
+        }
+        None => {
+            let file = fs::File::open(&file_path).await.map_err(read_error)?;
+            let byte_size = file.metadata().await.map_err(read_error)?.len();
+            let body = verified_file_body(file, storage_id.clone(), content_sha256.clone());
+            let mut response = (StatusCode::OK, body).into_response();
+            response
+                .headers_mut()
+                .insert(CONTENT_LENGTH, HeaderValue::from(byte_size));
+            (response, content_type, content_sha256)
+        }
+    };
 
-    let mut response = (StatusCode::OK, bytes).into_response();
     let response_headers = response.headers_mut();
     response_headers.insert(
         CACHE_CONTROL,
@@ -276,10 +461,45 @@ async fn get_storage_file(
         HeaderValue::from_str(&content_type)
             .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream")),
     );
+    if let Some(Ok(value)) = content_sha256.as_deref().map(HeaderValue::from_str) {
+        response_headers.insert(CONTENT_SHA256_HEADER, value);
+    }
     Ok(response)
 }
 
-fn extract_content_type(headers: &HeaderMap) -> String {
+/// Streams a stored file in [`DOWNLOAD_CHUNK_BYTES`] chunks, hashing it on the way. When the
+/// content does not match `expected`, the body ends with an error instead of its last chunk,
+/// so the client sees an aborted transfer rather than corrupt bytes.
+fn verified_file_body(file: fs::File, storage_id: String, expected: Option<String>) -> Body {
+    let chunks =
+        futures_util::stream::try_unfold((file, Sha256::new()), move |(mut file, mut hasher)| {
+            let (storage_id, expected) = (storage_id.clone(), expected.clone());
+            async move {
+                let mut chunk = vec![0_u8; DOWNLOAD_CHUNK_BYTES];
+                let read = file.read(&mut chunk).await?;
+                if read == 0 {
+                    let digest = hex::encode(hasher.finalize());
+                    if expected.is_some_and(|expected| expected != digest) {
+                        tracing::error!(
+                            storage_id = %storage_id,
+                            "storage object failed SHA-256 integrity verification"
+                        );
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "storage object failed SHA-256 integrity verification",
+                        ));
+                    }
+                    return Ok(None);
+                }
+                chunk.truncate(read);
+                hasher.update(&chunk);
+                Ok(Some((Bytes::from(chunk), (file, hasher))))
+            }
+        });
+    Body::from_stream(chunks)
+}
+
+pub(crate) fn extract_content_type(headers: &HeaderMap) -> String {
     headers
         .get(CONTENT_TYPE)
         .and_then(|value| value.to_str().ok())
@@ -289,7 +509,7 @@ fn extract_content_type(headers: &HeaderMap) -> String {
         .unwrap_or_else(|| "application/octet-stream".to_string())
 }
 
-fn required_upload_token(headers: &HeaderMap, query_token: Option<&str>) -> Result<String, AppError> {
+pub(crate) fn required_upload_token(headers: &HeaderMap, query_token: Option<&str>) -> Result<String, AppError> {
     if let Some(raw_token) = query_token {
         return normalize_upload_token(raw_token, "token query parameter");
     }
@@ -316,7 +536,7 @@ fn normalize_upload_token(raw_token: &str, source: &str) -> Result<String, AppEr
     Ok(parsed.hyphenated().to_string())
 }
 
-async fn remove_file_if_exists(path: &FsPath) -> Result<(), std::io::Error> {
+pub(crate) async fn remove_file_if_exists(path: &FsPath) -> Result<(), std::io::Error> {
     match fs::remove_file(path).await {
         Ok(()) => Ok(()),
         Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
@@ -0,0 +1,638 @@
+use std::path::{Path as FsPath, PathBuf};
+
+use axum::body::Body;
+use axum::extract::{Path, Query, State};
+use axum::http::HeaderMap;
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use futures_util::StreamExt;
+use mesosphere_application::state::AppState;
+use mesosphere_common::api::envelope::ApiEnvelope;
+use mesosphere_errors::AppError;
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+use super::storage::{
+    extract_content_type, remove_file_if_exists, required_upload_token, UploadTokenQuery,
+};
+use crate::api_models::storage::{
+    StorageMultipartCompleteRequest, StorageMultipartExpectedPart, StorageMultipartPart,
+    StorageMultipartUploadResponse, StorageUploadResponse,
+};
+
+/// Header carrying the client's hex SHA-256 of a part.
+const PART_SHA256_HEADER: &str = "X-Part-SHA256";
+/// Highest accepted part number.
+const MAX_PART_NUMBER: u32 = 10_000;
+/// Lifetime of an unfinished multipart upload (and of its upload token).
+const MULTIPART_UPLOAD_TTL_SECONDS: i64 = 24 * 60 * 60;
+/// Directory under the storage directory holding uploaded parts, one folder per upload.
+pub const MULTIPART_DIR: &str = "multipart";
+
+/// Registers multipart upload endpoints, authenticated like `/storage/upload` by the
+/// one-time upload token (`?token=<uuid>` or `X-Upload-Token`).
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/storage/multipart/init", post(init_multipart_upload))
+        .route("/storage/multipart/:upload_id", get(get_multipart_upload))
+        .route(
+            "/storage/multipart/:upload_id/parts/:part_number",
+            put(upload_part),
+        )
+        .route(
+            "/storage/multipart/:upload_id/complete",
+            post(complete_multipart_upload),
+        )
+}
+
+/// Multipart upload row authorized by the caller's token.
+struct MultipartUpload {
+    upload_id: String,
+    storage_id: String,
+    token: String,
+    content_type: String,
+}
+
+async fn init_multipart_upload(
+    State(state): State<AppState>,
+    Query(query): Query<UploadTokenQuery>,
+    headers: HeaderMap,
+) -> Result<Json<ApiEnvelope<StorageMultipartUploadResponse>>, AppError> {
+    let token = required_upload_token(&headers, query.token.as_deref())?;
+    let mut transaction = state.pool.begin().await?;
+    let token_row = sqlx::query(
+        r#"
+        SELECT storage_id, expires_at < UTC_TIMESTAMP(6) AS expired
+        FROM _storage_upload_tokens
+        WHERE token = ?
+        FOR UPDATE
+        "#,
+    )
+    .bind(&token)
+    .fetch_optional(&mut *transaction)
+    .await?;
+    let Some(token_row) = token_row else {
+        return Err(AppError::not_found("upload URL is invalid or already used"));
+    };
+    let storage_id: String = token_row.try_get("storage_id")?;
+    let existing = sqlx::query_scalar::<_, String>(
+        "SELECT upload_id FROM _storage_multipart_uploads WHERE storage_id = ?",
+    )
+    .bind(&storage_id)
+    .fetch_optional(&mut *transaction)
+    .await?;
+
+    // Initializing again with the same token resumes the existing upload.
+    let upload_id = match existing {
+        Some(upload_id) => upload_id,
+        None => {
+            if token_row.try_get::<i64, _>("expired")? != 0 {
+                return Err(AppError::validation("upload URL has expired"));
+            }
+            let upload_id = Uuid::new_v4().to_string();
+            sqlx::query(
+                r#"
+                INSERT INTO _storage_multipart_uploads
+                    (upload_id, storage_id, token, content_type, expires_at)
+                VALUES (?, ?, ?, ?, UTC_TIMESTAMP(6) + INTERVAL ? SECOND)
+                "#,
+            )
+            .bind(&upload_id)
+            .bind(&storage_id)
+            .bind(&token)
+            .bind(extract_content_type(&headers))
+            .bind(MULTIPART_UPLOAD_TTL_SECONDS)
+            .execute(&mut *transaction)
+            .await?;
+            // Keep the token (and its pending file row) alive for the whole upload.
+            sqlx::query(
+                r#"
+                UPDATE _storage_upload_tokens
+                SET expires_at = GREATEST(expires_at, UTC_TIMESTAMP(6) + INTERVAL ? SECOND)
+                WHERE token = ?
+                "#,
+            )
+            .bind(MULTIPART_UPLOAD_TTL_SECONDS)
+            .bind(&token)
+            .execute(&mut *transaction)
+            .await?;
+            upload_id
+        }
+    };
+    transaction.commit().await?;
+
+    let upload = authorized_upload(&state, &upload_id, &token).await?;
+    Ok(Json(ApiEnvelope::ok(upload_status(&state, &upload).await?)))
+}
+
+async fn get_multipart_upload(
+    State(state): State<AppState>,
+    Path(upload_id): Path<String>,
+    Query(query): Query<UploadTokenQuery>,
+    headers: HeaderMap,
+) -> Result<Json<ApiEnvelope<StorageMultipartUploadResponse>>, AppError> {
+    let token = required_upload_token(&headers, query.token.as_deref())?;
+    let upload = authorized_upload(&state, &upload_id, &token).await?;
+    Ok(Json(ApiEnvelope::ok(upload_status(&state, &upload).await?)))
+}
+
+async fn upload_part(
+    State(state): State<AppState>,
+    Path((upload_id, part_number)): Path<(String, u32)>,
+    Query(query): Query<UploadTokenQuery>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<Json<ApiEnvelope<StorageMultipartPart>>, AppError> {
+    if part_number == 0 || part_number > MAX_PART_NUMBER {
+        return Err(AppError::validation(format!(
+            "part_number must be between 1 and {}",
+            MAX_PART_NUMBER
+        )));
+    }
+    let token = required_upload_token(&headers, query.token.as_deref())?;
+    let upload = authorized_upload(&state, &upload_id, &token).await?;
+    let expected_sha256 = headers
+        .get(PART_SHA256_HEADER)
+        .map(|value| {
+            value
+                .to_str()
+                .map(|value| value.trim().to_ascii_lowercase())
+                .map_err(|_| {
+                    AppError::validation(format!("{} must be valid UTF-8", PART_SHA256_HEADER))
+                })
+        })
+        .transpose()?;
+
+    let parts_dir = parts_dir(&state, &upload.upload_id);
+    fs::create_dir_all(&parts_dir).await.map_err(|error| {
+        AppError::internal(format!(
+            "failed to create multipart directory '{}': {}",
+            parts_dir.display(),
+            error
+        ))
+    })?;
+    let part_path = parts_dir.join(part_file_name(part_number));
+    let temp_path = parts_dir.join(format!(
+        "{}.{}.tmp",
+        part_file_name(part_number),
+        Uuid::new_v4()
+    ));
+    let written = write_part(
+        body,
+        &temp_path,
+        state.config.storage_max_upload_bytes as u64,
+    )
+    .await;
+    let (byte_size, sha256) = match written {
+        Ok(written) => written,
+        Err(error) => {
+            let _ = remove_file_if_exists(&temp_path).await;
+            return Err(error);
+        }
+    };
+    if let Some(expected) = expected_sha256 {
+        if expected != sha256 {
+            let _ = remove_file_if_exists(&temp_path).await;
+            return Err(AppError::validation(format!(
+                "part {} checksum mismatch: expected {}, received {}",
+                part_number, expected, sha256
+            )));
+        }
+    }
+
+    // Parts of one upload can arrive concurrently, so the total is checked and the part
+    // recorded while the upload row is locked.
+    let recorded: Result<(), AppError> = async {
+        let mut transaction = state.pool.begin().await?;
+        sqlx::query(
+            "SELECT upload_id FROM _storage_multipart_uploads WHERE upload_id = ? FOR UPDATE",
+        )
+        .bind(&upload.upload_id)
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or_else(|| AppError::not_found("multipart upload was completed or expired"))?;
+        // Re-uploading a part replaces it, so only the other parts count toward the total.
+        let other_parts_bytes = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT CAST(COALESCE(SUM(byte_size), 0) AS SIGNED)
+            FROM _storage_multipart_parts
+            WHERE upload_id = ? AND part_number <> ?
+            "#,
+        )
+        .bind(&upload.upload_id)
+        .bind(part_number)
+        .fetch_one(&mut *transaction)
+        .await?;
+        check_total_bytes(
+            other_parts_bytes.max(0) as u64 + byte_size,
+            state.config.storage_max_multipart_bytes,
+        )?;
+        fs::rename(&temp_path, &part_path).await.map_err(|error| {
+            AppError::internal(format!(
+                "failed to finalize part '{}': {}",
+                part_path.display(),
+                error
+            ))
+        })?;
+        sqlx::query(
+            r#"
+            INSERT INTO _storage_multipart_parts (upload_id, part_number, byte_size, content_sha256)
+            VALUES (?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                byte_size = VALUES(byte_size),
+                content_sha256 = VALUES(content_sha256)
+            "#,
+        )
+        .bind(&upload.upload_id)
+        .bind(part_number)
+        .bind(byte_size)
+        .bind(&sha256)
+        .execute(&mut *transaction)
+        .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+    .await;
+    if let Err(error) = recorded {
+        let _ = remove_file_if_exists(&temp_path).await;
+        return Err(error);
+    }
+
+    Ok(Json(ApiEnvelope::ok(StorageMultipartPart {
+        part_number,
+        byte_size,
+        sha256,
+    })))
+}
+
+async fn complete_multipart_upload(
+    State(state): State<AppState>,
+    Path(upload_id): Path<String>,
+    Query(query): Query<UploadTokenQuery>,
+    headers: HeaderMap,
+    request: Option<Json<StorageMultipartCompleteRequest>>,
+) -> Result<Json<ApiEnvelope<StorageUploadResponse>>, AppError> {
+    let token = required_upload_token(&headers, query.token.as_deref())?;
+    let upload = authorized_upload(&state, &upload_id, &token).await?;
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let parts = stored_parts(&state, &upload.upload_id).await?;
+    check_parts_complete(&parts)?;
+    if let Some(expected) = &request.parts {
+        check_expected_parts(&parts, expected)?;
+    }
+    let total_bytes = parts.iter().map(|part| part.byte_size).sum::<u64>();
+    check_total_bytes(total_bytes, state.config.storage_max_multipart_bytes)?;
+
+    let storage_dir = PathBuf::from(state.config.storage_dir.as_str());
+    let parts_dir = parts_dir(&state, &upload.upload_id);
+    let stored_filename = format!("{}.bin", upload.storage_id);
+    let final_path = storage_dir.join(&stored_filename);
+    let temp_path = storage_dir.join(format!("{}.{}.bin.tmp", upload.storage_id, Uuid::new_v4()));
+    let assembled = assemble_parts(&parts_dir, &parts, &temp_path).await;
+    let content_sha256 = match assembled {
+        Ok(content_sha256) => content_sha256,
+        Err(error) => {
+            let _ = remove_file_if_exists(&temp_path).await;
+            return Err(error);
+        }
+    };
+
+    let committed: Result<(), AppError> = async {
+        let mut transaction = state.pool.begin().await?;
+        sqlx::query(
+            r#"
+            UPDATE _storage_files
+            SET content_type = ?, byte_size = ?, file_path = ?, content_sha256 = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&upload.content_type)
+        .bind(total_bytes)
+        .bind(&stored_filename)
+        .bind(&content_sha256)
+        .bind(&upload.storage_id)
+        .execute(&mut *transaction)
+        .await?;
+        sqlx::query("DELETE FROM _storage_multipart_uploads WHERE upload_id = ?")
+            .bind(&upload.upload_id)
+            .execute(&mut *transaction)
+            .await?;
+        sqlx::query("DELETE FROM _storage_upload_tokens WHERE token = ?")
+            .bind(&upload.token)
+            .execute(&mut *transaction)
+            .await?;
+        fs::rename(&temp_path, &final_path).await.map_err(|error| {
+            AppError::internal(format!(
+                "failed to finalize storage file '{}': {}",
+                final_path.display(),
+                error
+            ))
+        })?;
+        transaction.commit().await?;
+        Ok(())
+    }
+    .await;
+    if let Err(error) = committed {
+        let _ = remove_file_if_exists(&temp_path).await;
+        let _ = remove_file_if_exists(&final_path).await;
+        return Err(error);
+    }
+    if let Err(error) = fs::remove_dir_all(&parts_dir).await {
+        tracing::warn!(dir = %parts_dir.display(), error = %error, "failed to remove multipart parts");
+    }
+
+    Ok(Json(ApiEnvelope::ok(StorageUploadResponse {
+        storage_id: upload.storage_id,
+        sha256: content_sha256,
+    })))
+}
+
+/// Loads an unexpired upload that belongs to `token`.
+async fn authorized_upload(
+    state: &AppState,
+    upload_id: &str,
+    token: &str,
+) -> Result<MultipartUpload, AppError> {
+    let upload_id = Uuid::parse_str(upload_id.trim())
+        .map_err(|_| AppError::validation("upload_id must be a valid UUID"))?
+        .hyphenated()
+        .to_string();
+    let row = sqlx::query(
+        r#"
+        SELECT storage_id, content_type
+        FROM _storage_multipart_uploads
+        WHERE upload_id = ? AND token = ? AND expires_at > UTC_TIMESTAMP(6)
+        "#,
+    )
+    .bind(&upload_id)
+    .bind(token)
+    .fetch_optional(&state.pool)
+    .await?;
+    let Some(row) = row else {
+        return Err(AppError::not_found(
+            "multipart upload is invalid, expired or already completed",
+        ));
+    };
+    Ok(MultipartUpload {
+        upload_id,
+        storage_id: row.try_get("storage_id")?,
+        token: token.to_string(),
+        content_type: row.try_get("content_type")?,
+    })
+}
+
+async fn upload_status(
+    state: &AppState,
+    upload: &MultipartUpload,
+) -> Result<StorageMultipartUploadResponse, AppError> {
+    let expires_at = sqlx::query_scalar::<_, chrono::NaiveDateTime>(
+        "SELECT expires_at FROM _storage_multipart_uploads WHERE upload_id = ?",
+    )
+    .bind(&upload.upload_id)
+    .fetch_one(&state.pool)
+    .await?;
+    Ok(StorageMultipartUploadResponse {
+        upload_id: upload.upload_id.clone(),
+        storage_id: upload.storage_id.clone(),
+        max_part_bytes: state.config.storage_max_upload_bytes as u64,
+        max_total_bytes: state.config.storage_max_multipart_bytes,
+        expires_at: expires_at.to_string(),
+        parts: stored_parts(state, &upload.upload_id).await?,
+    })
+}
+
+async fn stored_parts(
+    state: &AppState,
+    upload_id: &str,
+) -> Result<Vec<StorageMultipartPart>, AppError> {
+    let rows = sqlx::query(
+        r#"
+        SELECT part_number, byte_size, content_sha256
+        FROM _storage_multipart_parts
+        WHERE upload_id = ?
+        ORDER BY part_number ASC
+        "#,
+    )
+    .bind(upload_id)
+    .fetch_all(&state.pool)
+    .await?;
+    rows.into_iter()
+        .map(|row| {
+            Ok(StorageMultipartPart {
+                part_number: row.try_get::<i32, _>("part_number")? as u32,
+                byte_size: row.try_get::<u64, _>("byte_size")?,
+                sha256: row.try_get("content_sha256")?,
+            })
+        })
+        .collect()
+}
+
+/// Streams a part body to `path`, enforcing `max_bytes`, and returns its size and SHA-256.
+async fn write_part(body: Body, path: &FsPath, max_bytes: u64) -> Result<(u64, String), AppError> {
+    let mut file = fs::File::create(path).await.map_err(|error| {
+        AppError::internal(format!(
+            "failed to create part file '{}': {}",
+            path.display(),
+            error
+        ))
+    })?;
+    let mut hasher = Sha256::new();
+    let mut byte_size = 0_u64;
+    let mut stream = body.into_data_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|error| {
+            AppError::validation(format!("failed to read part body: {}", error))
+        })?;
+        byte_size += chunk.len() as u64;
+        if byte_size > max_bytes {
+            return Err(AppError::validation(format!(
+                "part exceeds max allowed size of {} bytes",
+                max_bytes
+            )));
+        }
+        hasher.update(&chunk);
+        file.write_all(&chunk).await.map_err(|error| {
+            AppError::internal(format!(
+                "failed to write part file '{}': {}",
+                path.display(),
+                error
+            ))
+        })?;
+    }
+    if byte_size == 0 {
+        return Err(AppError::validation("part body cannot be empty"));
+    }
+    file.flush().await.map_err(|error| {
+        AppError::internal(format!(
+            "failed to flush part file '{}': {}",
+            path.display(),
+            error
+        ))
+    })?;
+    Ok((byte_size, hex::encode(hasher.finalize())))
+}
+
+/// Concatenates parts in order into `target`, re-verifying each checksum, and returns the
+/// SHA-256 of the whole object.
+async fn assemble_parts(
+    parts_dir: &FsPath,
+    parts: &[StorageMultipartPart],
+    target: &FsPath,
+) -> Result<String, AppError> {
+    let mut file = fs::File::create(target).await.map_err(|error| {
+        AppError::internal(format!(
+            "failed to create storage file '{}': {}",
+            target.display(),
+            error
+        ))
+    })?;
+    let mut hasher = Sha256::new();
+    for part in parts {
+        let part_path = parts_dir.join(part_file_name(part.part_number));
+        let bytes = fs::read(&part_path).await.map_err(|error| {
+            AppError::internal(format!(
+                "failed to read part '{}': {}",
+                part_path.display(),
+                error
+            ))
+        })?;
+        if hex::encode(Sha256::digest(&bytes)) != part.sha256 {
+            return Err(AppError::internal(format!(
+                "part {} failed SHA-256 integrity verification; upload it again",
+                part.part_number
+            )));
+        }
+        hasher.update(&bytes);
+        file.write_all(&bytes).await.map_err(|error| {
+            AppError::internal(format!(
+                "failed to write storage file '{}': {}",
+                target.display(),
+                error
+            ))
+        })?;
+    }
+    file.flush().await.map_err(|error| {
+        AppError::internal(format!(
+            "failed to flush storage file '{}': {}",
+            target.display(),
+            error
+        ))
+    })?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Rejects an upload whose parts add up to more than `max_bytes`.
+fn check_total_bytes(total_bytes: u64, max_bytes: u64) -> Result<(), AppError> {
+    if total_bytes > max_bytes {
+        return Err(AppError::validation(format!(
+            "multipart upload exceeds max allowed size of {} bytes",
+            max_bytes
+        )));
+    }
+    Ok(())
+}
+
+/// Requires parts numbered 1..=n without gaps.
+fn check_parts_complete(parts: &[StorageMultipartPart]) -> Result<(), AppError> {
+    if parts.is_empty() {
+        return Err(AppError::validation("multipart upload has no parts"));
+    }
+    if let Some((index, _)) = parts
+        .iter()
+        .enumerate()
+        .find(|(index, part)| part.part_number != *index as u32 + 1)
+    {
+        return Err(AppError::validation(format!(
+            "multipart upload is missing part {}",
+            index + 1
+        )));
+    }
+    Ok(())
+}
+
+/// Requires the stored parts to be exactly the client's list with matching checksums.
+fn check_expected_parts(
+    parts: &[StorageMultipartPart],
+    expected: &[StorageMultipartExpectedPart],
+) -> Result<(), AppError> {
+    if parts.len() != expected.len() {
+        return Err(AppError::validation(format!(
+            "{} parts are stored but {} were listed",
+            parts.len(),
+            expected.len()
+        )));
+    }
+    for expected in expected {
+        let stored = parts
+            .iter()
+            .find(|part| part.part_number == expected.part_number)
+            .ok_or_else(|| {
+                AppError::validation(format!("part {} was not uploaded", expected.part_number))
+            })?;
+        if !stored.sha256.eq_ignore_ascii_case(expected.sha256.trim()) {
+            return Err(AppError::validation(format!(
+                "part {} checksum mismatch: expected {}, stored {}",
+                expected.part_number, expected.sha256, stored.sha256
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn parts_dir(state: &AppState, upload_id: &str) -> PathBuf {
+    PathBuf::from(state.config.storage_dir.as_str())
+        .join(MULTIPART_DIR)
+        .join(upload_id)
+}
+
+fn part_file_name(part_number: u32) -> String {
+    format!("{:05}.part", part_number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_expected_parts, check_parts_complete, check_total_bytes};
+    use crate::api_models::storage::{StorageMultipartExpectedPart, StorageMultipartPart};
+
+    fn part(part_number: u32, sha256: &str) -> StorageMultipartPart {
+        StorageMultipartPart {
+            part_number,
+            byte_size: 1,
+            sha256: sha256.to_string(),
+        }
+    }
+
+    #[test]
+    fn completion_requires_contiguous_parts_and_matching_checksums() {
+        assert!(check_parts_complete(&[]).is_err());
+        assert!(check_parts_complete(&[part(1, "aa"), part(2, "bb")]).is_ok());
+        let gap = check_parts_complete(&[part(1, "aa"), part(3, "cc")]).unwrap_err();
+        assert!(gap.to_string().contains("missing part 2"));
+
+        let stored = [part(1, "aa"), part(2, "bb")];
+        let expected = |sha256: &str| {
+            vec![
+                StorageMultipartExpectedPart {
+                    part_number: 1,
+                    sha256: "AA".to_string(),
+                },
+                StorageMultipartExpectedPart {
+                    part_number: 2,
+                    sha256: sha256.to_string(),
+                },
+            ]
+        };
+        assert!(check_expected_parts(&stored, &expected("bb")).is_ok());
+        assert!(check_expected_parts(&stored, &expected("cc")).is_err());
+        assert!(check_expected_parts(&stored, &expected("bb")[..1]).is_err());
+    }
+
+    #[test]
+    fn total_size_may_reach_but_not_pass_the_limit() {
+        assert!(check_total_bytes(10, 10).is_ok());
+        assert!(check_total_bytes(11, 10).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use mesosphere_errors::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlPool, Row};
+use tokio::fs;
+use tracing::{info, warn};
+
+use crate::image_transform::{cached_storage_id, CACHE_DIR};
+use crate::routes::storage_multipart::MULTIPART_DIR;
+
+/// Minimum age before an unreferenced file is removed, so uploads still being committed survive.
+const ORPHAN_GRACE: Duration = Duration::from_secs(60 * 60);
+/// Rows deleted per statement when removing records whose file is gone.
+const DELETE_BATCH_SIZE: usize = 500;
+
+/// Outcome of one storage garbage-collection pass.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageGcReport {
+    /// Pending uploads whose token expired, removed with their placeholder rows.
+    pub expired_uploads: u64,
+    /// `_storage_files` rows removed because their file no longer exists.
+    pub missing_files: u64,
+    /// Files on disk removed because no `_storage_files` row references them.
+    pub orphan_files: u64,
+    /// Cached image transformations removed because their source object is gone.
+    pub stale_transforms: u64,
+    /// Part folders removed because their multipart upload expired or completed.
+    pub stale_multipart_uploads: u64,
+}
+
+/// Starts the periodic storage garbage collector; a zero interval disables it.
+pub fn spawn_storage_gc(pool: MySqlPool, storage_dir: PathBuf, interval_seconds: u32) {
+    if interval_seconds == 0 {
+        info!("storage garbage collection disabled");
+        return;
+    }
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(u64::from(interval_seconds)));
+        loop {
+            interval.tick().await;
+            match collect_storage_garbage(&pool, &storage_dir).await {
+                Ok(report) => info!(
+                    expired_uploads = report.expired_uploads,
+                    missing_files = report.missing_files,
+                    orphan_files = report.orphan_files,
+                    stale_transforms = report.stale_transforms,
+                    stale_multipart_uploads = report.stale_multipart_uploads,
+                    "storage garbage collection finished"
+                ),
+                Err(error) => warn!(error = %error, "storage garbage collection failed"),
+            }
+        }
+    });
+}
+
+/// Expires stale upload tokens, drops rows whose file is gone, and deletes unreferenced files.
+pub async fn collect_storage_garbage(
+    pool: &MySqlPool,
+    storage_dir: &Path,
+) -> Result<StorageGcReport, AppError> {
+    let mut report = StorageGcReport::default();
+
+    let expired = sqlx::query(
+        r#"
+        DELETE sf, ut
+        FROM _storage_upload_tokens ut
+        INNER JOIN _storage_files sf ON sf.id = ut.storage_id
+        WHERE ut.expires_at < UTC_TIMESTAMP(6)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+    // The multi-table delete counts both the token and the placeholder row.
+    report.expired_uploads = expired.rows_affected() / 2;
+
+    let uploaded = sqlx::query(
+        r#"
+        SELECT sf.id, sf.file_path
+        FROM _storage_files sf
+        LEFT JOIN _storage_upload_tokens ut ON ut.storage_id = sf.id
+        WHERE ut.token IS NULL
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    let mut missing_ids = Vec::<String>::new();
+    for row in &uploaded {
+        let id: String = row.try_get("id")?;
+        let file_name: String = row.try_get("file_path")?;
+        if !is_plain_file_name(&file_name) {
+            continue;
+        }
+        let exists = fs::try_exists(storage_dir.join(&file_name))
+            .await
+            .map_err(|error| {
+                AppError::internal(format!(
+                    "failed to check storage file '{}': {}",
+                    file_name, error
+                ))
+            })?;
+        if !exists {
+            missing_ids.push(id);
+        }
+    }
+    for batch in missing_ids.chunks(DELETE_BATCH_SIZE) {
+        let placeholders = std::iter::repeat_n("?", batch.len())
+            .collect::<Vec<&str>>()
+            .join(", ");
+        let sql = format!(
+            "DELETE FROM _storage_files WHERE id IN ({}) AND id NOT IN (SELECT storage_id FROM _storage_upload_tokens)",
+            placeholders
+        );
+        let mut query = sqlx::query(&sql);
+        for id in batch {
+            query = query.bind(id);
+        }
+        report.missing_files += query.execute(pool).await?.rows_affected();
+    }
+
+    let referenced = sqlx::query_scalar::<_, String>("SELECT file_path FROM _storage_files")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect::<HashSet<String>>();
+    let mut entries = match fs::read_dir(storage_dir).await {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(report),
+        Err(error) => {
+            return Err(AppError::internal(format!(
+                "failed to list storage directory '{}': {}",
+                storage_dir.display(),
+                error
+            )))
+        }
+    };
+    let now = SystemTime::now();
+    while let Some(entry) = entries.next_entry().await.map_err(|error| {
+        AppError::internal(format!(
+            "failed to list storage directory '{}': {}",
+            storage_dir.display(),
+            error
+        ))
+    })? {
+        let Ok(file_name) = entry.file_name().into_string() else {
+            continue;
+        };
+        let Ok(metadata) = entry.metadata().await else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        if !is_orphan(&file_name, &referenced, age) {
+            continue;
+        }
+        match fs::remove_file(entry.path()).await {
+            Ok(()) => report.orphan_files += 1,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => {
+                warn!(file = %file_name, error = %error, "failed to remove orphan storage file")
+            }
+        }
+    }
+
+    report.stale_transforms = remove_stale_transforms(pool, &storage_dir.join(CACHE_DIR)).await?;
+    report.stale_multipart_uploads =
+        remove_stale_multipart_parts(pool, &storage_dir.join(MULTIPART_DIR)).await?;
+    Ok(report)
+}
+
+/// Deletes part folders of multipart uploads that no longer exist or have expired.
+async fn remove_stale_multipart_parts(
+    pool: &MySqlPool,
+    multipart_dir: &Path,
+) -> Result<u64, AppError> {
+    let Ok(mut entries) = fs::read_dir(multipart_dir).await else {
+        return Ok(0);
+    };
+    sqlx::query("DELETE FROM _storage_multipart_uploads WHERE expires_at < UTC_TIMESTAMP(6)")
+        .execute(pool)
+        .await?;
+    let upload_ids =
+        sqlx::query_scalar::<_, String>("SELECT upload_id FROM _storage_multipart_uploads")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect::<HashSet<String>>();
+    let mut removed = 0_u64;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let Ok(upload_id) = entry.file_name().into_string() else {
+            continue;
+        };
+        if !upload_ids.contains(&upload_id) && fs::remove_dir_all(entry.path()).await.is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Deletes cached transformations whose source object no longer exists.
+async fn remove_stale_transforms(pool: &MySqlPool, cache_dir: &Path) -> Result<u64, AppError> {
+    let Ok(mut entries) = fs::read_dir(cache_dir).await else {
+        return Ok(0);
+    };
+    let storage_ids = sqlx::query_scalar::<_, String>("SELECT id FROM _storage_files")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect::<HashSet<String>>();
+    let mut removed = 0_u64;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let Ok(file_name) = entry.file_name().into_string() else {
+            continue;
+        };
+        let live = cached_storage_id(&file_name).is_some_and(|id| storage_ids.contains(id));
+        if !live && fs::remove_file(entry.path()).await.is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+fn is_plain_file_name(file_name: &str) -> bool {
+    !file_name.is_empty()
+        && !file_name.contains('/')
+        && !file_name.contains('\\')
+        && !file_name.contains("..")
+}
+
+/// Storage-owned files (`.bin` objects and `.bin.tmp` partial writes) that no row references
+/// and that are older than the grace period.
+fn is_orphan(file_name: &str, referenced: &HashSet<String>, age: Duration) -> bool {
+    (file_name.ends_with(".bin") || file_name.ends_with(".bin.tmp"))
+        && !referenced.contains(file_name)
+        && age >= ORPHAN_GRACE
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    use super::{is_orphan, ORPHAN_GRACE};
+
+    #[test]
+    fn only_old_unreferenced_storage_files_are_orphans() {
+        let referenced = HashSet::from(["kept.bin".to_string()]);
+        let old = ORPHAN_GRACE + Duration::from_secs(1);
+        assert!(is_orphan("lost.bin", &referenced, old));
+        assert!(is_orphan("lost.1234.bin.tmp", &referenced, old));
+        assert!(!is_orphan("kept.bin", &referenced, old));
+        assert!(!is_orphan("lost.bin", &referenced, Duration::from_secs(5)));
+        assert!(!is_orphan("notes.txt", &referenced, old));
+    }
+}
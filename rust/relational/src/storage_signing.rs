@@ -0,0 +1,66 @@
+use hmac::{Hmac, Mac};
+use mesosphere_errors::AppError;
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Lifetime of a signed URL when the caller does not choose one.
+pub const DEFAULT_SIGNED_URL_TTL_SECONDS: u64 = 15 * 60;
+/// Longest lifetime a signed URL may be issued for.
+pub const MAX_SIGNED_URL_TTL_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+/// Returns the hex HMAC-SHA256 signature granting access to `storage_id` until `expires`
+/// (Unix seconds).
+pub fn sign_storage_download(
+    secret: &str,
+    storage_id: &str,
+    expires: i64,
+) -> Result<String, AppError> {
+    Ok(hex::encode(
+        storage_mac(secret, storage_id, expires)?
+            .finalize()
+            .into_bytes(),
+    ))
+}
+
+/// Checks a download signature in constant time and rejects it once `now` passes `expires`.
+pub fn verify_storage_download(
+    secret: &str,
+    storage_id: &str,
+    expires: i64,
+    signature: &str,
+    now: i64,
+) -> Result<(), AppError> {
+    let signature = hex::decode(signature.trim())
+        .map_err(|_| AppError::unauthorized("storage URL signature is malformed"))?;
+    storage_mac(secret, storage_id, expires)?
+        .verify_slice(&signature)
+        .map_err(|_| AppError::unauthorized("storage URL signature is invalid"))?;
+    if now > expires {
+        return Err(AppError::unauthorized("storage URL has expired"));
+    }
+    Ok(())
+}
+
+fn storage_mac(secret: &str, storage_id: &str, expires: i64) -> Result<HmacSha256, AppError> {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .map_err(|error| AppError::internal(format!("invalid storage signing key: {}", error)))?;
+    mac.update(format!("storage-download\n{}\n{}", storage_id, expires).as_bytes());
+    Ok(mac)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sign_storage_download, verify_storage_download};
+
+    #[test]
+    fn signatures_bind_id_expiry_and_secret() {
+        let signature = sign_storage_download("secret", "file-1", 1_000).expect("sign");
+        assert!(verify_storage_download("secret", "file-1", 1_000, &signature, 999).is_ok());
+        assert!(verify_storage_download("secret", "file-1", 1_000, &signature, 1_001).is_err());
+        assert!(verify_storage_download("secret", "file-2", 1_000, &signature, 999).is_err());
+        assert!(verify_storage_download("secret", "file-1", 2_000, &signature, 999).is_err());
+        assert!(verify_storage_download("other", "file-1", 1_000, &signature, 999).is_err());
+        assert!(verify_storage_download("secret", "file-1", 1_000, "zz", 999).is_err());
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::env;
 use std::time::Instant;
 
+use axum::extract::MatchedPath;
 use axum::http::Request;
 use axum::middleware::Next;
 use axum::response::Response;
@@ -12,7 +13,7 @@ use opentelemetry_otlp::{SpanExporter, WithExportConfig, WithHttpConfig};
 use opentelemetry_sdk::trace::{self, Tracer};
 use opentelemetry_sdk::Resource;
 use mesosphere_errors::AppError;
-use mesosphere_metrics::capture_http_action;
+use mesosphere_metrics::{capture_http_action, record_route_latency};
 use tracing::{error, info, Instrument};
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
@@ -52,6 +53,11 @@ pub fn init_tracing(log_level: &str) -> Result<(), AppError> {
 pub async fn trace_http_action(request: Request<axum::body::Body>, next: Next) -> Response {
     let method = request.method().to_string();
     let path = request.uri().path().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|matched| matched.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
     let request_id = request
         .headers()
         .get("x-request-id")
@@ -65,6 +71,7 @@ pub async fn trace_http_action(request: Request<axum::body::Body>, next: Next) -
         action.type = %action_type,
         http.method = %method,
         http.path = %path,
+        http.route = %route,
         request.id = %request_id_value,
     );
 
@@ -81,6 +88,7 @@ pub async fn trace_http_action(request: Request<axum::body::Body>, next: Next) -
         info!(parent: &span, http.status_code = status, duration_ms, "request.completed");
     }
 
+    record_route_latency(&method, &route, status, duration_ms);
     capture_http_action(
         action_type,
         &method,
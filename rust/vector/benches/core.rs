@@ -0,0 +1,126 @@
+//! Micro-benchmarks for the CPU-bound vector paths: similarity scoring, embedding blob
+//! encoding and metadata filter compilation.
+//!
+//! Run with `cargo bench -p mesosphere-vector`, optionally followed by `-- <name filter>`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use mesosphere_vector::testing::datagen::{vector_items, VectorDatasetSpec};
+use mesosphere_vector::{
+    compile_metadata_filter, cosine_similarity_with_norms, decode_stored_embedding,
+    encode_embedding, encode_embedding_int8, vector_norm,
+};
+use serde_json::json;
+
+/// Embedding dimension used throughout (a common sentence-embedding size).
+const DIMENSION: usize = 384;
+/// Collection sizes scanned by the scoring benchmarks.
+const COLLECTION_SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+/// Measured samples per benchmark; the median is reported.
+const SAMPLES: usize = 10;
+/// Target duration of one sample.
+const SAMPLE_TIME: Duration = Duration::from_millis(50);
+
+/// Times `routine` and prints the median and fastest time per iteration.
+fn bench<R>(filter: Option<&str>, name: &str, mut routine: impl FnMut() -> R) {
+    if filter.is_some_and(|filter| !name.contains(filter)) {
+        return;
+    }
+    let mut iterations = 1_u64;
+    loop {
+        let started = Instant::now();
+        for _ in 0..iterations {
+            black_box(routine());
+        }
+        if started.elapsed() >= SAMPLE_TIME || iterations >= 1 << 30 {
+            break;
+        }
+        iterations *= 2;
+    }
+    let mut per_iteration = (0..SAMPLES)
+        .map(|_| {
+            let started = Instant::now();
+            for _ in 0..iterations {
+                black_box(routine());
+            }
+            started.elapsed() / iterations as u32
+        })
+        .collect::<Vec<Duration>>();
+    per_iteration.sort();
+    println!(
+        "{:<40} median {:>12?}   min {:>12?}   ({} iterations x {} samples)",
+        name,
+        per_iteration[SAMPLES / 2],
+        per_iteration[0],
+        iterations,
+        SAMPLES
+    );
+}
+
+/// Scores every item against the query and keeps the ten best, as an exact query does.
+fn top_ten(query: &[f32], items: &[(Vec<f32>, f64)]) -> Vec<f64> {
+    let query_norm = vector_norm(query);
+    let mut scores = items
+        .iter()
+        .map(|(item, norm)| cosine_similarity_with_norms(query, query_norm, item, *norm))
+        .collect::<Vec<f64>>();
+    scores.sort_by(|left, right| right.partial_cmp(left).unwrap_or(std::cmp::Ordering::Equal));
+    scores.truncate(10);
+    scores
+}
+
+fn main() {
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with('-'));
+    let filter = filter.as_deref();
+    // Seeded datasets keep runs comparable across machines.
+    let dataset = |seed: u64, items: usize| {
+        vector_items(&VectorDatasetSpec {
+            seed,
+            items,
+            dimension: DIMENSION,
+            document_words: 0,
+            ..VectorDatasetSpec::default()
+        })
+    };
+    let query = dataset(0, 1).remove(0).embedding;
+
+    for size in COLLECTION_SIZES {
+        let items = dataset(size as u64, size)
+            .into_iter()
+            .map(|item| {
+                let norm = vector_norm(&item.embedding);
+                (item.embedding, norm)
+            })
+            .collect::<Vec<(Vec<f32>, f64)>>();
+        bench(filter, &format!("query/exact_top10/{}", size), || {
+            top_ten(&query, &items)
+        });
+    }
+
+    let embedding = query.clone();
+    let f32_blob = encode_embedding(&embedding);
+    let int8_blob = encode_embedding_int8(&embedding);
+    bench(filter, "codec/encode_f32", || encode_embedding(&embedding));
+    bench(filter, "codec/encode_int8", || {
+        encode_embedding_int8(&embedding)
+    });
+    bench(filter, "codec/decode_f32", || {
+        decode_stored_embedding(&f32_blob, DIMENSION)
+    });
+    bench(filter, "codec/decode_int8", || {
+        decode_stored_embedding(&int8_blob, DIMENSION)
+    });
+    bench(filter, "codec/norm", || vector_norm(&embedding));
+
+    let where_filter = json!({
+        "$and": [
+            {"source": {"$in": ["crawl", "upload", "api"]}},
+            {"$or": [{"year": {"$gte": 2020}}, {"pinned": true}]},
+            {"author.name": {"$ne": "bot"}}
+        ]
+    });
+    bench(filter, "filters/compile_nested", || {
+        compile_metadata_filter(&where_filter)
+    });
+}
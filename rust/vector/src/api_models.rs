@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::codec::EmbeddingQuantization;
+use crate::repository::{
+    VectorFacet, VectorIdMode, VectorQueryExplain, VectorResultGroup, VectorScoredItem,
+};
+use crate::usage::{VectorQuotas, VectorUsage};
+
 /// Create-collection request payload.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateCollectionRequest {
@@ -9,6 +15,18 @@ pub struct CreateCollectionRequest {
     /// Optional collection metadata JSON.
     #[serde(default)]
     pub metadata: Option<Value>,
+    /// Optional strict item metadata schema (`{"key": {"type": "string", "required": true}}`).
+    #[serde(default)]
+    pub metadata_schema: Option<Value>,
+    /// Embedding storage encoding: `none` (default) or `int8`.
+    #[serde(default)]
+    pub quantization: EmbeddingQuantization,
+    /// Optional provider/model fingerprint (e.g. `openai:text-embedding-3-small`).
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+    /// Optional embedding dimension; otherwise fixed by the first write.
+    #[serde(default)]
+    pub embedding_dimension: Option<u32>,
 }
 
 /// Vector collection descriptor.
@@ -20,6 +38,14 @@ pub struct CollectionResponse {
     pub name: String,
     /// Collection metadata.
     pub metadata: Option<Value>,
+    /// Declared item metadata schema.
+    pub metadata_schema: Option<Value>,
+    /// Embedding storage encoding.
+    pub quantization: EmbeddingQuantization,
+    /// Provider/model fingerprint of the collection's embeddings.
+    pub embedding_model: Option<String>,
+    /// Embedding dimension shared by every item and query.
+    pub embedding_dimension: Option<u32>,
     /// Creation timestamp.
     pub created_at: String,
     /// Last update timestamp.
@@ -32,7 +58,11 @@ pub struct VectorItemInput {
     /// Optional item id (generated when omitted).
     #[serde(default)]
     pub id: Option<String>,
-    /// Embedding vector values.
+    /// Optional id of the logical document this chunk belongs to.
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// Embedding vector values; omit to embed `document` on the server.
+    #[serde(default)]
     pub embedding: Vec<f32>,
     /// Optional document text.
     #[serde(default)]
@@ -40,6 +70,15 @@ pub struct VectorItemInput {
     /// Optional metadata JSON.
     #[serde(default)]
     pub metadata: Option<Value>,
+    /// Optional lifetime in seconds after which the item expires.
+    #[serde(default)]
+    pub ttl_seconds: Option<u64>,
+    /// Optional source URI.
+    #[serde(default)]
+    pub uri: Option<String>,
+    /// Optional typed payload columns (`{"source": "crawl", "chunk_index": 3}`).
+    #[serde(default)]
+    pub data: Option<Value>,
 }
 
 /// Item payload used for vector update operations.
@@ -47,6 +86,9 @@ pub struct VectorItemInput {
 pub struct VectorItemUpdate {
     /// Existing item id.
     pub id: String,
+    /// Optional replacement parent id.
+    #[serde(default)]
+    pub parent_id: Option<String>,
     /// Optional replacement embedding.
     #[serde(default)]
     pub embedding: Option<Vec<f32>>,
@@ -56,6 +98,15 @@ pub struct VectorItemUpdate {
     /// Optional replacement metadata.
     #[serde(default)]
     pub metadata: Option<Value>,
+    /// Optional new lifetime in seconds, counted from the update.
+    #[serde(default)]
+    pub ttl_seconds: Option<u64>,
+    /// Optional replacement URI.
+    #[serde(default)]
+    pub uri: Option<String>,
+    /// Optional replacement data columns.
+    #[serde(default)]
+    pub data: Option<Value>,
 }
 
 /// Add-item request body.
@@ -63,6 +114,79 @@ pub struct VectorItemUpdate {
 pub struct VectorAddItemsRequest {
     /// Items to persist.
     pub items: Vec<VectorItemInput>,
+    /// Optional namespace the items are written to.
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// Optional server-side embedding model override (must be allowed by the deployment).
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+    /// Rows per insert statement; the whole request still commits in one transaction.
+    #[serde(default)]
+    pub chunk_size: Option<usize>,
+    /// How ids are generated for items without one: `uuid` (default) or `content_hash`.
+    #[serde(default)]
+    pub id_mode: VectorIdMode,
+}
+
+/// Upsert-item request body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorUpsertItemsRequest {
+    /// Items to insert or replace by id.
+    pub items: Vec<VectorItemInput>,
+    /// Optional namespace the items are written to.
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// Optional server-side embedding model override (must be allowed by the deployment).
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+}
+
+/// Upsert-item response payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorUpsertItemsResponse {
+    /// Number of newly inserted items.
+    pub inserted: u64,
+    /// Number of replaced items.
+    pub updated: u64,
+    /// Newly inserted ids.
+    pub inserted_ids: Vec<String>,
+    /// Replaced ids.
+    pub updated_ids: Vec<String>,
+}
+
+/// Bulk upsert line that could not be stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorBulkLineError {
+    /// 1-based line number in the request body.
+    pub line: u64,
+    /// Reason the line was rejected.
+    pub error: String,
+}
+
+/// Bulk upsert result summary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorBulkUpsertResponse {
+    /// Lines read from the body, blank ones included.
+    pub lines: u64,
+    /// Number of newly inserted items.
+    pub inserted: u64,
+    /// Number of replaced items.
+    pub updated: u64,
+    /// Number of item lines that were not stored.
+    pub failed: u64,
+    /// Transactions committed.
+    pub chunks: u64,
+    /// First failures, capped to keep the response small.
+    pub errors: Vec<VectorBulkLineError>,
+}
+
+/// Usage of the calling API key with the deployment's quotas.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorUsageResponse {
+    /// Recorded usage.
+    pub usage: VectorUsage,
+    /// Configured limits; `0` means unlimited.
+    pub quotas: VectorQuotas,
 }
 
 /// Update-item request body.
@@ -78,6 +202,12 @@ pub struct VectorDeleteItemsRequest {
     /// Item ids to delete.
     #[serde(default)]
     pub ids: Vec<String>,
+    /// Optional metadata filter; combined with `ids` when both are sent.
+    #[serde(default, rename = "where")]
+    pub filter: Option<Value>,
+    /// Optional namespace; only items inside it are deleted.
+    #[serde(default)]
+    pub namespace: Option<String>,
 }
 
 /// Get-item request body.
@@ -86,14 +216,237 @@ pub struct VectorGetItemsRequest {
     /// Optional ids filter.
     #[serde(default)]
     pub ids: Vec<String>,
+    /// Optional metadata filter.
+    #[serde(default, rename = "where")]
+    pub filter: Option<Value>,
+    /// Optional namespace to read from.
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// Optional extra fields to return (`uris`, `data`, `embeddings`).
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Optional maximum number of items, applied in SQL.
+    #[serde(default)]
+    pub limit: Option<u64>,
+    /// Optional number of matching items to skip, applied in SQL.
+    #[serde(default)]
+    pub offset: Option<u64>,
+}
+
+/// Ordered batch-get request body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorBatchGetRequest {
+    /// Ids to fetch; results follow this order.
+    pub ids: Vec<String>,
+    /// Optional extra fields to return (`uris`, `data`, `embeddings`).
+    #[serde(default)]
+    pub include: Vec<String>,
+}
+
+/// Ordered batch-get response payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorBatchGetResponse {
+    /// Found items in request order.
+    pub items: Vec<VectorItemResponse>,
+    /// Requested ids that do not exist.
+    pub missing: Vec<String>,
+}
+
+/// Existence-check request body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorExistsRequest {
+    /// Item id to look up.
+    pub id: String,
+}
+
+/// Existence-check response payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorExistsResponse {
+    /// Whether the id is stored in the collection.
+    pub exists: bool,
+}
+
+/// Count request body.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct VectorCountRequest {
+    /// Optional metadata filter.
+    #[serde(default, rename = "where")]
+    pub filter: Option<Value>,
+}
+
+/// Count response payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorCountResponse {
+    /// Number of matching items.
+    pub count: u64,
+}
+
+/// Scroll request body.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct VectorScrollRequest {
+    /// Cursor returned by the previous page (omit for the first page).
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Page size (defaults to 100, capped at 1000).
+    #[serde(default)]
+    pub batch_size: Option<u32>,
+    /// Optional metadata filter.
+    #[serde(default, rename = "where")]
+    pub filter: Option<Value>,
+}
+
+/// Facet request body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorFacetsRequest {
+    /// Metadata keys to count values for.
+    pub keys: Vec<String>,
+    /// Optional metadata filter restricting counted items.
+    #[serde(default, rename = "where")]
+    pub filter: Option<Value>,
+    /// Maximum distinct values per key (defaults to 20).
+    #[serde(default)]
+    pub limit: Option<u32>,
 }
 
 /// Vector query request body.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VectorQueryRequest {
     /// Query vectors.
+    #[serde(default)]
     pub query_embeddings: Vec<Vec<f32>>,
-    /// Top-k results per query.
+    /// Query texts embedded on the server instead of `query_embeddings`.
+    #[serde(default)]
+    pub query_texts: Vec<String>,
+    /// Optional server-side embedding model override (must be allowed by the deployment).
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+    /// Top-k results per query (top-k groups when `group_by` is set).
+    #[serde(default)]
+    pub n_results: Option<u32>,
+    /// Optional metadata filter applied before scoring.
+    #[serde(default, rename = "where")]
+    pub filter: Option<Value>,
+    /// Optional metadata key to group results by (for example `metadata.source`).
+    #[serde(default)]
+    pub group_by: Option<String>,
+    /// Maximum items per group (defaults to 1).
+    #[serde(default)]
+    pub group_size: Option<u32>,
+    /// Optional Maximal Marginal Relevance re-ranking.
+    #[serde(default)]
+    pub mmr: Option<VectorMmrOptions>,
+    /// Return only the best-scoring chunk per parent id.
+    #[serde(default)]
+    pub dedupe_by_parent: bool,
+    /// Optional namespace to search in.
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// Optional extra fields to return (`uris`, `data`, `embeddings`).
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Return an execution trace (candidate counts and timings) with the results.
+    #[serde(default)]
+    pub explain: bool,
+}
+
+/// MMR re-ranking options.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorMmrOptions {
+    /// Relevance/diversity trade-off in `[0, 1]` (defaults to 0.5).
+    #[serde(default)]
+    pub lambda: Option<f64>,
+    /// Candidate pool size (defaults to 4 * n_results).
+    #[serde(default)]
+    pub fetch_k: Option<u32>,
+}
+
+/// Full-text search request body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorTextSearchRequest {
+    /// Keyword query matched against item documents.
+    pub query: String,
+    /// Maximum number of hits.
+    #[serde(default)]
+    pub n_results: Option<u32>,
+    /// Optional metadata filter.
+    #[serde(default, rename = "where")]
+    pub filter: Option<Value>,
+}
+
+/// Full-text search response payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorTextSearchResponse {
+    /// Matching items, best match first.
+    pub items: Vec<VectorItemResponse>,
+    /// Relevance score per item.
+    pub scores: Vec<f64>,
+}
+
+/// Scored scan request body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorScanRequest {
+    /// Query vector.
+    pub query_embedding: Vec<f32>,
+    /// Cursor returned by the previous page (omit for the first page).
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Page size (defaults to 100, capped at 1000).
+    #[serde(default)]
+    pub batch_size: Option<u32>,
+    /// Optional minimum cosine similarity for returned items.
+    #[serde(default)]
+    pub min_similarity: Option<f64>,
+    /// Optional metadata filter.
+    #[serde(default, rename = "where")]
+    pub filter: Option<Value>,
+}
+
+/// Scored scan response payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorScanResponse {
+    /// Scored items of this page in id order.
+    pub items: Vec<VectorScoredItem>,
+    /// Cursor for the next page, or null when finished.
+    pub next_cursor: Option<String>,
+}
+
+/// Hybrid (vector + keyword) query request body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorHybridQueryRequest {
+    /// Query vector.
+    pub query_embedding: Vec<f32>,
+    /// Keyword query matched against item documents.
+    pub query_text: String,
+    /// Weight of vector similarity versus keyword relevance (defaults to 0.5).
+    #[serde(default)]
+    pub alpha: Option<f64>,
+    /// Top-k results.
+    #[serde(default)]
+    pub n_results: Option<u32>,
+    /// Optional metadata filter applied before scoring.
+    #[serde(default, rename = "where")]
+    pub filter: Option<Value>,
+}
+
+/// Query-by-ids request body ("more like these").
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorQueryByIdsRequest {
+    /// Seed item ids whose centroid is used as the query vector.
+    pub ids: Vec<String>,
+    /// Top-k results (seed ids are excluded).
+    #[serde(default)]
+    pub n_results: Option<u32>,
+}
+
+/// Recommendation request body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorRecommendRequest {
+    /// Ids of items the results should resemble.
+    pub positive_ids: Vec<String>,
+    /// Ids of items the results should move away from.
+    #[serde(default)]
+    pub negative_ids: Vec<String>,
+    /// Top-k results (example ids are excluded).
     #[serde(default)]
     pub n_results: Option<u32>,
 }
@@ -103,10 +456,37 @@ pub struct VectorQueryRequest {
 pub struct VectorItemResponse {
     /// Item id.
     pub id: String,
+    /// Parent document id.
+    pub parent_id: Option<String>,
     /// Optional document text.
     pub document: Option<String>,
     /// Optional metadata JSON.
     pub metadata: Option<Value>,
+    /// Source URI (present when `uris` is included).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// Data columns (present when `data` is included).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    /// Stored embedding (present when `embeddings` is included).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+}
+
+/// Scroll response payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorScrollResponse {
+    /// Items in this page ordered by id.
+    pub items: Vec<VectorItemResponse>,
+    /// Cursor for the next page, or null when finished.
+    pub next_cursor: Option<String>,
+}
+
+/// Facet response payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorFacetsResponse {
+    /// Value counts per requested key, in request order.
+    pub facets: Vec<VectorFacet>,
 }
 
 /// Vector query response payload.
@@ -114,10 +494,27 @@ pub struct VectorItemResponse {
 pub struct VectorQueryResponse {
     /// Result ids grouped by query index.
     pub ids: Vec<Vec<String>>,
+    /// Result parent ids grouped by query index.
+    pub parent_ids: Vec<Vec<Option<String>>>,
     /// Result documents grouped by query index.
     pub documents: Vec<Vec<Option<String>>>,
     /// Result metadata grouped by query index.
     pub metadatas: Vec<Vec<Option<Value>>>,
     /// Result distances grouped by query index.
     pub distances: Vec<Vec<f64>>,
+    /// Metadata groups by query index (present only for `group_by` queries).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<Vec<VectorResultGroup>>>,
+    /// Result URIs grouped by query index (present when `uris` is included).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uris: Option<Vec<Vec<Option<String>>>>,
+    /// Result data columns grouped by query index (present when `data` is included).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<Vec<Option<Value>>>>,
+    /// Result embeddings grouped by query index (present when `embeddings` is included).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embeddings: Option<Vec<Vec<Option<Vec<f32>>>>>,
+    /// Execution trace (present when `explain` was requested).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explain: Option<VectorQueryExplain>,
 }
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use mesosphere_errors::AppError;
+
+/// One item line in a bulk NDJSON upsert body.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VectorBulkRecord {
+    /// Optional item id; generated when omitted.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Optional namespace partition.
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// Optional parent document id.
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// Embedding values; when absent, the server embeds the line's document with its
+    /// configured embedder, and lines with neither fail.
+    #[serde(default)]
+    pub embedding: Option<Vec<f32>>,
+    /// Optional document.
+    #[serde(default)]
+    pub document: Option<String>,
+    /// Optional metadata.
+    #[serde(default)]
+    pub metadata: Option<Value>,
+    /// Optional lifetime in seconds.
+    #[serde(default)]
+    pub ttl_seconds: Option<u64>,
+    /// Optional source URI.
+    #[serde(default)]
+    pub uri: Option<String>,
+    /// Optional data columns.
+    #[serde(default)]
+    pub data: Option<Value>,
+}
+
+/// Splits a byte stream into complete newline-terminated lines.
+#[derive(Debug, Default)]
+pub struct NdjsonLines {
+    buffer: Vec<u8>,
+    line_number: usize,
+}
+
+impl NdjsonLines {
+    /// Appends a body chunk and returns the non-blank lines it completes with their 1-based
+    /// line numbers.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Vec<(usize, String)>, AppError> {
+        self.buffer.extend_from_slice(chunk);
+        let Some(end) = self.buffer.iter().rposition(|byte| *byte == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let complete = self.buffer.drain(..=end).collect::<Vec<u8>>();
+        let mut lines = Vec::new();
+        for line in complete[..complete.len() - 1].split(|byte| *byte == b'\n') {
+            self.line_number += 1;
+            self.take_line(line, &mut lines)?;
+        }
+        Ok(lines)
+    }
+
+    /// Returns the trailing line when the body does not end with a newline.
+    pub fn finish(&mut self) -> Result<Vec<(usize, String)>, AppError> {
+        let mut lines = Vec::new();
+        if !self.buffer.is_empty() {
+            let line = std::mem::take(&mut self.buffer);
+            self.line_number += 1;
+            self.take_line(&line, &mut lines)?;
+        }
+        Ok(lines)
+    }
+
+    /// Number of lines consumed so far, blank ones included.
+    pub fn line_count(&self) -> usize {
+        self.line_number
+    }
+
+    fn take_line(&self, line: &[u8], lines: &mut Vec<(usize, String)>) -> Result<(), AppError> {
+        let text = std::str::from_utf8(line).map_err(|_| {
+            AppError::validation(format!("line {} is not valid UTF-8", self.line_number))
+        })?;
+        let text = text.trim();
+        if !text.is_empty() {
+            lines.push((self.line_number, text.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Parses one bulk line into a record.
+pub fn parse_bulk_line(line: &str) -> Result<VectorBulkRecord, String> {
+    serde_json::from_str::<VectorBulkRecord>(line).map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_bulk_line, NdjsonLines};
+
+    #[test]
+    fn lines_split_across_chunks_are_reassembled() {
+        let mut lines = NdjsonLines::default();
+        let mut output = lines.push(b"{\"id\":\"a\"}\n{\"id\"").expect("push");
+        output.extend(lines.push(b":\"b\"}\n\n{\"id\":\"c\"}").expect("push"));
+        output.extend(lines.finish().expect("finish"));
+
+        assert_eq!(
+            output,
+            vec![
+                (1, "{\"id\":\"a\"}".to_string()),
+                (2, "{\"id\":\"b\"}".to_string()),
+                (4, "{\"id\":\"c\"}".to_string()),
+            ]
+        );
+        assert_eq!(lines.line_count(), 4);
+        assert!(parse_bulk_line(&output[0].1)
+            .expect("parse")
+            .embedding
+            .is_none());
+    }
+
+    #[test]
+    fn multibyte_characters_may_straddle_chunks() {
+        let text = "{\"document\":\"caf\u{e9}\"}\n".as_bytes();
+        let mut lines = NdjsonLines::default();
+        assert!(lines.push(&text[..17]).expect("push").is_empty());
+        let output = lines.push(&text[17..]).expect("push");
+        assert_eq!(
+            parse_bulk_line(&output[0].1).expect("parse").document,
+            Some("caf\u{e9}".to_string())
+        );
+    }
+}
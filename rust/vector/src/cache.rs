@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use chrono::NaiveDateTime;
+
+use crate::repository::VectorCandidate;
+
+/// Snapshot of a collection's live rows used to detect stale cache entries.
+///
+/// Inserts and updates move `max_updated_at` forward, while deletes and expiries change
+/// `live_count`, so a matching token means the cached rows are still current, including
+/// after writes made by other server processes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CacheToken {
+    pub(crate) live_count: i64,
+    pub(crate) max_updated_at: Option<NaiveDateTime>,
+}
+
+struct CacheEntry<T> {
+    token: CacheToken,
+    values: Arc<Vec<T>>,
+    bytes: usize,
+    last_used: u64,
+}
+
+/// Least-recently-used cache of decoded rows per collection, bounded by a byte budget.
+pub(crate) struct CollectionCache<T> {
+    entries: HashMap<String, CacheEntry<T>>,
+    total_bytes: usize,
+    clock: u64,
+}
+
+impl<T> CollectionCache<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            total_bytes: 0,
+            clock: 0,
+        }
+    }
+
+    /// Returns cached rows when the entry was stored under the same token.
+    pub(crate) fn get(&mut self, collection_id: &str, token: &CacheToken) -> Option<Arc<Vec<T>>> {
+        self.clock += 1;
+        let clock = self.clock;
+        match self.entries.get_mut(collection_id) {
+            Some(entry) if entry.token == *token => {
+                entry.last_used = clock;
+                Some(entry.values.clone())
+            }
+            Some(_) => {
+                self.remove(collection_id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Stores rows and evicts least recently used collections until the budget holds.
+    /// Collections larger than the whole budget are not cached.
+    pub(crate) fn insert(
+        &mut self,
+        collection_id: &str,
+        token: CacheToken,
+        values: Arc<Vec<T>>,
+        bytes: usize,
+        budget_bytes: usize,
+    ) {
+        self.remove(collection_id);
+        if bytes > budget_bytes {
+            return;
+        }
+        while self.total_bytes + bytes > budget_bytes {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.remove(&oldest);
+        }
+        self.clock += 1;
+        self.total_bytes += bytes;
+        self.entries.insert(
+            collection_id.to_string(),
+            CacheEntry {
+                token,
+                values,
+                bytes,
+                last_used: self.clock,
+            },
+        );
+    }
+
+    fn remove(&mut self, collection_id: &str) {
+        if let Some(entry) = self.entries.remove(collection_id) {
+            self.total_bytes -= entry.bytes;
+        }
+    }
+}
+
+/// Process-wide cache of decoded query candidates.
+pub(crate) fn candidate_cache() -> &'static Mutex<CollectionCache<VectorCandidate>> {
+    static CACHE: OnceLock<Mutex<CollectionCache<VectorCandidate>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(CollectionCache::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CacheToken, CollectionCache};
+    use std::sync::Arc;
+
+    fn token(live_count: i64) -> CacheToken {
+        CacheToken {
+            live_count,
+            max_updated_at: None,
+        }
+    }
+
+    #[test]
+    fn stale_tokens_miss_and_budget_evicts_least_recently_used() {
+        let mut cache = CollectionCache::<u32>::new();
+        cache.insert("a", token(1), Arc::new(vec![1]), 40, 100);
+        cache.insert("b", token(1), Arc::new(vec![2]), 40, 100);
+        assert!(cache.get("a", &token(1)).is_some());
+        assert!(cache.get("b", &token(2)).is_none());
+        assert!(cache.get("b", &token(1)).is_none());
+
+        cache.insert("b", token(2), Arc::new(vec![2]), 40, 100);
+        assert!(cache.get("a", &token(1)).is_some());
+        cache.insert("c", token(1), Arc::new(vec![3]), 40, 100);
+        assert!(cache.get("b", &token(2)).is_none());
+        assert!(cache.get("a", &token(1)).is_some());
+        assert!(cache.get("c", &token(1)).is_some());
+
+        cache.insert("huge", token(1), Arc::new(vec![4]), 101, 100);
+        assert!(cache.get("huge", &token(1)).is_none());
+        assert_eq!(cache.total_bytes, 80);
+    }
+}
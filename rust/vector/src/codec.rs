@@ -1,4 +1,6 @@
-﻿/// Encodes a vector of `f32` values into a compact little-endian byte buffer.
+﻿use serde::{Deserialize, Serialize};
+
+/// Encodes a vector of `f32` values into a compact little-endian byte buffer.
 pub fn encode_embedding(values: &[f32]) -> Vec<u8> {
     let mut bytes = Vec::with_capacity(std::mem::size_of_val(values));
     for value in values {
@@ -9,7 +11,7 @@ pub fn encode_embedding(values: &[f32]) -> Vec<u8> {
 
 /// Decodes a little-endian byte buffer into `f32` values.
 pub fn decode_embedding(bytes: &[u8]) -> Result<Vec<f32>, String> {
-    if bytes.len() % std::mem::size_of::<f32>() != 0 {
+    if !bytes.len().is_multiple_of(std::mem::size_of::<f32>()) {
         return Err("embedding blob length is not a multiple of 4".to_string());
     }
 
@@ -32,9 +34,98 @@ pub fn vector_norm(values: &[f32]) -> f64 {
     squared_sum.sqrt()
 }
 
+/// Storage encoding for embeddings, chosen per collection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingQuantization {
+    /// Full-precision little-endian `f32` values (4 bytes per dimension).
+    #[default]
+    None,
+    /// Symmetric int8 scalar quantization: one `f32` scale followed by one byte per dimension.
+    Int8,
+}
+
+impl EmbeddingQuantization {
+    /// Returns the storage spelling of the encoding.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Int8 => "int8",
+        }
+    }
+
+    /// Parses the storage spelling of the encoding.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "none" => Ok(Self::None),
+            "int8" => Ok(Self::Int8),
+            other => Err(format!("unsupported embedding quantization '{}'", other)),
+        }
+    }
+
+    /// Encodes an embedding and returns the blob with the norm of the stored values.
+    ///
+    /// For quantized encodings the norm is taken from the dequantized vector, so cosine
+    /// scores stay consistent with what is read back.
+    pub fn encode(self, values: &[f32]) -> (Vec<u8>, f64) {
+        match self {
+            Self::None => (encode_embedding(values), vector_norm(values)),
+            Self::Int8 => {
+                let blob = encode_embedding_int8(values);
+                let norm = vector_norm(&decode_embedding_int8(&blob));
+                (blob, norm)
+            }
+        }
+    }
+}
+
+/// Quantizes values to int8 with a single symmetric scale (`max |v| / 127`).
+pub fn encode_embedding_int8(values: &[f32]) -> Vec<u8> {
+    let max_abs = values
+        .iter()
+        .fold(0.0_f32, |current, value| current.max(value.abs()));
+    let scale = if max_abs > 0.0 { max_abs / 127.0 } else { 0.0 };
+    let mut bytes = Vec::with_capacity(std::mem::size_of::<f32>() + values.len());
+    bytes.extend_from_slice(&scale.to_le_bytes());
+    for value in values {
+        let quantized = if scale > 0.0 {
+            (value / scale).round().clamp(-127.0, 127.0) as i8
+        } else {
+            0
+        };
+        bytes.push(quantized as u8);
+    }
+    bytes
+}
+
+fn decode_embedding_int8(bytes: &[u8]) -> Vec<f32> {
+    let scale = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    bytes[std::mem::size_of::<f32>()..]
+        .iter()
+        .map(|byte| *byte as i8 as f32 * scale)
+        .collect()
+}
+
+/// Decodes a stored embedding blob of `dimension` values in either storage encoding.
+///
+/// The encoding is recovered from the blob length: `4 * dimension` bytes for `f32` and
+/// `4 + dimension` bytes for int8, which never coincide for a whole dimension.
+pub fn decode_stored_embedding(bytes: &[u8], dimension: usize) -> Result<Vec<f32>, String> {
+    if bytes.len() == dimension * std::mem::size_of::<f32>() {
+        decode_embedding(bytes)
+    } else if bytes.len() == dimension + std::mem::size_of::<f32>() {
+        Ok(decode_embedding_int8(bytes))
+    } else {
+        Err("vector blob length and embedding_dim mismatch".to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{decode_embedding, encode_embedding, vector_norm};
+    use super::{
+        decode_embedding, decode_stored_embedding, encode_embedding, encode_embedding_int8,
+        vector_norm, EmbeddingQuantization,
+    };
 
     #[test]
     fn round_trip_codec_and_norm() {
@@ -44,4 +135,27 @@ mod tests {
         assert_eq!(values, decoded);
         assert!(vector_norm(&values) > 0.0);
     }
+
+    #[test]
+    fn int8_quantization_round_trips_within_one_step() {
+        let values = vec![0.5_f32, -1.0, 0.25, 0.0, 0.999];
+        let (blob, norm) = EmbeddingQuantization::Int8.encode(&values);
+        assert_eq!(blob.len(), 4 + values.len());
+
+        let decoded = decode_stored_embedding(&blob, values.len()).expect("decode");
+        let step = 1.0 / 127.0;
+        assert!(values
+            .iter()
+            .zip(&decoded)
+            .all(|(original, restored)| (original - restored).abs() <= step));
+        assert!((norm - vector_norm(&decoded)).abs() < 1e-12);
+
+        let (full, _) = EmbeddingQuantization::None.encode(&values);
+        assert_eq!(decode_stored_embedding(&full, values.len()), Ok(values));
+        assert!(decode_stored_embedding(&full, 3).is_err());
+        assert_eq!(
+            decode_stored_embedding(&encode_embedding_int8(&[0.0, 0.0]), 2),
+            Ok(vec![0.0, 0.0])
+        );
+    }
 }
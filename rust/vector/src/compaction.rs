@@ -0,0 +1,77 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Process-wide tracker deciding when deletes warrant an automatic compaction.
+pub static COMPACTION: CompactionTracker = CompactionTracker::new();
+
+/// Counts deleted rows since the last compaction and guards against concurrent runs.
+#[derive(Debug)]
+pub struct CompactionTracker {
+    deleted_rows: AtomicU64,
+    running: AtomicBool,
+}
+
+impl CompactionTracker {
+    /// Creates an idle tracker.
+    pub const fn new() -> Self {
+        Self {
+            deleted_rows: AtomicU64::new(0),
+            running: AtomicBool::new(false),
+        }
+    }
+
+    /// Adds deleted rows and returns `true` when `threshold` is reached and no compaction is
+    /// running, in which case the caller owns the run and must call `finish`. A zero
+    /// threshold disables auto-compaction.
+    pub fn record_deletions(&self, rows: u64, threshold: u64) -> bool {
+        if threshold == 0 || rows == 0 {
+            return false;
+        }
+        let deleted = self.deleted_rows.fetch_add(rows, Ordering::Relaxed) + rows;
+        if deleted < threshold {
+            return false;
+        }
+        if self
+            .running
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            return false;
+        }
+        self.deleted_rows.store(0, Ordering::Relaxed);
+        true
+    }
+
+    /// Marks the claimed compaction run as finished.
+    pub fn finish(&self) {
+        self.running.store(false, Ordering::Release);
+    }
+}
+
+impl Default for CompactionTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompactionTracker;
+
+    #[test]
+    fn claims_one_run_once_deletes_reach_threshold() {
+        let tracker = CompactionTracker::new();
+        assert!(!tracker.record_deletions(60, 100));
+        assert!(tracker.record_deletions(40, 100));
+        assert!(!tracker.record_deletions(500, 100), "a run is in progress");
+        tracker.finish();
+        assert!(
+            tracker.record_deletions(1, 100),
+            "deletes during the run still count"
+        );
+        tracker.finish();
+        assert!(
+            !tracker.record_deletions(1_000, 0),
+            "zero disables auto-compaction"
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use serde_json::json;
+
+use mesosphere_application::state::AppState;
+use mesosphere_errors::AppError;
+
+use crate::codec::EmbeddingQuantization;
+use crate::repository::{VectorRepository, DEFAULT_INSERT_CHUNK_SIZE};
+use crate::testing::datagen::{vector_items, VectorDatasetSpec};
+
+/// Collection populated when the backend runs in demo mode.
+pub const DEMO_COLLECTION: &str = "demo_articles";
+/// Items generated into the demo collection.
+const DEMO_ITEMS: usize = 500;
+/// Embedding dimension of the demo collection.
+const DEMO_DIMENSION: usize = 64;
+
+/// Creates and fills the demo collection on first start; returns `false` when it already exists.
+pub async fn seed_demo_data(state: &AppState) -> Result<bool, AppError> {
+    let repository = VectorRepository::new(state.pool.clone(), state.config.vector_max_dim);
+    match repository.collection(DEMO_COLLECTION).await {
+        Ok(_) => return Ok(false),
+        Err(AppError::NotFound(_)) => {}
+        Err(error) => return Err(error),
+    }
+    repository
+        .create_collection(
+            DEMO_COLLECTION,
+            Some(json!({
+                "demo": true,
+                "description": "Generated sample articles for exploring Mesosphere",
+            })),
+            None,
+            EmbeddingQuantization::None,
+            None,
+            Some(DEMO_DIMENSION as u32),
+        )
+        .await?;
+    let items = vector_items(&VectorDatasetSpec {
+        items: DEMO_ITEMS,
+        dimension: DEMO_DIMENSION,
+        ..VectorDatasetSpec::default()
+    });
+    repository
+        .add_items(DEMO_COLLECTION, &items, DEFAULT_INSERT_CHUNK_SIZE)
+        .await?;
+    Ok(true)
+}
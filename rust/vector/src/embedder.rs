@@ -0,0 +1,302 @@
+use std::env;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use reqwest::Client;
+use serde_json::{json, Value};
+use tracing::{debug, info};
+
+use mesosphere_errors::AppError;
+
+static EMBEDDER: OnceLock<ServerEmbedder> = OnceLock::new();
+
+/// Hosted embedding API used for server-side embedding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingProvider {
+    /// OpenAI-compatible `/embeddings` API.
+    OpenAi,
+    /// Ollama `/api/embed` API.
+    Ollama,
+}
+
+impl EmbeddingProvider {
+    fn parse(value: &str) -> Result<Self, AppError> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "openai" => Ok(Self::OpenAi),
+            "ollama" => Ok(Self::Ollama),
+            other => Err(AppError::config(format!(
+                "MESOSPHERE_EMBEDDING_PROVIDER '{}' is not supported (use openai or ollama)",
+                other
+            ))),
+        }
+    }
+
+    fn default_base_url(self) -> &'static str {
+        match self {
+            Self::OpenAi => "https://api.openai.com/v1",
+            Self::Ollama => "http://localhost:11434",
+        }
+    }
+}
+
+/// Runtime configuration for server-side embedding.
+#[derive(Debug, Clone)]
+pub struct EmbedderConfig {
+    /// Provider called for embeddings; `None` disables server-side embedding.
+    pub provider: Option<EmbeddingProvider>,
+    /// Default model.
+    pub model: String,
+    /// Provider API key.
+    pub api_key: Option<String>,
+    /// Provider base URL.
+    pub base_url: String,
+    /// Models requests may select instead of the default.
+    pub allowed_models: Vec<String>,
+    /// Outbound request timeout.
+    pub request_timeout_ms: u64,
+}
+
+impl EmbedderConfig {
+    /// Loads server-side embedding settings from environment variables.
+    pub fn from_env() -> Result<Self, AppError> {
+        let provider = env::var("MESOSPHERE_EMBEDDING_PROVIDER")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .map(|value| EmbeddingProvider::parse(&value))
+            .transpose()?;
+        let model = env::var("MESOSPHERE_EMBEDDING_MODEL").unwrap_or_default();
+        if provider.is_some() && model.trim().is_empty() {
+            return Err(AppError::config(
+                "MESOSPHERE_EMBEDDING_MODEL is required when MESOSPHERE_EMBEDDING_PROVIDER is set",
+            ));
+        }
+        let base_url = env::var("MESOSPHERE_EMBEDDING_BASE_URL")
+            .ok()
+            .or_else(|| provider.map(|provider| provider.default_base_url().to_string()))
+            .unwrap_or_default()
+            .trim_end_matches('/')
+            .to_string();
+        let allowed_models = env::var("MESOSPHERE_EMBEDDING_ALLOWED_MODELS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|model| !model.is_empty())
+            .map(ToOwned::to_owned)
+            .collect::<Vec<String>>();
+        let request_timeout_ms = env::var("MESOSPHERE_EMBEDDING_TIMEOUT_MS")
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(30_000);
+
+        Ok(Self {
+            provider,
+            model,
+            api_key: env::var("MESOSPHERE_EMBEDDING_API_KEY").ok(),
+            base_url,
+            allowed_models,
+            request_timeout_ms,
+        })
+    }
+}
+
+/// Client for the deployment's configured embedding provider.
+#[derive(Debug, Clone)]
+pub struct ServerEmbedder {
+    http: Client,
+    provider: EmbeddingProvider,
+    model: String,
+    api_key: Option<String>,
+    base_url: String,
+    allowed_models: Vec<String>,
+}
+
+/// Initializes the global server-side embedder; a no-op when no provider is configured.
+pub fn init_embedder(config: EmbedderConfig) -> Result<(), AppError> {
+    let Some(provider) = config.provider else {
+        info!("server-side embedding disabled");
+        return Ok(());
+    };
+    if provider == EmbeddingProvider::OpenAi && config.api_key.is_none() {
+        return Err(AppError::config(
+            "MESOSPHERE_EMBEDDING_API_KEY is required for the openai embedding provider",
+        ));
+    }
+    let http = Client::builder()
+        .timeout(Duration::from_millis(config.request_timeout_ms))
+        .build()
+        .map_err(|error| {
+            AppError::internal(format!("failed to create embedding http client: {}", error))
+        })?;
+    let embedder = ServerEmbedder {
+        http,
+        provider,
+        model: config.model,
+        api_key: config.api_key,
+        base_url: config.base_url,
+        allowed_models: config.allowed_models,
+    };
+    if EMBEDDER.set(embedder).is_err() {
+        debug!("server-side embedder already initialized; keeping existing client");
+    }
+    info!("server-side embedding initialized");
+    Ok(())
+}
+
+/// Returns the configured embedder or a validation error telling the client to send embeddings.
+pub fn server_embedder() -> Result<&'static ServerEmbedder, AppError> {
+    EMBEDDER.get().ok_or_else(|| {
+        AppError::validation(
+            "server-side embedding is not configured; send embeddings with the request",
+        )
+    })
+}
+
+impl ServerEmbedder {
+    /// Returns whether `model` is the default model or an allowed override.
+    pub fn serves(&self, model: &str) -> bool {
+        resolve_model(&self.model, &self.allowed_models, Some(model)).is_ok()
+    }
+
+    /// Embeds `texts` with the default model or an allowed override.
+    pub async fn embed(
+        &self,
+        texts: &[String],
+        model: Option<&str>,
+    ) -> Result<Vec<Vec<f32>>, AppError> {
+        let model = resolve_model(&self.model, &self.allowed_models, model)?;
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (url, body) = match self.provider {
+            EmbeddingProvider::OpenAi => (
+                format!("{}/embeddings", self.base_url),
+                json!({ "model": model, "input": texts }),
+            ),
+            EmbeddingProvider::Ollama => (
+                format!("{}/api/embed", self.base_url),
+                json!({ "model": model, "input": texts }),
+            ),
+        };
+        let mut request = self.http.post(&url).json(&body);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|error| AppError::internal(format!("embedding request failed: {}", error)))?;
+        let status = response.status();
+        let payload = response.json::<Value>().await.map_err(|error| {
+            AppError::internal(format!("embedding response was not JSON: {}", error))
+        })?;
+        if !status.is_success() {
+            return Err(AppError::internal(format!(
+                "embedding provider returned {}: {}",
+                status, payload
+            )));
+        }
+        let embeddings = match self.provider {
+            EmbeddingProvider::OpenAi => parse_openai_embeddings(&payload)?,
+            EmbeddingProvider::Ollama => parse_ollama_embeddings(&payload)?,
+        };
+        if embeddings.len() != texts.len() {
+            return Err(AppError::internal(format!(
+                "embedding provider returned {} embeddings for {} texts",
+                embeddings.len(),
+                texts.len()
+            )));
+        }
+        Ok(embeddings)
+    }
+}
+
+fn resolve_model<'a>(
+    default_model: &'a str,
+    allowed_models: &'a [String],
+    requested: Option<&'a str>,
+) -> Result<&'a str, AppError> {
+    match requested {
+        None => Ok(default_model),
+        Some(model) if model == default_model => Ok(default_model),
+        Some(model) if allowed_models.iter().any(|allowed| allowed == model) => Ok(model),
+        Some(model) => Err(AppError::validation(format!(
+            "embedding model '{}' is not allowed on this deployment",
+            model
+        ))),
+    }
+}
+
+fn parse_openai_embeddings(payload: &Value) -> Result<Vec<Vec<f32>>, AppError> {
+    let mut rows = payload
+        .get("data")
+        .and_then(Value::as_array)
+        .ok_or_else(|| AppError::internal("embedding response is missing 'data'"))?
+        .iter()
+        .map(|row| {
+            let index = row.get("index").and_then(Value::as_u64).unwrap_or(0);
+            Ok((index, parse_vector(row.get("embedding"))?))
+        })
+        .collect::<Result<Vec<(u64, Vec<f32>)>, AppError>>()?;
+    rows.sort_by_key(|(index, _)| *index);
+    Ok(rows.into_iter().map(|(_, vector)| vector).collect())
+}
+
+fn parse_ollama_embeddings(payload: &Value) -> Result<Vec<Vec<f32>>, AppError> {
+    payload
+        .get("embeddings")
+        .and_then(Value::as_array)
+        .ok_or_else(|| AppError::internal("embedding response is missing 'embeddings'"))?
+        .iter()
+        .map(|vector| parse_vector(Some(vector)))
+        .collect()
+}
+
+fn parse_vector(value: Option<&Value>) -> Result<Vec<f32>, AppError> {
+    value
+        .and_then(Value::as_array)
+        .ok_or_else(|| AppError::internal("embedding response contains a non-array embedding"))?
+        .iter()
+        .map(|number| {
+            number
+                .as_f64()
+                .map(|number| number as f32)
+                .ok_or_else(|| AppError::internal("embedding response contains a non-number"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_ollama_embeddings, parse_openai_embeddings, resolve_model};
+    use serde_json::json;
+
+    #[test]
+    fn model_overrides_must_be_allowed() {
+        let allowed = vec!["text-embedding-3-large".to_string()];
+        let default = "text-embedding-3-small";
+        assert_eq!(resolve_model(default, &allowed, None).unwrap(), default);
+        assert_eq!(
+            resolve_model(default, &allowed, Some("text-embedding-3-large")).unwrap(),
+            "text-embedding-3-large"
+        );
+        assert!(resolve_model(default, &allowed, Some("other")).is_err());
+    }
+
+    #[test]
+    fn parses_provider_responses_in_input_order() {
+        let openai = json!({"data": [
+            {"index": 1, "embedding": [0.5, 0.25]},
+            {"index": 0, "embedding": [1.0, 0.0]}
+        ]});
+        assert_eq!(
+            parse_openai_embeddings(&openai).unwrap(),
+            vec![vec![1.0, 0.0], vec![0.5, 0.25]]
+        );
+        let ollama = json!({"embeddings": [[0.5], [1.5]]});
+        assert_eq!(
+            parse_ollama_embeddings(&ollama).unwrap(),
+            vec![vec![0.5], vec![1.5]]
+        );
+        assert!(parse_ollama_embeddings(&json!({"embeddings": [["x"]]})).is_err());
+    }
+}
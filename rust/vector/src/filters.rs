@@ -0,0 +1,512 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde_json::{Map, Value};
+
+use mesosphere_database::query_builder::{CompiledWhere, SqlParam};
+use mesosphere_errors::AppError;
+
+/// Distinct filter shapes kept before the plan cache is reset.
+const MAX_CACHED_FILTER_SHAPES: usize = 1024;
+/// Maximum nesting of `$and` / `$or` / `$not` groups.
+const MAX_FILTER_DEPTH: usize = 8;
+
+/// Compiled clause for one filter shape; leaf values are bound per request.
+struct FilterTemplate {
+    clause: String,
+    params: Vec<TemplateParam>,
+}
+
+enum TemplateParam {
+    /// JSON path derived from the (shape-defining) key.
+    Path(String),
+    /// Next scalar leaf of the request's filter as JSON text, in document order.
+    Leaf,
+    /// Next scalar leaf bound as raw text (for `LIKE` patterns).
+    TextLeaf,
+    /// Next scalar leaf bound as a SQL boolean.
+    BoolLeaf,
+}
+
+/// Compiles a metadata filter object into a SQL predicate over `vector_items.metadata`.
+///
+/// Every top-level key is ANDed. A key maps either to a scalar (implicit `$eq`) or to an
+/// operator object using `$eq`, `$ne`, `$gt`, `$gte`, `$lt`, `$lte`, `$in`, `$nin`,
+/// `$like`, `$between` (`[low, high]`), or `$is_null` (`true`/`false`, matching missing
+/// keys and JSON nulls). Dotted keys address nested metadata objects. `$and` and `$or` take
+/// arrays of filter objects and `$not` takes one, nested up to eight levels.
+///
+/// Clauses are cached by filter shape (keys, operators, and leaf types), so repeated
+/// filters that only differ in values skip validation and SQL building. The clause does
+/// not depend on any collection schema, so entries never need invalidating.
+pub fn compile_metadata_filter(filter: &Value) -> Result<CompiledWhere, AppError> {
+    let object = filter
+        .as_object()
+        .ok_or_else(|| AppError::validation("metadata filter must be an object"))?;
+    if object.is_empty() {
+        return Ok(CompiledWhere::default());
+    }
+
+    let shape = filter_shape(filter).to_string();
+    let cached = filter_plan_cache()
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(&shape).cloned());
+    let template = match cached {
+        Some(template) => template,
+        None => {
+            let template = Arc::new(compile_template(object)?);
+            if let Ok(mut cache) = filter_plan_cache().lock() {
+                if cache.len() >= MAX_CACHED_FILTER_SHAPES {
+                    cache.clear();
+                }
+                cache.insert(shape, template.clone());
+            }
+            template
+        }
+    };
+
+    let mut leaves = Vec::<&Value>::new();
+    collect_leaves(filter, &mut leaves);
+    let mut leaves = leaves.into_iter();
+    let params = template
+        .params
+        .iter()
+        .map(|param| match param {
+            TemplateParam::Path(path) => SqlParam::String(path.clone()),
+            TemplateParam::Leaf => {
+                SqlParam::String(leaves.next().map(Value::to_string).unwrap_or_default())
+            }
+            TemplateParam::TextLeaf => SqlParam::String(
+                leaves
+                    .next()
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+            TemplateParam::BoolLeaf => {
+                SqlParam::Bool(leaves.next().and_then(Value::as_bool).unwrap_or_default())
+            }
+        })
+        .collect();
+    Ok(CompiledWhere {
+        clause: Some(template.clause.clone()),
+        params,
+    })
+}
+
+fn filter_plan_cache() -> &'static Mutex<HashMap<String, Arc<FilterTemplate>>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Arc<FilterTemplate>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Replaces scalar leaves with their type so filters differing only in values share a key.
+fn filter_shape(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| (key.clone(), filter_shape(value)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.iter().map(filter_shape).collect()),
+        Value::String(_) => Value::String("$string".to_string()),
+        Value::Number(_) => Value::String("$number".to_string()),
+        Value::Bool(_) => Value::String("$bool".to_string()),
+        Value::Null => Value::Null,
+    }
+}
+
+/// Collects scalar leaves in the order the compiler consumes them.
+fn collect_leaves<'a>(value: &'a Value, leaves: &mut Vec<&'a Value>) {
+    match value {
+        Value::Object(object) => object
+            .values()
+            .for_each(|value| collect_leaves(value, leaves)),
+        Value::Array(values) => values
+            .iter()
+            .for_each(|value| collect_leaves(value, leaves)),
+        Value::Null => {}
+        scalar => leaves.push(scalar),
+    }
+}
+
+fn compile_template(object: &Map<String, Value>) -> Result<FilterTemplate, AppError> {
+    let mut params = Vec::<TemplateParam>::new();
+    let clause = compile_object(object, 0, &mut params)?;
+    Ok(FilterTemplate { clause, params })
+}
+
+/// Compiles one filter object into an AND of its keys; empty objects match everything.
+fn compile_object(
+    object: &Map<String, Value>,
+    depth: usize,
+    params: &mut Vec<TemplateParam>,
+) -> Result<String, AppError> {
+    if depth > MAX_FILTER_DEPTH {
+        return Err(AppError::validation(format!(
+            "metadata filter nesting exceeds the maximum depth of {}",
+            MAX_FILTER_DEPTH
+        )));
+    }
+    if object.is_empty() {
+        return Ok("1 = 1".to_string());
+    }
+
+    let mut clauses = Vec::<String>::new();
+    for (key, selector) in object {
+        match key.as_str() {
+            "$and" | "$or" => {
+                let members = selector.as_array().ok_or_else(|| {
+                    AppError::validation(format!("'{}' filter must be an array of objects", key))
+                })?;
+                if members.is_empty() {
+                    clauses.push(if key == "$and" { "1 = 1" } else { "1 = 0" }.to_string());
+                    continue;
+                }
+                let mut compiled = Vec::<String>::with_capacity(members.len());
+                for member in members {
+                    let member = member.as_object().ok_or_else(|| {
+                        AppError::validation(format!(
+                            "'{}' filter must be an array of objects",
+                            key
+                        ))
+                    })?;
+                    compiled.push(format!("({})", compile_object(member, depth + 1, params)?));
+                }
+                let joiner = if key == "$and" { " AND " } else { " OR " };
+                clauses.push(format!("({})", compiled.join(joiner)));
+            }
+            "$not" => {
+                let inner = selector
+                    .as_object()
+                    .ok_or_else(|| AppError::validation("'$not' filter must be an object"))?;
+                clauses.push(format!(
+                    "NOT ({})",
+                    compile_object(inner, depth + 1, params)?
+                ));
+            }
+            _ => {
+                let path = metadata_path(key)?;
+                match selector {
+                    Value::Object(operators) => {
+                        compile_operators(key, &path, operators, &mut clauses, params)?
+                    }
+                    _ => compile_comparison(key, &path, "=", selector, &mut clauses, params)?,
+                }
+            }
+        }
+    }
+    Ok(clauses.join(" AND "))
+}
+
+fn compile_operators(
+    key: &str,
+    path: &str,
+    operators: &Map<String, Value>,
+    clauses: &mut Vec<String>,
+    params: &mut Vec<TemplateParam>,
+) -> Result<(), AppError> {
+    if operators.is_empty() {
+        return Err(AppError::validation(format!(
+            "metadata filter for '{}' cannot be an empty object",
+            key
+        )));
+    }
+
+    for (operator, operand) in operators {
+        match operator.as_str() {
+            "$eq" => compile_comparison(key, path, "=", operand, clauses, params)?,
+            "$gt" => compile_comparison(key, path, ">", operand, clauses, params)?,
+            "$gte" => compile_comparison(key, path, ">=", operand, clauses, params)?,
+            "$lt" => compile_comparison(key, path, "<", operand, clauses, params)?,
+            "$lte" => compile_comparison(key, path, "<=", operand, clauses, params)?,
+            "$ne" => {
+                ensure_scalar(key, operand)?;
+                clauses.push("NOT (JSON_EXTRACT(metadata, ?) <=> CAST(? AS JSON))".to_string());
+                params.push(TemplateParam::Path(path.to_string()));
+                params.push(TemplateParam::Leaf);
+            }
+            "$in" | "$nin" => {
+                let values = operand.as_array().ok_or_else(|| {
+                    AppError::validation(format!(
+                        "'{}' filter for '{}' must be an array",
+                        operator, key
+                    ))
+                })?;
+                let negated = operator == "$nin";
+                if values.is_empty() {
+                    clauses.push(if negated { "1 = 1" } else { "1 = 0" }.to_string());
+                    continue;
+                }
+                let mut alternatives = Vec::<String>::with_capacity(values.len());
+                for value in values {
+                    ensure_scalar(key, value)?;
+                    alternatives.push("JSON_EXTRACT(metadata, ?) <=> CAST(? AS JSON)".to_string());
+                    params.push(TemplateParam::Path(path.to_string()));
+                    params.push(TemplateParam::Leaf);
+                }
+                let joined = alternatives.join(" OR ");
+                clauses.push(if negated {
+                    format!("NOT ({})", joined)
+                } else {
+                    format!("({})", joined)
+                });
+            }
+            "$like" => {
+                if !operand.is_string() {
+                    return Err(AppError::validation(format!(
+                        "'$like' filter for '{}' must be a string pattern",
+                        key
+                    )));
+                }
+                clauses.push("JSON_UNQUOTE(JSON_EXTRACT(metadata, ?)) LIKE ?".to_string());
+                params.push(TemplateParam::Path(path.to_string()));
+                params.push(TemplateParam::TextLeaf);
+            }
+            "$between" => {
+                let bounds = operand
+                    .as_array()
+                    .filter(|bounds| bounds.len() == 2)
+                    .ok_or_else(|| {
+                        AppError::validation(format!(
+                            "'$between' filter for '{}' must be a [low, high] array",
+                            key
+                        ))
+                    })?;
+                for bound in bounds {
+                    ensure_range_operand(key, bound)?;
+                }
+                // MySQL has no BETWEEN for JSON values, so compare each bound separately.
+                clauses.push(
+                    "(JSON_EXTRACT(metadata, ?) >= CAST(? AS JSON) \
+                     AND JSON_EXTRACT(metadata, ?) <= CAST(? AS JSON))"
+                        .to_string(),
+                );
+                params.push(TemplateParam::Path(path.to_string()));
+                params.push(TemplateParam::Leaf);
+                params.push(TemplateParam::Path(path.to_string()));
+                params.push(TemplateParam::Leaf);
+            }
+            "$is_null" => {
+                if !operand.is_boolean() {
+                    return Err(AppError::validation(format!(
+                        "'$is_null' filter for '{}' must be true or false",
+                        key
+                    )));
+                }
+                clauses.push(
+                    "((JSON_EXTRACT(metadata, ?) IS NULL \
+                     OR JSON_TYPE(JSON_EXTRACT(metadata, ?)) = 'NULL') = ?)"
+                        .to_string(),
+                );
+                params.push(TemplateParam::Path(path.to_string()));
+                params.push(TemplateParam::Path(path.to_string()));
+                params.push(TemplateParam::BoolLeaf);
+            }
+            _ => {
+                return Err(AppError::validation(format!(
+                    "unsupported metadata filter operator '{}' for '{}'",
+                    operator, key
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn compile_comparison(
+    key: &str,
+    path: &str,
+    sql_operator: &str,
+    value: &Value,
+    clauses: &mut Vec<String>,
+    params: &mut Vec<TemplateParam>,
+) -> Result<(), AppError> {
+    if sql_operator == "=" {
+        ensure_scalar(key, value)?;
+    } else {
+        ensure_range_operand(key, value)?;
+    }
+    clauses.push(format!(
+        "JSON_EXTRACT(metadata, ?) {} CAST(? AS JSON)",
+        sql_operator
+    ));
+    params.push(TemplateParam::Path(path.to_string()));
+    params.push(TemplateParam::Leaf);
+    Ok(())
+}
+
+fn ensure_range_operand(key: &str, value: &Value) -> Result<(), AppError> {
+    if !(value.is_number() || value.is_string()) {
+        return Err(AppError::validation(format!(
+            "range filter for '{}' requires a number or string",
+            key
+        )));
+    }
+    Ok(())
+}
+
+fn ensure_scalar(key: &str, value: &Value) -> Result<(), AppError> {
+    if value.is_array() || value.is_object() || value.is_null() {
+        return Err(AppError::validation(format!(
+            "metadata filter value for '{}' must be a string, number, or boolean",
+            key
+        )));
+    }
+    Ok(())
+}
+
+/// Validates a dotted metadata key and converts it into a MySQL JSON path.
+pub(crate) fn metadata_path(key: &str) -> Result<String, AppError> {
+    let valid = !key.is_empty()
+        && key.split('.').all(|segment| {
+            let mut chars = segment.chars();
+            matches!(chars.next(), Some(first) if first.is_ascii_alphabetic() || first == '_')
+                && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        });
+    if !valid {
+        return Err(AppError::validation(format!(
+            "invalid metadata filter key '{}'",
+            key
+        )));
+    }
+    Ok(format!("$.{}", key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compile_metadata_filter;
+    use mesosphere_database::query_builder::SqlParam;
+    use serde_json::json;
+
+    fn string_params(params: &[SqlParam]) -> Vec<String> {
+        params
+            .iter()
+            .map(|param| match param {
+                SqlParam::String(value) => value.clone(),
+                SqlParam::Number(value) => value.to_string(),
+                SqlParam::Bool(value) => value.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn compiles_implicit_eq_and_in() {
+        let compiled = compile_metadata_filter(&json!({
+            "source": "youtube",
+            "meta.rank": {"$in": [1, 2]}
+        }))
+        .expect("filter should compile");
+
+        assert_eq!(
+            compiled.clause.as_deref(),
+            Some(
+                "(JSON_EXTRACT(metadata, ?) <=> CAST(? AS JSON) OR JSON_EXTRACT(metadata, ?) <=> CAST(? AS JSON)) \
+                 AND JSON_EXTRACT(metadata, ?) = CAST(? AS JSON)"
+            )
+        );
+        assert_eq!(
+            string_params(&compiled.params),
+            vec![
+                "$.meta.rank",
+                "1",
+                "$.meta.rank",
+                "2",
+                "$.source",
+                "\"youtube\""
+            ]
+        );
+    }
+
+    #[test]
+    fn compiles_range_and_negated_operators() {
+        let compiled = compile_metadata_filter(&json!({
+            "year": {"$gte": 2020, "$lt": 2024},
+            "lang": {"$ne": "en", "$nin": ["de"]}
+        }))
+        .expect("filter should compile");
+
+        assert_eq!(
+            compiled.clause.as_deref(),
+            Some(
+                "NOT (JSON_EXTRACT(metadata, ?) <=> CAST(? AS JSON)) \
+                 AND NOT (JSON_EXTRACT(metadata, ?) <=> CAST(? AS JSON)) \
+                 AND JSON_EXTRACT(metadata, ?) >= CAST(? AS JSON) \
+                 AND JSON_EXTRACT(metadata, ?) < CAST(? AS JSON)"
+            )
+        );
+        assert_eq!(
+            string_params(&compiled.params),
+            vec!["$.lang", "\"en\"", "$.lang", "\"de\"", "$.year", "2020", "$.year", "2024"]
+        );
+        assert!(compile_metadata_filter(&json!({"flag": {"$gt": true}})).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_keys_and_operators() {
+        assert!(compile_metadata_filter(&json!({"bad key": 1})).is_err());
+        assert!(compile_metadata_filter(&json!({"a": {"$regex": "x"}})).is_err());
+        assert!(compile_metadata_filter(&json!({"a": [1]})).is_err());
+        assert!(compile_metadata_filter(&json!([1])).is_err());
+    }
+
+    #[test]
+    fn reuses_plan_for_same_shape_with_new_values() {
+        let first = compile_metadata_filter(&json!({"lang": {"$in": ["en", "fr"]}, "year": 2020}))
+            .expect("filter should compile");
+        let second = compile_metadata_filter(&json!({"lang": {"$in": ["de", "it"]}, "year": 1999}))
+            .expect("filter should compile");
+
+        assert_eq!(first.clause, second.clause);
+        assert_eq!(
+            string_params(&second.params),
+            vec!["$.lang", "\"de\"", "$.lang", "\"it\"", "$.year", "1999"]
+        );
+        assert!(compile_metadata_filter(&json!({"year": {"$gt": true}})).is_err());
+        assert!(compile_metadata_filter(&json!({"year": {"$gt": 2000}})).is_ok());
+    }
+
+    #[test]
+    fn compiles_nested_logic_and_extended_operators() {
+        let compiled = compile_metadata_filter(&json!({
+            "$or": [
+                {"title": {"$like": "intro%"}},
+                {"$not": {"year": {"$between": [2000, 2010]}}}
+            ],
+            "author": {"$is_null": false}
+        }))
+        .expect("filter should compile");
+
+        assert_eq!(
+            compiled.clause.as_deref(),
+            Some(
+                "((JSON_UNQUOTE(JSON_EXTRACT(metadata, ?)) LIKE ?) \
+                 OR (NOT ((JSON_EXTRACT(metadata, ?) >= CAST(? AS JSON) \
+                 AND JSON_EXTRACT(metadata, ?) <= CAST(? AS JSON))))) \
+                 AND ((JSON_EXTRACT(metadata, ?) IS NULL \
+                 OR JSON_TYPE(JSON_EXTRACT(metadata, ?)) = 'NULL') = ?)"
+            )
+        );
+        assert_eq!(
+            string_params(&compiled.params),
+            vec![
+                "$.title", "intro%", "$.year", "2000", "$.year", "2010", "$.author", "$.author",
+                "false"
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_logic_and_excessive_depth() {
+        let mut nested = json!({"a": 1});
+        for _ in 0..9 {
+            nested = json!({"$not": nested});
+        }
+        assert!(compile_metadata_filter(&nested).is_err());
+        assert!(compile_metadata_filter(&json!({"$or": {"a": 1}})).is_err());
+        assert!(compile_metadata_filter(&json!({"a": {"$between": [1]}})).is_err());
+        assert!(compile_metadata_filter(&json!({"a": {"$is_null": "yes"}})).is_err());
+        assert!(compile_metadata_filter(&json!({"a": {"$like": 3}})).is_err());
+    }
+}
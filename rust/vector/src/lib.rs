@@ -1,12 +1,34 @@
-﻿mod codec;
+﻿mod bulk;
+mod cache;
+mod codec;
+mod compaction;
+mod filters;
+mod ops;
+mod schema;
 mod scoring;
+mod snapshot;
 
 /// Vector endpoint request/response models.
 pub mod api_models;
+/// Sample collection seeded when the backend runs in demo mode.
+pub mod demo;
+/// Server-side embedding through the deployment's configured provider.
+pub mod embedder;
 /// Vector persistence and query repository.
 pub mod repository;
 /// Vector route handlers.
 pub mod routes;
+/// Synthetic datasets for benchmarks, demos and tests.
+pub mod testing;
+/// Per-API-key usage accounting and quotas.
+pub mod usage;
 
-pub use codec::{decode_embedding, encode_embedding, vector_norm};
-pub use scoring::cosine_similarity;
+pub use codec::{
+    decode_embedding, decode_stored_embedding, encode_embedding, encode_embedding_int8,
+    vector_norm, EmbeddingQuantization,
+};
+pub use filters::compile_metadata_filter;
+pub use ops::{average_embeddings, normalize_embedding, weighted_sum};
+pub use schema::{MetadataFieldSchema, MetadataFieldType, MetadataSchema};
+pub use scoring::{cosine_similarity, cosine_similarity_with_norms};
+pub use snapshot::{encode_jsonl, parse_jsonl, parse_jsonl_line, VectorSnapshotRecord};
@@ -0,0 +1,73 @@
+/// Computes the component-wise weighted sum of equally sized vectors.
+pub fn weighted_sum(vectors: &[(&[f32], f32)]) -> Result<Vec<f32>, String> {
+    let Some((first, _)) = vectors.first() else {
+        return Err("cannot combine an empty set of vectors".to_string());
+    };
+
+    let dimension = first.len();
+    let mut sums = vec![0.0_f64; dimension];
+    for (values, weight) in vectors {
+        if values.len() != dimension {
+            return Err(format!(
+                "vector dimension {} does not match expected dimension {}",
+                values.len(),
+                dimension
+            ));
+        }
+        let weight = *weight as f64;
+        for (sum, value) in sums.iter_mut().zip(values.iter()) {
+            *sum += *value as f64 * weight;
+        }
+    }
+
+    Ok(sums.into_iter().map(|value| value as f32).collect())
+}
+
+/// Computes the centroid (component-wise mean) of equally sized vectors.
+pub fn average_embeddings(vectors: &[Vec<f32>]) -> Result<Vec<f32>, String> {
+    if vectors.is_empty() {
+        return Err("cannot average an empty set of vectors".to_string());
+    }
+    let weight = 1.0 / vectors.len() as f32;
+    let weighted = vectors
+        .iter()
+        .map(|values| (values.as_slice(), weight))
+        .collect::<Vec<(&[f32], f32)>>();
+    weighted_sum(&weighted)
+}
+
+/// Scales a vector to unit length; zero vectors are returned unchanged.
+pub fn normalize_embedding(values: &[f32]) -> Vec<f32> {
+    let norm = crate::codec::vector_norm(values);
+    if norm == 0.0 {
+        return values.to_vec();
+    }
+    values
+        .iter()
+        .map(|value| (*value as f64 / norm) as f32)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{average_embeddings, normalize_embedding, weighted_sum};
+
+    #[test]
+    fn average_and_weighted_sum_combine_components() {
+        let average = average_embeddings(&[vec![1.0, 3.0], vec![3.0, 5.0]]).expect("average");
+        assert_eq!(average, vec![2.0, 4.0]);
+
+        let sum = weighted_sum(&[(&[1.0, 0.0], 2.0), (&[0.0, 1.0], -1.0)]).expect("sum");
+        assert_eq!(sum, vec![2.0, -1.0]);
+
+        assert!(average_embeddings(&[vec![1.0], vec![1.0, 2.0]]).is_err());
+        assert!(average_embeddings(&[]).is_err());
+    }
+
+    #[test]
+    fn normalize_produces_unit_vectors() {
+        let normalized = normalize_embedding(&[3.0, 4.0]);
+        assert_eq!(normalized, vec![0.6, 0.8]);
+        assert_eq!(normalize_embedding(&[0.0, 0.0]), vec![0.0, 0.0]);
+    }
+}
@@ -1,13 +1,47 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use sqlx::{MySqlPool, Row};
 use tracing::instrument;
 use uuid::Uuid;
 
-use crate::codec::{decode_embedding, encode_embedding, vector_norm};
-use crate::scoring::cosine_similarity;
+use crate::cache::{candidate_cache, CacheToken};
+use crate::codec::{decode_stored_embedding, vector_norm, EmbeddingQuantization};
+use crate::filters::{compile_metadata_filter, metadata_path};
+use crate::ops::{average_embeddings, weighted_sum};
+use crate::schema::MetadataSchema;
+use crate::scoring::{cosine_similarity_with_norms, score_in_parallel};
+use crate::snapshot::VectorSnapshotRecord;
+use crate::usage::estimate_item_bytes;
+use mesosphere_database::query_builder::{bind_params, CompiledWhere, SqlParam};
 use mesosphere_errors::AppError;
 
+/// Upper bound for one scroll page.
+pub const MAX_SCROLL_BATCH_SIZE: u32 = 1000;
+/// Upper bound for distinct values returned per facet key.
+pub const MAX_FACET_VALUES: u32 = 1000;
+/// Metadata keys reported by collection statistics.
+const STATS_TOP_METADATA_KEYS: u32 = 20;
+/// Upper bound for an item URI.
+pub const MAX_URI_LENGTH: usize = 2048;
+/// Upper bound for a namespace name.
+pub const MAX_NAMESPACE_LENGTH: usize = 255;
+/// Upper bound for a data column name.
+const MAX_DATA_COLUMN_LENGTH: usize = 64;
+/// Rows per multi-row insert when the caller does not choose a chunk size.
+pub const DEFAULT_INSERT_CHUNK_SIZE: usize = 500;
+/// Upper bound for rows per insert statement, keeping binds under MySQL's 65535 limit.
+pub const MAX_INSERT_CHUNK_SIZE: usize = 4000;
+/// Tables rebuilt and re-analyzed by compaction.
+const COMPACTED_TABLES: &str = "vector_items, vector_collections";
+/// Estimated stored size of a `vector_items` row, sized like `estimate_item_bytes`.
+const ITEM_BYTES_SQL: &str = "embedding_dim * 4 + COALESCE(LENGTH(document), 0) \
+    + COALESCE(LENGTH(metadata), 0) + COALESCE(LENGTH(uri), 0) + COALESCE(LENGTH(data), 0)";
+
 /// Vector collection record.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VectorCollectionRecord {
@@ -17,6 +51,14 @@ pub struct VectorCollectionRecord {
     pub name: String,
     /// Metadata JSON.
     pub metadata: Option<Value>,
+    /// Declared item metadata schema, when the collection is strict.
+    pub metadata_schema: Option<Value>,
+    /// Storage encoding of item embeddings.
+    pub quantization: EmbeddingQuantization,
+    /// Provider/model that produced the collection's embeddings, when declared.
+    pub embedding_model: Option<String>,
+    /// Embedding dimension, declared at creation or fixed by the first write.
+    pub embedding_dimension: Option<u32>,
     /// Created timestamp.
     pub created_at: String,
     /// Updated timestamp.
@@ -28,12 +70,67 @@ pub struct VectorCollectionRecord {
 pub struct NewVectorItem {
     /// Optional item id.
     pub id: Option<String>,
+    /// Optional namespace partition (the default namespace when omitted).
+    pub namespace: Option<String>,
+    /// Optional logical document this chunk belongs to.
+    pub parent_id: Option<String>,
     /// Embedding data.
     pub embedding: Vec<f32>,
     /// Optional document.
     pub document: Option<String>,
     /// Optional metadata.
     pub metadata: Option<Value>,
+    /// Optional lifetime in seconds; the item is hidden and purged once it elapses.
+    pub ttl_seconds: Option<u64>,
+    /// Optional source URI.
+    pub uri: Option<String>,
+    /// Optional typed payload columns (flat object of scalar values).
+    pub data: Option<Value>,
+}
+
+impl NewVectorItem {
+    /// Deterministic id derived from the collection, namespace and item content.
+    ///
+    /// Identical content added twice to the same collection and namespace maps to the same
+    /// id; the SHA-256 digest is truncated to a version 8 UUID so it fits the id column.
+    pub fn content_hash_id(&self, collection_name: &str) -> String {
+        let mut hasher = Sha256::new();
+        let mut field = |value: &[u8]| {
+            hasher.update((value.len() as u64).to_le_bytes());
+            hasher.update(value);
+        };
+        let json = |value: &Option<Value>| value.as_ref().map(Value::to_string).unwrap_or_default();
+        field(collection_name.as_bytes());
+        field(self.namespace.as_deref().unwrap_or_default().as_bytes());
+        field(self.parent_id.as_deref().unwrap_or_default().as_bytes());
+        let embedding = self
+            .embedding
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect::<Vec<u8>>();
+        field(&embedding);
+        field(self.document.as_deref().unwrap_or_default().as_bytes());
+        field(json(&self.metadata).as_bytes());
+        field(self.uri.as_deref().unwrap_or_default().as_bytes());
+        field(json(&self.data).as_bytes());
+        let digest = hasher.finalize();
+        let mut bytes = [0_u8; 16];
+        bytes.copy_from_slice(&digest[..16]);
+        uuid::Builder::from_custom_bytes(bytes)
+            .into_uuid()
+            .to_string()
+    }
+}
+
+/// How `add` assigns ids to items that do not carry one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VectorIdMode {
+    /// Random version 4 UUIDs.
+    #[default]
+    Uuid,
+    /// Content-derived ids; re-adding identical content is a no-op instead of a duplicate.
+    ContentHash,
 }
 
 /// Vector item payload for update operations.
@@ -41,12 +138,44 @@ pub struct NewVectorItem {
 pub struct UpdateVectorItem {
     /// Existing item id.
     pub id: String,
+    /// Optional parent id replacement.
+    pub parent_id: Option<String>,
     /// Optional embedding replacement.
     pub embedding: Option<Vec<f32>>,
     /// Optional document replacement.
     pub document: Option<String>,
     /// Optional metadata replacement.
     pub metadata: Option<Value>,
+    /// Optional new lifetime in seconds, counted from now.
+    pub ttl_seconds: Option<u64>,
+    /// Optional URI replacement.
+    pub uri: Option<String>,
+    /// Optional data columns replacement.
+    pub data: Option<Value>,
+}
+
+/// Outcome of an insert or upsert batch.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VectorUpsertResult {
+    /// Ids that did not exist before the upsert.
+    pub inserted_ids: Vec<String>,
+    /// Ids that already existed and were replaced.
+    pub updated_ids: Vec<String>,
+    /// Estimated bytes the replaced items held before the write.
+    #[serde(default)]
+    pub replaced_bytes: u64,
+    /// Expired items purged by the write.
+    #[serde(default)]
+    pub purged: VectorRemoval,
+}
+
+/// Items removed by a delete or purge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorRemoval {
+    /// Number of items removed.
+    pub items: u64,
+    /// Estimated bytes the removed items held.
+    pub bytes: u64,
 }
 
 /// Vector item read model.
@@ -54,10 +183,136 @@ pub struct UpdateVectorItem {
 pub struct VectorItemRecord {
     /// Item id.
     pub id: String,
+    /// Parent document id.
+    pub parent_id: Option<String>,
+    /// Optional document.
+    pub document: Option<String>,
+    /// Optional metadata.
+    pub metadata: Option<Value>,
+}
+
+/// Outcome of one table maintenance statement, as reported by MySQL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorMaintenanceStep {
+    /// Qualified table name.
+    pub table: String,
+    /// Operation (`optimize` or `analyze`).
+    pub operation: String,
+    /// Message type (`status`, `note`, `info`, `warning` or `error`).
+    pub status: String,
+    /// Message text.
+    pub message: String,
+}
+
+/// Result of compacting vector storage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorCompactionReport {
+    /// Expired items purged before rebuilding.
+    pub purged_expired: u64,
+    /// Estimated bytes the purged items held.
+    #[serde(default)]
+    pub purged_expired_bytes: u64,
+    /// Maintenance statement results in execution order.
+    pub steps: Vec<VectorMaintenanceStep>,
+}
+
+/// Optional columns of one item, fetched when a caller includes them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VectorItemColumns {
+    /// Source URI.
+    pub uri: Option<String>,
+    /// Typed payload columns.
+    pub data: Option<Value>,
+    /// Stored embedding, decoded only when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+}
+
+/// Items fetched by id in request order, plus ids that do not exist.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorBatchGetResult {
+    /// Found items in the order their ids were requested.
+    pub items: Vec<VectorItemRecord>,
+    /// Requested ids without a stored item, in request order.
+    pub missing: Vec<String>,
+}
+
+/// Item scored against a query vector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorScoredItem {
+    /// Item id.
+    pub id: String,
+    /// Parent document id.
+    pub parent_id: Option<String>,
     /// Optional document.
     pub document: Option<String>,
     /// Optional metadata.
     pub metadata: Option<Value>,
+    /// Cosine distance to the query vector.
+    pub distance: f64,
+}
+
+/// One page of a scored scan, in id order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorScoredPage {
+    /// Scored items of this page.
+    pub items: Vec<VectorScoredItem>,
+    /// Cursor for the next page; `None` once the collection is exhausted.
+    pub next_cursor: Option<String>,
+}
+
+/// One id-ordered page of a snapshot export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorSnapshotPage {
+    /// Snapshot records of this page.
+    pub records: Vec<VectorSnapshotRecord>,
+    /// Cursor for the next page; `None` once the collection is exhausted.
+    pub next_cursor: Option<String>,
+}
+
+/// Full-text search hits ordered by relevance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorTextSearchResult {
+    /// Matching items, best match first.
+    pub items: Vec<VectorItemRecord>,
+    /// FULLTEXT relevance score per item.
+    pub scores: Vec<f64>,
+}
+
+/// `LIMIT`/`OFFSET` window for item reads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ItemPage {
+    /// Maximum items to return; unbounded when `None`.
+    pub limit: Option<u64>,
+    /// Items to skip before the first returned one.
+    pub offset: u64,
+}
+
+/// One page of a keyset scroll over a collection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorScrollPage {
+    /// Items ordered by id.
+    pub items: Vec<VectorItemRecord>,
+    /// Cursor for the next page; `None` once the collection is exhausted.
+    pub next_cursor: Option<String>,
+}
+
+/// Value counts for one metadata key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorFacet {
+    /// Metadata key.
+    pub key: String,
+    /// Distinct values ordered by descending count.
+    pub values: Vec<VectorFacetValue>,
+}
+
+/// One distinct metadata value and its item count.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorFacetValue {
+    /// Metadata value.
+    pub value: Value,
+    /// Number of items carrying the value.
+    pub count: u64,
 }
 
 /// Vector query result payload.
@@ -65,18 +320,141 @@ pub struct VectorItemRecord {
 pub struct VectorQueryResult {
     /// Result ids grouped per query vector.
     pub ids: Vec<Vec<String>>,
+    /// Result parent ids grouped per query vector.
+    pub parent_ids: Vec<Vec<Option<String>>>,
     /// Result documents grouped per query vector.
     pub documents: Vec<Vec<Option<String>>>,
     /// Result metadatas grouped per query vector.
     pub metadatas: Vec<Vec<Option<Value>>>,
     /// Result distances grouped per query vector.
     pub distances: Vec<Vec<f64>>,
+    /// Metadata groups per query vector when grouping was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<Vec<VectorResultGroup>>>,
+    /// Execution trace when `explain` was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explain: Option<VectorQueryExplain>,
+}
+
+/// How a similarity query was executed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorQueryExplain {
+    /// Search strategy; every query is an exact scan (`exact`), there is no ANN index.
+    pub index: String,
+    /// Where candidates came from: the in-memory `cache` or the `database`.
+    pub candidate_source: String,
+    /// Live items in the collection.
+    pub collection_items: u64,
+    /// Candidates loaded after the metadata filter and namespace were applied.
+    pub candidates_scanned: u64,
+    /// Live items excluded by the metadata filter or namespace.
+    pub candidates_filtered: u64,
+    /// Similarity computations across all query vectors.
+    pub candidates_scored: u64,
+    /// Results returned across all query vectors.
+    pub results_returned: u64,
+    /// Milliseconds spent loading candidates.
+    pub load_ms: f64,
+    /// Milliseconds spent scoring and ranking.
+    pub score_ms: f64,
+    /// Total milliseconds spent in the repository.
+    pub total_ms: f64,
+}
+
+/// Best-matching items sharing one metadata value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorResultGroup {
+    /// Shared metadata value.
+    pub value: Value,
+    /// Item ids ordered by similarity.
+    pub ids: Vec<String>,
+    /// Item documents.
+    pub documents: Vec<Option<String>>,
+    /// Item metadatas.
+    pub metadatas: Vec<Option<Value>>,
+    /// Item distances.
+    pub distances: Vec<f64>,
+}
+
+/// Group-by options for similarity queries.
+#[derive(Debug, Clone)]
+pub struct QueryGrouping {
+    /// Metadata key path (for example `source` or `metadata.source`).
+    pub key: String,
+    /// Maximum items kept per group.
+    pub group_size: usize,
+}
+
+/// Maximal Marginal Relevance re-ranking options.
+#[derive(Debug, Clone)]
+pub struct QueryMmr {
+    /// Trade-off between relevance (1.0) and diversity (0.0).
+    pub lambda: f64,
+    /// Number of most similar candidates re-ranked for diversity.
+    pub fetch_k: usize,
+}
+
+/// Optional behaviours for similarity queries.
+#[derive(Debug, Clone, Default)]
+pub struct VectorQueryOptions {
+    /// Metadata filter applied before scoring.
+    pub filter: Option<Value>,
+    /// Group results by a metadata key.
+    pub grouping: Option<QueryGrouping>,
+    /// Diversify results with MMR.
+    pub mmr: Option<QueryMmr>,
+    /// Keep only the best-scoring chunk per parent id.
+    pub dedupe_by_parent: bool,
+    /// Restrict candidates to one namespace.
+    pub namespace: Option<String>,
+    /// Attach a [`VectorQueryExplain`] trace to the result.
+    pub explain: bool,
+}
+
+/// Live item count of one namespace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorNamespaceCount {
+    /// Namespace name (empty for the default namespace).
+    pub namespace: String,
+    /// Number of live items.
+    pub count: u64,
+}
+
+/// How many live items carry one top-level metadata key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorMetadataKeyCount {
+    /// Metadata key.
+    pub key: String,
+    /// Number of live items with the key.
+    pub count: u64,
+}
+
+/// Aggregate statistics of one collection, computed in SQL without loading items.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorCollectionStats {
+    /// Collection name.
+    pub name: String,
+    /// Number of live items.
+    pub item_count: u64,
+    /// Mean embedding dimension of live items.
+    pub average_dimension: Option<f64>,
+    /// Embedding dimension of the most recently written item.
+    pub last_dimension: Option<u32>,
+    /// Approximate bytes used by embeddings, documents, metadata, URIs and data.
+    pub approximate_bytes: u64,
+    /// Collection creation timestamp.
+    pub created_at: String,
+    /// Latest write to the collection or any of its items.
+    pub updated_at: String,
+    /// Most common top-level metadata keys, most frequent first.
+    pub top_metadata_keys: Vec<VectorMetadataKeyCount>,
 }
 
 #[derive(Clone)]
 pub struct VectorRepository {
     pool: MySqlPool,
     max_dimension: usize,
+    cache_budget_bytes: usize,
 }
 
 impl VectorRepository {
@@ -85,29 +463,72 @@ impl VectorRepository {
         Self {
             pool,
             max_dimension,
+            cache_budget_bytes: 0,
         }
     }
 
-    /// Creates a vector collection.
-    #[instrument(skip(self, metadata), fields(collection = name))]
+    /// Enables the process-wide decoded-embedding cache for unfiltered similarity queries,
+    /// bounded by `budget_bytes` across all collections (`0` disables it).
+    pub fn with_cache_budget(mut self, budget_bytes: usize) -> Self {
+        self.cache_budget_bytes = budget_bytes;
+        self
+    }
+
+    /// Creates a vector collection, optionally with a strict item metadata schema, a
+    /// quantized embedding encoding and an embedding model/dimension fingerprint.
+    #[instrument(skip(self, metadata, metadata_schema), fields(collection = name, quantization = quantization.as_str()))]
     pub async fn create_collection(
         &self,
         name: &str,
         metadata: Option<Value>,
+        metadata_schema: Option<Value>,
+        quantization: EmbeddingQuantization,
+        embedding_model: Option<String>,
+        embedding_dimension: Option<u32>,
     ) -> Result<VectorCollectionRecord, AppError> {
         if name.trim().is_empty() {
             return Err(AppError::validation("collection name cannot be empty"));
         }
+        if let Some(schema) = &metadata_schema {
+            MetadataSchema::parse(schema)?;
+        }
+        if let Some(model) = &embedding_model {
+            if model.trim().is_empty() || model.len() > 255 {
+                return Err(AppError::validation(
+                    "embedding_model must be between 1 and 255 characters",
+                ));
+            }
+        }
+        if let Some(dimension) = embedding_dimension {
+            if dimension == 0 || dimension as usize > self.max_dimension {
+                return Err(AppError::validation(format!(
+                    "embedding_dimension must be between 1 and {}",
+                    self.max_dimension
+                )));
+            }
+        }
         let collection_id = Uuid::new_v4().to_string();
         sqlx::query(
             r#"
-            INSERT INTO vector_collections (id, name, metadata)
-            VALUES (?, ?, ?)
+            INSERT INTO vector_collections (
+                id,
+                name,
+                metadata,
+                metadata_schema,
+                quantization,
+                embedding_model,
+                embedding_dimension
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&collection_id)
         .bind(name)
         .bind(metadata.clone().map(sqlx::types::Json))
+        .bind(metadata_schema.map(sqlx::types::Json))
+        .bind(quantization.as_str())
+        .bind(embedding_model)
+        .bind(embedding_dimension)
         .execute(&self.pool)
         .await?;
 
@@ -121,7 +542,8 @@ impl VectorRepository {
     pub async fn list_collections(&self) -> Result<Vec<VectorCollectionRecord>, AppError> {
         let rows = sqlx::query(
             r#"
-            SELECT id, name, metadata, _created_at, _updated_at
+            SELECT id, name, metadata, metadata_schema, quantization, embedding_model,
+                embedding_dimension, _created_at, _updated_at
             FROM vector_collections
             ORDER BY name ASC
             "#,
@@ -132,65 +554,262 @@ impl VectorRepository {
         rows.into_iter().map(row_to_collection).collect()
     }
 
-    /// Deletes one collection (and cascade-deletes items).
+    /// Deletes one collection with its items and returns the affected collection count
+    /// along with the items removed.
     #[instrument(skip(self), fields(collection = name))]
-    pub async fn delete_collection(&self, name: &str) -> Result<u64, AppError> {
-        let result = sqlx::query("DELETE FROM vector_collections WHERE name = ?")
-            .bind(name)
-            .execute(&self.pool)
+    pub async fn delete_collection(&self, name: &str) -> Result<(u64, VectorRemoval), AppError> {
+        let mut transaction = self.pool.begin().await?;
+        let collection_id = sqlx::query_scalar::<_, String>(
+            "SELECT id FROM vector_collections WHERE name = ? FOR UPDATE",
+        )
+        .bind(name)
+        .fetch_optional(&mut *transaction)
+        .await?;
+        let Some(collection_id) = collection_id else {
+            return Ok((0, VectorRemoval::default()));
+        };
+        let removed = remove_items(
+            &mut transaction,
+            "collection_id = ?",
+            &[SqlParam::String(collection_id.clone())],
+        )
+        .await?;
+        let result = sqlx::query("DELETE FROM vector_collections WHERE id = ?")
+            .bind(collection_id)
+            .execute(&mut *transaction)
             .await?;
-        Ok(result.rows_affected())
+        transaction.commit().await?;
+        Ok((result.rows_affected(), removed))
     }
 
-    /// Adds items to a collection and returns inserted ids.
+    /// Adds items to a collection in one transaction and returns the inserted ids.
+    ///
+    /// Rows are written with multi-row `INSERT` statements of up to `chunk_size` rows
+    /// (clamped to [`MAX_INSERT_CHUNK_SIZE`]), so a full chunk reuses one prepared statement.
     #[instrument(skip(self, items), fields(collection = collection_name, item_count = items.len()))]
     pub async fn add_items(
         &self,
         collection_name: &str,
         items: &[NewVectorItem],
-    ) -> Result<Vec<String>, AppError> {
-        let collection_id = self.collection_id(collection_name).await?;
+        chunk_size: usize,
+    ) -> Result<VectorUpsertResult, AppError> {
+        let (collection, schema) = self.write_target(collection_name).await?;
         if items.is_empty() {
-            return Ok(Vec::new());
+            return Ok(VectorUpsertResult::default());
+        }
+        for item in items {
+            validate_embedding(&item.embedding, self.max_dimension)?;
+            validate_metadata(schema.as_ref(), item.metadata.as_ref())?;
+            validate_columns(item.uri.as_deref(), item.data.as_ref())?;
+            validate_namespace(item.namespace.as_deref())?;
         }
+        let dimension = check_dimensions(
+            &collection,
+            items.iter().map(|item| item.embedding.as_slice()),
+        )?;
+        let (collection_id, quantization) = (collection.id.clone(), collection.quantization);
+        let ids = items
+            .iter()
+            .map(|item| {
+                item.id
+                    .clone()
+                    .unwrap_or_else(|| Uuid::new_v4().to_string())
+            })
+            .collect::<Vec<String>>();
 
-        let mut inserted_ids = Vec::<String>::new();
         let mut transaction = self.pool.begin().await?;
+        let purged = purge_expired(&mut transaction, &collection_id).await?;
+        record_dimension(&mut transaction, &collection, dimension).await?;
+        let chunk_size = chunk_size.clamp(1, MAX_INSERT_CHUNK_SIZE);
+        for (chunk, chunk_ids) in items.chunks(chunk_size).zip(ids.chunks(chunk_size)) {
+            let sql = insert_items_sql(chunk.len());
+            let mut query = sqlx::query(&sql);
+            for (item, id) in chunk.iter().zip(chunk_ids) {
+                let (blob, norm) = quantization.encode(&item.embedding);
+                query = query
+                    .bind(id)
+                    .bind(&collection_id)
+                    .bind(item.namespace.clone().unwrap_or_default())
+                    .bind(item.parent_id.clone())
+                    .bind(blob)
+                    .bind(item.embedding.len() as i32)
+                    .bind(norm)
+                    .bind(item.document.clone())
+                    .bind(item.metadata.clone().map(sqlx::types::Json))
+                    .bind(item.uri.clone())
+                    .bind(item.data.clone().map(sqlx::types::Json))
+                    .bind(item.ttl_seconds)
+                    .bind(item.ttl_seconds);
+            }
+            query.execute(&mut *transaction).await?;
+        }
+        transaction.commit().await?;
+        Ok(VectorUpsertResult {
+            inserted_ids: ids,
+            purged,
+            ..VectorUpsertResult::default()
+        })
+    }
+
+    /// Inserts or replaces items in one transaction and reports which ids were new.
+    #[instrument(skip(self, items), fields(collection = collection_name, item_count = items.len()))]
+    pub async fn upsert_items(
+        &self,
+        collection_name: &str,
+        items: &[NewVectorItem],
+    ) -> Result<VectorUpsertResult, AppError> {
+        let (collection, schema) = self.write_target(collection_name).await?;
+        let mut result = VectorUpsertResult::default();
+        if items.is_empty() {
+            return Ok(result);
+        }
         for item in items {
             validate_embedding(&item.embedding, self.max_dimension)?;
-            let id = item
-                .id
-                .clone()
-                .unwrap_or_else(|| Uuid::new_v4().to_string());
-            let blob = encode_embedding(&item.embedding);
-            let norm = vector_norm(&item.embedding);
-            sqlx::query(
+            validate_metadata(schema.as_ref(), item.metadata.as_ref())?;
+            validate_columns(item.uri.as_deref(), item.data.as_ref())?;
+            validate_namespace(item.namespace.as_deref())?;
+        }
+        let dimension = check_dimensions(
+            &collection,
+            items.iter().map(|item| item.embedding.as_slice()),
+        )?;
+        let (collection_id, quantization) = (collection.id.clone(), collection.quantization);
+
+        let ids = items
+            .iter()
+            .map(|item| {
+                item.id
+                    .clone()
+                    .unwrap_or_else(|| Uuid::new_v4().to_string())
+            })
+            .collect::<Vec<String>>();
+
+        let mut transaction = self.pool.begin().await?;
+        result.purged = purge_expired(&mut transaction, &collection_id).await?;
+        record_dimension(&mut transaction, &collection, dimension).await?;
+        let placeholders = std::iter::repeat_n("?", ids.len())
+            .collect::<Vec<&str>>()
+            .join(", ");
+        let sql = format!(
+            "SELECT id, collection_id, namespace, CAST({} AS SIGNED) AS stored_bytes \
+             FROM vector_items WHERE id IN ({})",
+            ITEM_BYTES_SQL, placeholders
+        );
+        let mut existing_query = sqlx::query(&sql);
+        for id in &ids {
+            existing_query = existing_query.bind(id);
+        }
+        let existing = existing_query
+            .fetch_all(&mut *transaction)
+            .await?
+            .into_iter()
+            .map(|row| {
+                Ok((
+                    row.try_get::<String, _>("id")?,
+                    (
+                        row.try_get::<String, _>("collection_id")?,
+                        row.try_get::<String, _>("namespace")?,
+                        row.try_get::<i64, _>("stored_bytes")?.max(0) as u64,
+                    ),
+                ))
+            })
+            .collect::<Result<HashMap<String, (String, String, u64)>, AppError>>()?;
+        for (item, id) in items.iter().zip(&ids) {
+            let Some((owner, namespace, _)) = existing.get(id) else {
+                continue;
+            };
+            if *owner != collection_id {
+                return Err(AppError::validation(format!(
+                    "item id '{}' already belongs to another collection",
+                    id
+                )));
+            }
+            if namespace.as_str() != item.namespace.as_deref().unwrap_or_default() {
+                return Err(AppError::validation(format!(
+                    "item id '{}' already belongs to another namespace",
+                    id
+                )));
+            }
+        }
+
+        // Sizes of the stored rows, tracked through the batch so a repeated id replaces the
+        // size its earlier occurrence wrote.
+        let mut stored_bytes = existing
+            .into_iter()
+            .map(|(id, (_, _, bytes))| (id, bytes))
+            .collect::<HashMap<String, u64>>();
+        for (item, id) in items.iter().zip(ids) {
+            let (blob, norm) = quantization.encode(&item.embedding);
+            let outcome = sqlx::query(
                 r#"
                 INSERT INTO vector_items (
                     id,
                     collection_id,
+                    namespace,
+                    parent_id,
                     embedding_blob,
                     embedding_dim,
                     embedding_norm,
                     document,
-                    metadata
+                    metadata,
+                    uri,
+                    data,
+                    expires_at
                 )
-                VALUES (?, ?, ?, ?, ?, ?, ?)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, IF(? IS NULL, NULL, UTC_TIMESTAMP(6) + INTERVAL ? SECOND))
+                ON DUPLICATE KEY UPDATE
+                    parent_id = VALUES(parent_id),
+                    embedding_blob = VALUES(embedding_blob),
+                    embedding_dim = VALUES(embedding_dim),
+                    embedding_norm = VALUES(embedding_norm),
+                    document = VALUES(document),
+                    metadata = VALUES(metadata),
+                    uri = VALUES(uri),
+                    data = VALUES(data),
+                    expires_at = VALUES(expires_at),
+                    _updated_at = CURRENT_TIMESTAMP(6)
                 "#,
             )
             .bind(&id)
             .bind(&collection_id)
+            .bind(item.namespace.clone().unwrap_or_default())
+            .bind(item.parent_id.clone())
             .bind(blob)
             .bind(item.embedding.len() as i32)
             .bind(norm)
             .bind(item.document.clone())
             .bind(item.metadata.clone().map(sqlx::types::Json))
+            .bind(item.uri.clone())
+            .bind(item.data.clone().map(sqlx::types::Json))
+            .bind(item.ttl_seconds)
+            .bind(item.ttl_seconds)
             .execute(&mut *transaction)
             .await?;
-            inserted_ids.push(id);
+            // MySQL reports 1 affected row for a fresh insert and 2 (or 0 when unchanged)
+            // for a row replaced through ON DUPLICATE KEY UPDATE.
+            let previous_bytes = stored_bytes.insert(id.clone(), estimate_item_bytes(item));
+            if outcome.rows_affected() == 1 {
+                result.inserted_ids.push(id);
+            } else {
+                result.replaced_bytes += previous_bytes.unwrap_or(0);
+                result.updated_ids.push(id);
+            }
         }
         transaction.commit().await?;
-        Ok(inserted_ids)
+        Ok(result)
+    }
+
+    /// Deletes items whose TTL has elapsed and returns what was removed.
+    #[instrument(skip(self), fields(collection = collection_name))]
+    pub async fn purge_expired_items(
+        &self,
+        collection_name: &str,
+    ) -> Result<VectorRemoval, AppError> {
+        let collection_id = self.collection_id(collection_name).await?;
+        let mut transaction = self.pool.begin().await?;
+        let purged = purge_expired(&mut transaction, &collection_id).await?;
+        transaction.commit().await?;
+        Ok(purged)
     }
 
     /// Updates items in a collection and returns affected row count.
@@ -200,23 +819,30 @@ impl VectorRepository {
         collection_name: &str,
         items: &[UpdateVectorItem],
     ) -> Result<u64, AppError> {
-        let collection_id = self.collection_id(collection_name).await?;
+        let (collection, schema) = self.write_target(collection_name).await?;
         if items.is_empty() {
             return Ok(0);
         }
+        let dimension = check_dimensions(
+            &collection,
+            items.iter().filter_map(|item| item.embedding.as_deref()),
+        )?;
+        let (collection_id, quantization) = (collection.id.clone(), collection.quantization);
 
         let mut affected = 0_u64;
         let mut transaction = self.pool.begin().await?;
+        record_dimension(&mut transaction, &collection, dimension).await?;
         for item in items {
             if let Some(embedding) = &item.embedding {
                 validate_embedding(embedding, self.max_dimension)?;
             }
+            validate_columns(item.uri.as_deref(), item.data.as_ref())?;
 
             let row = sqlx::query(
                 r#"
-                SELECT embedding_blob, embedding_dim, embedding_norm, document, metadata
+                SELECT parent_id, embedding_blob, embedding_dim, embedding_norm, document, metadata
                 FROM vector_items
-                WHERE collection_id = ? AND id = ?
+                WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6)) AND id = ?
                 "#,
             )
             .bind(&collection_id)
@@ -231,12 +857,16 @@ impl VectorRepository {
             let next_embedding = if let Some(embedding) = &item.embedding {
                 embedding.clone()
             } else {
-                decode_embedding(&existing.try_get::<Vec<u8>, _>("embedding_blob")?)
-                    .map_err(AppError::validation)?
+                decode_row_embedding(&existing)?
             };
-            let next_blob = encode_embedding(&next_embedding);
-            let next_norm = vector_norm(&next_embedding);
+            let (next_blob, next_norm) = quantization.encode(&next_embedding);
             let next_dim = next_embedding.len() as i32;
+            let next_parent_id = item.parent_id.clone().or_else(|| {
+                existing
+                    .try_get::<Option<String>, _>("parent_id")
+                    .ok()
+                    .flatten()
+            });
             let next_document = item.document.clone().or_else(|| {
                 existing
                     .try_get::<Option<String>, _>("document")
@@ -250,24 +880,34 @@ impl VectorRepository {
                     .flatten()
                     .map(|json| json.0)
             });
+            validate_metadata(schema.as_ref(), next_metadata.as_ref())?;
 
             let result = sqlx::query(
                 r#"
                 UPDATE vector_items
-                SET embedding_blob = ?,
+                SET parent_id = ?,
+                    embedding_blob = ?,
                     embedding_dim = ?,
                     embedding_norm = ?,
                     document = ?,
                     metadata = ?,
+                    uri = COALESCE(?, uri),
+                    data = COALESCE(?, data),
+                    expires_at = IF(? IS NULL, expires_at, UTC_TIMESTAMP(6) + INTERVAL ? SECOND),
                     _updated_at = CURRENT_TIMESTAMP(6)
                 WHERE collection_id = ? AND id = ?
                 "#,
             )
+            .bind(next_parent_id)
             .bind(next_blob)
             .bind(next_dim)
             .bind(next_norm)
             .bind(next_document)
             .bind(next_metadata.map(sqlx::types::Json))
+            .bind(item.uri.clone())
+            .bind(item.data.clone().map(sqlx::types::Json))
+            .bind(item.ttl_seconds)
+            .bind(item.ttl_seconds)
             .bind(&collection_id)
             .bind(&item.id)
             .execute(&mut *transaction)
@@ -278,187 +918,1009 @@ impl VectorRepository {
         Ok(affected)
     }
 
-    /// Deletes items by ids and returns affected row count.
-    #[instrument(skip(self, ids), fields(collection = collection_name, item_count = ids.len()))]
+    /// Deletes items matching `ids` and/or a metadata filter in one statement and returns what
+    /// was removed. Nothing is deleted when both are empty.
+    #[instrument(skip(self, ids, filter), fields(collection = collection_name, item_count = ids.len()))]
     pub async fn delete_items(
         &self,
         collection_name: &str,
         ids: &[String],
-    ) -> Result<u64, AppError> {
+        filter: Option<&Value>,
+        namespace: Option<&str>,
+    ) -> Result<VectorRemoval, AppError> {
         let collection_id = self.collection_id(collection_name).await?;
-        if ids.is_empty() {
-            return Ok(0);
+        let compiled = filter
+            .map(compile_metadata_filter)
+            .transpose()?
+            .unwrap_or_default();
+        if ids.is_empty() && compiled.clause.is_none() {
+            return Ok(VectorRemoval::default());
         }
+        let compiled = scope_to_namespace(compiled, namespace);
 
-        let placeholders = std::iter::repeat_n("?", ids.len())
-            .collect::<Vec<&str>>()
-            .join(", ");
-        let sql = format!(
-            "DELETE FROM vector_items WHERE collection_id = ? AND id IN ({})",
-            placeholders
+        let mut condition = String::from("collection_id = ?");
+        let mut params = vec![SqlParam::String(collection_id)];
+        if !ids.is_empty() {
+            let placeholders = std::iter::repeat_n("?", ids.len())
+                .collect::<Vec<&str>>()
+                .join(", ");
+            condition.push_str(&format!(" AND id IN ({})", placeholders));
+            params.extend(ids.iter().cloned().map(SqlParam::String));
+        }
+        if let Some(clause) = &compiled.clause {
+            condition.push_str(&format!(" AND ({})", clause));
+            params.extend(compiled.params);
+        }
+        let mut transaction = self.pool.begin().await?;
+        let removed = remove_items(&mut transaction, &condition, &params).await?;
+        transaction.commit().await?;
+        Ok(removed)
+    }
+
+    /// Deletes every item of one namespace and returns what was removed.
+    #[instrument(skip(self), fields(collection = collection_name))]
+    pub async fn delete_namespace(
+        &self,
+        collection_name: &str,
+        namespace: &str,
+    ) -> Result<VectorRemoval, AppError> {
+        let collection_id = self.collection_id(collection_name).await?;
+        let mut transaction = self.pool.begin().await?;
+        let removed = remove_items(
+            &mut transaction,
+            "collection_id = ? AND namespace = ?",
+            &[
+                SqlParam::String(collection_id),
+                SqlParam::String(namespace.to_string()),
+            ],
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(removed)
+    }
+
+    /// Lists the namespaces holding live items with their item counts.
+    #[instrument(skip(self), fields(collection = collection_name))]
+    pub async fn list_namespaces(
+        &self,
+        collection_name: &str,
+    ) -> Result<Vec<VectorNamespaceCount>, AppError> {
+        let collection_id = self.collection_id(collection_name).await?;
+        let rows = sqlx::query(
+            r#"
+            SELECT namespace, COUNT(*) AS item_count
+            FROM vector_items
+            WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6))
+            GROUP BY namespace
+            ORDER BY namespace ASC
+            "#,
+        )
+        .bind(collection_id)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter()
+            .map(|row| {
+                Ok(VectorNamespaceCount {
+                    namespace: row.try_get::<String, _>("namespace")?,
+                    count: row.try_get::<i64, _>("item_count")? as u64,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns items by optional id and metadata filters.
+    ///
+    /// `page` applies `LIMIT`/`OFFSET` in SQL; paged reads are ordered by creation time
+    /// and id so consecutive pages do not overlap.
+    #[instrument(skip(self, ids, filter), fields(collection = collection_name, item_count = ids.len()))]
+    pub async fn get_items(
+        &self,
+        collection_name: &str,
+        ids: &[String],
+        filter: Option<&Value>,
+        namespace: Option<&str>,
+        page: Option<ItemPage>,
+    ) -> Result<Vec<VectorItemRecord>, AppError> {
+        let collection_id = self.collection_id(collection_name).await?;
+        let compiled = scope_to_namespace(
+            filter
+                .map(compile_metadata_filter)
+                .transpose()?
+                .unwrap_or_default(),
+            namespace,
         );
+
+        let mut sql =
+            String::from("SELECT id, parent_id, document, metadata FROM vector_items WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6))");
+        if !ids.is_empty() {
+            let placeholders = std::iter::repeat_n("?", ids.len())
+                .collect::<Vec<&str>>()
+                .join(", ");
+            sql.push_str(&format!(" AND id IN ({})", placeholders));
+        }
+        if let Some(clause) = &compiled.clause {
+            sql.push_str(&format!(" AND ({})", clause));
+        }
+        if page.is_some() {
+            sql.push_str(" ORDER BY _created_at ASC, id ASC LIMIT ? OFFSET ?");
+        } else if ids.is_empty() {
+            sql.push_str(" ORDER BY _created_at ASC");
+        }
+
         let mut query = sqlx::query(&sql).bind(collection_id);
         for id in ids {
             query = query.bind(id);
         }
-        let result = query.execute(&self.pool).await?;
-        Ok(result.rows_affected())
+        let mut query = bind_params(query, &compiled.params);
+        if let Some(page) = page {
+            query = query.bind(page.limit.unwrap_or(u64::MAX)).bind(page.offset);
+        }
+        let rows = query.fetch_all(&self.pool).await?;
+        rows.into_iter().map(row_to_item).collect()
     }
 
-    /// Returns items by optional id filter.
+    /// Returns items for `ids` in request order and reports ids that were not found.
     #[instrument(skip(self, ids), fields(collection = collection_name, item_count = ids.len()))]
-    pub async fn get_items(
+    pub async fn get_items_ordered(
         &self,
         collection_name: &str,
         ids: &[String],
-    ) -> Result<Vec<VectorItemRecord>, AppError> {
-        let collection_id = self.collection_id(collection_name).await?;
+    ) -> Result<VectorBatchGetResult, AppError> {
         if ids.is_empty() {
-            let rows = sqlx::query(
-                r#"
-                SELECT id, document, metadata
-                FROM vector_items
-                WHERE collection_id = ?
-                ORDER BY _created_at ASC
-                "#,
-            )
-            .bind(&collection_id)
-            .fetch_all(&self.pool)
-            .await?;
-            return rows.into_iter().map(row_to_item).collect();
+            return Err(AppError::validation("ids cannot be empty"));
         }
+        let mut found = self
+            .get_items(collection_name, ids, None, None, None)
+            .await?
+            .into_iter()
+            .map(|item| (item.id.clone(), item))
+            .collect::<std::collections::HashMap<String, VectorItemRecord>>();
+        Ok(order_by_request(ids, |id| found.remove(id)))
+    }
 
+    /// Returns the URI and data columns of the live items among `ids`, keyed by id, plus their
+    /// decoded embeddings when `with_embeddings` is set.
+    #[instrument(skip(self, ids), fields(collection = collection_name, item_count = ids.len()))]
+    pub async fn item_columns(
+        &self,
+        collection_name: &str,
+        ids: &[String],
+        with_embeddings: bool,
+    ) -> Result<HashMap<String, VectorItemColumns>, AppError> {
+        let collection_id = self.collection_id(collection_name).await?;
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
         let placeholders = std::iter::repeat_n("?", ids.len())
             .collect::<Vec<&str>>()
             .join(", ");
+        let columns = if with_embeddings {
+            "id, uri, data, embedding_blob, embedding_dim"
+        } else {
+            "id, uri, data"
+        };
         let sql = format!(
-            "SELECT id, document, metadata FROM vector_items WHERE collection_id = ? AND id IN ({})",
-            placeholders
+            "SELECT {} FROM vector_items WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6)) AND id IN ({})",
+            columns, placeholders
         );
         let mut query = sqlx::query(&sql).bind(collection_id);
         for id in ids {
             query = query.bind(id);
         }
         let rows = query.fetch_all(&self.pool).await?;
-        rows.into_iter().map(row_to_item).collect()
+        rows.into_iter()
+            .map(|row| {
+                let mut columns = row_to_columns(&row)?;
+                if with_embeddings {
+                    columns.embedding = Some(decode_row_embedding(&row)?);
+                }
+                Ok((row.try_get::<String, _>("id")?, columns))
+            })
+            .collect()
     }
 
-    /// Runs cosine-similarity search and returns top-k per query embedding.
-    #[instrument(skip(self, query_embeddings), fields(collection = collection_name, query_count = query_embeddings.len(), n_results = n_results))]
-    pub async fn query(
-        &self,
-        collection_name: &str,
-        query_embeddings: &[Vec<f32>],
-        n_results: u32,
-    ) -> Result<VectorQueryResult, AppError> {
-        if query_embeddings.is_empty() {
-            return Err(AppError::validation("query_embeddings cannot be empty"));
+    /// Returns which of `ids` are held by a live item in any collection. Upserts replace those
+    /// ids instead of inserting them.
+    #[instrument(skip(self, ids), fields(item_count = ids.len()))]
+    pub async fn live_item_ids(&self, ids: &[String]) -> Result<HashSet<String>, AppError> {
+        if ids.is_empty() {
+            return Ok(HashSet::new());
         }
-        for embedding in query_embeddings {
-            validate_embedding(embedding, self.max_dimension)?;
+        let placeholders = std::iter::repeat_n("?", ids.len())
+            .collect::<Vec<&str>>()
+            .join(", ");
+        let sql = format!(
+            "SELECT id FROM vector_items WHERE (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6)) AND id IN ({})",
+            placeholders
+        );
+        let mut query = sqlx::query_scalar::<_, String>(&sql);
+        for id in ids {
+            query = query.bind(id);
         }
+        Ok(query.fetch_all(&self.pool).await?.into_iter().collect())
+    }
 
+    /// Returns whether an item id exists in the collection.
+    #[instrument(skip(self), fields(collection = collection_name))]
+    pub async fn item_exists(&self, collection_name: &str, id: &str) -> Result<bool, AppError> {
         let collection_id = self.collection_id(collection_name).await?;
-        let rows = sqlx::query(
+        let exists = sqlx::query_scalar::<_, i64>(
             r#"
-            SELECT id, embedding_blob, embedding_dim, embedding_norm, document, metadata
-            FROM vector_items
-            WHERE collection_id = ?
+            SELECT EXISTS(
+                SELECT 1 FROM vector_items WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6)) AND id = ?
+            )
             "#,
         )
         .bind(collection_id)
-        .fetch_all(&self.pool)
+        .bind(id)
+        .fetch_one(&self.pool)
         .await?;
+        Ok(exists != 0)
+    }
 
-        let candidates = rows
-            .into_iter()
-            .map(|row| -> Result<VectorCandidate, AppError> {
-                let embedding_blob = row.try_get::<Vec<u8>, _>("embedding_blob")?;
-                let vector = decode_embedding(&embedding_blob).map_err(AppError::validation)?;
-                let embedding_dim = row.try_get::<i32, _>("embedding_dim")? as usize;
-                if vector.len() != embedding_dim {
-                    return Err(AppError::internal(
-                        "vector blob length and embedding_dim mismatch",
-                    ));
-                }
-                Ok(VectorCandidate {
-                    id: row.try_get::<String, _>("id")?,
-                    vector,
-                    norm: row.try_get::<f64, _>("embedding_norm")?,
-                    document: row.try_get::<Option<String>, _>("document")?,
-                    metadata: row
-                        .try_get::<Option<sqlx::types::Json<Value>>, _>("metadata")?
-                        .map(|json| json.0),
-                })
-            })
-            .collect::<Result<Vec<VectorCandidate>, AppError>>()?;
+    /// Counts items, optionally restricted by a metadata filter.
+    #[instrument(skip(self, filter), fields(collection = collection_name))]
+    pub async fn count_items(
+        &self,
+        collection_name: &str,
+        filter: Option<&Value>,
+    ) -> Result<u64, AppError> {
+        let collection_id = self.collection_id(collection_name).await?;
+        let compiled = filter
+            .map(compile_metadata_filter)
+            .transpose()?
+            .unwrap_or_default();
+        let mut sql = String::from("SELECT COUNT(*) FROM vector_items WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6))");
+        if let Some(clause) = &compiled.clause {
+            sql.push_str(&format!(" AND ({})", clause));
+        }
+        let query = sqlx::query(&sql).bind(collection_id);
+        let row = bind_params(query, &compiled.params)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.try_get::<i64, _>(0)? as u64)
+    }
 
-        let top_k = n_results.max(1) as usize;
-        let mut response_ids = Vec::<Vec<String>>::new();
-        let mut response_documents = Vec::<Vec<Option<String>>>::new();
-        let mut response_metadatas = Vec::<Vec<Option<Value>>>::new();
-        let mut response_distances = Vec::<Vec<f64>>::new();
+    /// Returns item count, dimensions, approximate size, timestamps and top metadata keys.
+    #[instrument(skip(self), fields(collection = collection_name))]
+    pub async fn collection_stats(
+        &self,
+        collection_name: &str,
+    ) -> Result<VectorCollectionStats, AppError> {
+        let collection = self.collection(collection_name).await?;
+        let totals = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS item_count,
+                CAST(AVG(embedding_dim) AS DOUBLE) AS average_dimension,
+                CAST(COALESCE(SUM(
+                    LENGTH(embedding_blob) + COALESCE(LENGTH(document), 0)
+                    + COALESCE(LENGTH(metadata), 0) + COALESCE(LENGTH(uri), 0)
+                    + COALESCE(LENGTH(data), 0)
+                ), 0) AS SIGNED) AS approximate_bytes,
+                MAX(_updated_at) AS last_updated_at
+            FROM vector_items
+            WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6))
+            "#,
+        )
+        .bind(&collection.id)
+        .fetch_one(&self.pool)
+        .await?;
+        let last_dimension = sqlx::query_scalar::<_, i32>(
+            r#"
+            SELECT embedding_dim
+            FROM vector_items
+            WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6))
+            ORDER BY _updated_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(&collection.id)
+        .fetch_optional(&self.pool)
+        .await?;
+        let key_rows = sqlx::query(
+            r#"
+            SELECT metadata_keys.metadata_key, COUNT(*) AS key_count
+            FROM vector_items,
+                JSON_TABLE(
+                    JSON_KEYS(vector_items.metadata), '$[*]'
+                    COLUMNS (metadata_key VARCHAR(255) PATH '$')
+                ) AS metadata_keys
+            WHERE vector_items.collection_id = ?
+                AND JSON_TYPE(vector_items.metadata) = 'OBJECT'
+                AND (vector_items.expires_at IS NULL OR vector_items.expires_at > UTC_TIMESTAMP(6))
+            GROUP BY metadata_keys.metadata_key
+            ORDER BY key_count DESC, metadata_keys.metadata_key ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(&collection.id)
+        .bind(STATS_TOP_METADATA_KEYS)
+        .fetch_all(&self.pool)
+        .await?;
 
-        for query_embedding in query_embeddings {
-            let mut scored = candidates
-                .iter()
-                .filter(|candidate| candidate.vector.len() == query_embedding.len())
-                .map(|candidate| {
-                    let similarity =
-                        cosine_similarity(query_embedding, &candidate.vector, candidate.norm);
-                    (candidate, similarity)
+        let last_updated_at = totals
+            .try_get::<Option<chrono::NaiveDateTime>, _>("last_updated_at")?
+            .map(|value| value.to_string());
+        let updated_at = match last_updated_at {
+            Some(item_updated_at) if item_updated_at > collection.updated_at => item_updated_at,
+            _ => collection.updated_at,
+        };
+        Ok(VectorCollectionStats {
+            name: collection.name,
+            item_count: totals.try_get::<i64, _>("item_count")? as u64,
+            average_dimension: totals.try_get::<Option<f64>, _>("average_dimension")?,
+            last_dimension: last_dimension.map(|dimension| dimension as u32),
+            approximate_bytes: totals.try_get::<i64, _>("approximate_bytes")?.max(0) as u64,
+            created_at: collection.created_at,
+            updated_at,
+            top_metadata_keys: key_rows
+                .into_iter()
+                .map(|row| {
+                    Ok(VectorMetadataKeyCount {
+                        key: row.try_get::<String, _>("metadata_key")?,
+                        count: row.try_get::<i64, _>("key_count")? as u64,
+                    })
                 })
-                .collect::<Vec<(&VectorCandidate, f64)>>();
+                .collect::<Result<Vec<VectorMetadataKeyCount>, AppError>>()?,
+        })
+    }
+
+    /// Returns one page of items ordered by id, starting after `cursor`.
+    ///
+    /// Pages are keyed on the primary key rather than an offset, so concurrent inserts
+    /// and deletes never cause rows to be skipped or repeated across pages.
+    #[instrument(skip(self, cursor, filter), fields(collection = collection_name, batch_size = batch_size))]
+    pub async fn scroll(
+        &self,
+        collection_name: &str,
+        cursor: Option<&str>,
+        batch_size: u32,
+        filter: Option<&Value>,
+    ) -> Result<VectorScrollPage, AppError> {
+        let collection_id = self.collection_id(collection_name).await?;
+        let batch_size = batch_size.clamp(1, MAX_SCROLL_BATCH_SIZE);
+        let compiled = filter
+            .map(compile_metadata_filter)
+            .transpose()?
+            .unwrap_or_default();
 
-            scored.sort_by(|left, right| {
-                right
-                    .1
-                    .partial_cmp(&left.1)
-                    .unwrap_or(std::cmp::Ordering::Equal)
+        let rows = self
+            .fetch_keyset_page(
+                "id, parent_id, document, metadata",
+                &collection_id,
+                cursor,
+                batch_size,
+                &compiled,
+            )
+            .await?;
+        let items = rows
+            .into_iter()
+            .map(row_to_item)
+            .collect::<Result<Vec<VectorItemRecord>, AppError>>()?;
+        let next_cursor = if items.len() == batch_size as usize {
+            items.last().map(|item| item.id.clone())
+        } else {
+            None
+        };
+        Ok(VectorScrollPage { items, next_cursor })
+    }
+
+    /// Reads one id-ordered page of live items with their embeddings for snapshot export.
+    ///
+    /// Takes the collection id (see `collection`) so a streamed export resolves the name
+    /// once; pass the previous page's `next_cursor` to continue.
+    #[instrument(skip(self, cursor), fields(collection_id = collection_id))]
+    pub async fn export_page(
+        &self,
+        collection_id: &str,
+        cursor: Option<&str>,
+    ) -> Result<VectorSnapshotPage, AppError> {
+        let rows = self
+            .fetch_keyset_page(
+                "id, namespace, parent_id, embedding_blob, embedding_dim, embedding_norm, document, metadata, uri, data",
+                collection_id,
+                cursor,
+                MAX_SCROLL_BATCH_SIZE,
+                &CompiledWhere::default(),
+            )
+            .await?;
+        let page_full = rows.len() == MAX_SCROLL_BATCH_SIZE as usize;
+        let mut records = Vec::<VectorSnapshotRecord>::with_capacity(rows.len());
+        for row in rows {
+            let columns = row_to_columns(&row)?;
+            let namespace = row.try_get::<String, _>("namespace")?;
+            let candidate = row_to_candidate(row)?;
+            records.push(VectorSnapshotRecord {
+                id: candidate.id,
+                namespace: Some(namespace).filter(|namespace| !namespace.is_empty()),
+                parent_id: candidate.parent_id,
+                embedding: candidate.vector,
+                document: candidate.document,
+                metadata: candidate.metadata,
+                uri: columns.uri,
+                data: columns.data,
             });
-            scored.truncate(top_k);
+        }
+        let next_cursor = page_full
+            .then(|| records.last().map(|record| record.id.clone()))
+            .flatten();
+        Ok(VectorSnapshotPage {
+            records,
+            next_cursor,
+        })
+    }
 
-            response_ids.push(
-                scored
-                    .iter()
-                    .map(|(candidate, _)| candidate.id.clone())
-                    .collect(),
-            );
-            response_documents.push(
-                scored
-                    .iter()
-                    .map(|(candidate, _)| candidate.document.clone())
-                    .collect(),
-            );
-            response_metadatas.push(
-                scored
-                    .iter()
-                    .map(|(candidate, _)| candidate.metadata.clone())
-                    .collect(),
-            );
-            response_distances.push(
-                scored
-                    .iter()
-                    .map(|(_, similarity)| 1.0 - similarity)
-                    .collect(),
+    /// Scores one id-ordered page of items against a query vector.
+    ///
+    /// Unlike `query`, nothing beyond the current page is held in memory, so callers can
+    /// walk collections of any size by following `next_cursor`. Items below
+    /// `min_similarity` are dropped from the page but still advance the cursor.
+    #[instrument(skip(self, query_embedding, cursor, filter), fields(collection = collection_name, batch_size = batch_size))]
+    pub async fn scan_scores(
+        &self,
+        collection_name: &str,
+        query_embedding: &[f32],
+        cursor: Option<&str>,
+        batch_size: u32,
+        filter: Option<&Value>,
+        min_similarity: Option<f64>,
+    ) -> Result<VectorScoredPage, AppError> {
+        validate_embedding(query_embedding, self.max_dimension)?;
+        let collection = self.collection(collection_name).await?;
+        check_dimensions(&collection, [query_embedding])?;
+        let collection_id = collection.id;
+        let batch_size = batch_size.clamp(1, MAX_SCROLL_BATCH_SIZE);
+        let compiled = filter
+            .map(compile_metadata_filter)
+            .transpose()?
+            .unwrap_or_default();
+
+        let rows = self
+            .fetch_keyset_page(
+                "id, parent_id, embedding_blob, embedding_dim, embedding_norm, document, metadata",
+                &collection_id,
+                cursor,
+                batch_size,
+                &compiled,
+            )
+            .await?;
+        let page_full = rows.len() == batch_size as usize;
+        let candidates = rows
+            .into_iter()
+            .map(row_to_candidate)
+            .collect::<Result<Vec<VectorCandidate>, AppError>>()?;
+        let next_cursor = if page_full {
+            candidates.last().map(|candidate| candidate.id.clone())
+        } else {
+            None
+        };
+
+        let query_norm = vector_norm(query_embedding);
+        let items = candidates
+            .into_iter()
+            .filter(|candidate| candidate.vector.len() == query_embedding.len())
+            .filter_map(|candidate| {
+                let similarity = cosine_similarity_with_norms(
+                    query_embedding,
+                    query_norm,
+                    &candidate.vector,
+                    candidate.norm,
+                );
+                if min_similarity.is_some_and(|threshold| similarity < threshold) {
+                    return None;
+                }
+                Some(VectorScoredItem {
+                    id: candidate.id,
+                    parent_id: candidate.parent_id,
+                    document: candidate.document,
+                    metadata: candidate.metadata,
+                    distance: 1.0 - similarity,
+                })
+            })
+            .collect();
+        Ok(VectorScoredPage { items, next_cursor })
+    }
+
+    /// Counts distinct values of the given metadata keys, optionally within a filter.
+    #[instrument(skip(self, keys, filter), fields(collection = collection_name, key_count = keys.len(), limit = limit))]
+    pub async fn facets(
+        &self,
+        collection_name: &str,
+        keys: &[String],
+        filter: Option<&Value>,
+        limit: u32,
+    ) -> Result<Vec<VectorFacet>, AppError> {
+        if keys.is_empty() {
+            return Err(AppError::validation("keys cannot be empty"));
+        }
+        let collection_id = self.collection_id(collection_name).await?;
+        let limit = limit.clamp(1, MAX_FACET_VALUES);
+        let compiled = filter
+            .map(compile_metadata_filter)
+            .transpose()?
+            .unwrap_or_default();
+        let filter_sql = compiled
+            .clause
+            .as_ref()
+            .map(|clause| format!(" AND ({})", clause))
+            .unwrap_or_default();
+        let sql = format!(
+            "SELECT CAST(JSON_EXTRACT(metadata, ?) AS CHAR) AS facet_value, COUNT(*) AS facet_count \
+             FROM vector_items \
+             WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6)) AND JSON_EXTRACT(metadata, ?) IS NOT NULL{} \
+             GROUP BY facet_value \
+             ORDER BY facet_count DESC, facet_value ASC \
+             LIMIT {}",
+            filter_sql, limit
+        );
+
+        let mut facets = Vec::<VectorFacet>::with_capacity(keys.len());
+        for key in keys {
+            let path = metadata_path(key)?;
+            let query = sqlx::query(&sql)
+                .bind(&path)
+                .bind(&collection_id)
+                .bind(&path);
+            let rows = bind_params(query, &compiled.params)
+                .fetch_all(&self.pool)
+                .await?;
+            let values = rows
+                .iter()
+                .map(|row| {
+                    let raw = row.try_get::<String, _>("facet_value")?;
+                    Ok(VectorFacetValue {
+                        value: serde_json::from_str(&raw).unwrap_or(Value::String(raw)),
+                        count: row.try_get::<i64, _>("facet_count")? as u64,
+                    })
+                })
+                .collect::<Result<Vec<VectorFacetValue>, AppError>>()?;
+            facets.push(VectorFacet {
+                key: key.clone(),
+                values,
+            });
+        }
+        Ok(facets)
+    }
+
+    /// Runs cosine-similarity search and returns top-k per query embedding.
+    ///
+    /// With `grouping`, `n_results` counts distinct metadata values instead of items and
+    /// each group keeps its best `group_size` matches. With `mmr`, the `fetch_k` most
+    /// similar items are re-ranked to penalise near-duplicates. With `dedupe_by_parent`,
+    /// only the best chunk of each parent document is kept; items without a parent stand
+    /// for themselves.
+    #[instrument(skip(self, query_embeddings, options), fields(collection = collection_name, query_count = query_embeddings.len(), n_results = n_results))]
+    pub async fn query(
+        &self,
+        collection_name: &str,
+        query_embeddings: &[Vec<f32>],
+        n_results: u32,
+        options: &VectorQueryOptions,
+    ) -> Result<VectorQueryResult, AppError> {
+        if query_embeddings.is_empty() {
+            return Err(AppError::validation("query_embeddings cannot be empty"));
+        }
+        for embedding in query_embeddings {
+            validate_embedding(embedding, self.max_dimension)?;
+        }
+        if options.grouping.is_some() && options.mmr.is_some() {
+            return Err(AppError::validation(
+                "group_by and mmr cannot be combined in one query",
+            ));
+        }
+        if options.grouping.is_some() && options.dedupe_by_parent {
+            return Err(AppError::validation(
+                "group_by and dedupe_by_parent cannot be combined in one query",
+            ));
+        }
+        if let Some(mmr) = &options.mmr {
+            if !(0.0..=1.0).contains(&mmr.lambda) {
+                return Err(AppError::validation("mmr lambda must be between 0 and 1"));
+            }
+        }
+
+        let started = Instant::now();
+        let collection = self.collection(collection_name).await?;
+        check_dimensions(&collection, query_embeddings.iter().map(Vec::as_slice))?;
+        let collection_id = collection.id;
+        let compiled = scope_to_namespace(
+            options
+                .filter
+                .as_ref()
+                .map(compile_metadata_filter)
+                .transpose()?
+                .unwrap_or_default(),
+            options.namespace.as_deref(),
+        );
+        let filtered = compiled.clause.is_some();
+        let (candidates, from_cache) = self
+            .load_candidates_with_source(&collection_id, &compiled)
+            .await?;
+        let loaded = started.elapsed();
+
+        let top_k = n_results.max(1) as usize;
+        let mut result = if let Some(grouping) = &options.grouping {
+            let key_path = grouping_key_path(&grouping.key)?;
+            let grouped = query_embeddings
+                .iter()
+                .map(|query_embedding| {
+                    let scored = rank_candidates(&candidates, query_embedding, usize::MAX, &[]);
+                    group_ranked(scored, &key_path, top_k, grouping.group_size.max(1))
+                })
+                .collect::<Vec<Vec<ScoredGroup>>>();
+            build_grouped_query_result(grouped)
+        } else {
+            rank_query(&candidates, query_embeddings, top_k, options)
+        };
+        if !options.explain {
+            return Ok(result);
+        }
+
+        let scored = started.elapsed();
+        let collection_items = if filtered {
+            self.cache_token(&collection_id).await?.live_count.max(0) as u64
+        } else {
+            candidates.len() as u64
+        };
+        let dimension = query_embeddings[0].len();
+        let eligible = candidates
+            .iter()
+            .filter(|candidate| candidate.vector.len() == dimension)
+            .count() as u64;
+        let millis = |duration: std::time::Duration| duration.as_secs_f64() * 1000.0;
+        result.explain = Some(VectorQueryExplain {
+            index: "exact".to_string(),
+            candidate_source: if from_cache { "cache" } else { "database" }.to_string(),
+            collection_items,
+            candidates_scanned: candidates.len() as u64,
+            candidates_filtered: collection_items.saturating_sub(candidates.len() as u64),
+            candidates_scored: eligible * query_embeddings.len() as u64,
+            results_returned: result.ids.iter().map(Vec::len).sum::<usize>() as u64,
+            load_ms: millis(loaded),
+            score_ms: millis(scored - loaded),
+            total_ms: millis(started.elapsed()),
+        });
+        Ok(result)
+    }
+
+    /// Searches item documents through the FULLTEXT index, best match first.
+    #[instrument(skip(self, query_text, filter), fields(collection = collection_name, n_results = n_results))]
+    pub async fn text_search(
+        &self,
+        collection_name: &str,
+        query_text: &str,
+        n_results: u32,
+        filter: Option<&Value>,
+    ) -> Result<VectorTextSearchResult, AppError> {
+        if query_text.trim().is_empty() {
+            return Err(AppError::validation("query cannot be empty"));
+        }
+        let collection_id = self.collection_id(collection_name).await?;
+        let compiled = filter
+            .map(compile_metadata_filter)
+            .transpose()?
+            .unwrap_or_default();
+        let mut sql = String::from(
+            "SELECT id, parent_id, document, metadata, \
+             MATCH(document) AGAINST(? IN NATURAL LANGUAGE MODE) AS text_score \
+             FROM vector_items \
+             WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6)) AND MATCH(document) AGAINST(? IN NATURAL LANGUAGE MODE)",
+        );
+        if let Some(clause) = &compiled.clause {
+            sql.push_str(&format!(" AND ({})", clause));
+        }
+        sql.push_str(&format!(
+            " ORDER BY text_score DESC, id ASC LIMIT {}",
+            n_results.max(1)
+        ));
+
+        let query = sqlx::query(&sql)
+            .bind(query_text)
+            .bind(collection_id)
+            .bind(query_text);
+        let rows = bind_params(query, &compiled.params)
+            .fetch_all(&self.pool)
+            .await?;
+        let mut result = VectorTextSearchResult {
+            items: Vec::with_capacity(rows.len()),
+            scores: Vec::with_capacity(rows.len()),
+        };
+        for row in rows {
+            result.scores.push(row.try_get::<f64, _>("text_score")?);
+            result.items.push(row_to_item(row)?);
+        }
+        Ok(result)
+    }
+
+    /// Blends cosine similarity with full-text relevance over `document`.
+    ///
+    /// Each candidate scores `alpha * cosine + (1 - alpha) * text`, where the text score is
+    /// the MySQL FULLTEXT relevance scaled by the best match in the candidate set.
+    #[instrument(skip(self, query_embedding, query_text, filter), fields(collection = collection_name, alpha = alpha, n_results = n_results))]
+    pub async fn hybrid_query(
+        &self,
+        collection_name: &str,
+        query_embedding: &[f32],
+        query_text: &str,
+        alpha: f64,
+        n_results: u32,
+        filter: Option<&Value>,
+    ) -> Result<VectorQueryResult, AppError> {
+        validate_embedding(query_embedding, self.max_dimension)?;
+        if query_text.trim().is_empty() {
+            return Err(AppError::validation("query_text cannot be empty"));
+        }
+        if !(0.0..=1.0).contains(&alpha) {
+            return Err(AppError::validation("alpha must be between 0 and 1"));
+        }
+
+        let collection = self.collection(collection_name).await?;
+        check_dimensions(&collection, [query_embedding])?;
+        let collection_id = collection.id;
+        let compiled = filter
+            .map(compile_metadata_filter)
+            .transpose()?
+            .unwrap_or_default();
+        let mut sql = String::from(
+            "SELECT id, parent_id, embedding_blob, embedding_dim, embedding_norm, document, metadata, \
+             MATCH(document) AGAINST(? IN NATURAL LANGUAGE MODE) AS text_score \
+             FROM vector_items WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6))",
+        );
+        if let Some(clause) = &compiled.clause {
+            sql.push_str(&format!(" AND ({})", clause));
+        }
+        let query = sqlx::query(&sql).bind(query_text).bind(collection_id);
+        let rows = bind_params(query, &compiled.params)
+            .fetch_all(&self.pool)
+            .await?;
+        let candidates = rows
+            .into_iter()
+            .map(|row| {
+                let text_score = row.try_get::<f64, _>("text_score")?;
+                Ok((row_to_candidate(row)?, text_score))
+            })
+            .collect::<Result<Vec<(VectorCandidate, f64)>, AppError>>()?;
+
+        let top_k = n_results.max(1) as usize;
+        let ranked = hybrid_rank(&candidates, query_embedding, alpha, top_k);
+        Ok(build_query_result(vec![ranked]))
+    }
+
+    /// Queries by the centroid of stored items ("more like these"), excluding the seed ids.
+    #[instrument(skip(self, ids), fields(collection = collection_name, item_count = ids.len(), n_results = n_results))]
+    pub async fn query_by_ids(
+        &self,
+        collection_name: &str,
+        ids: &[String],
+        n_results: u32,
+    ) -> Result<VectorQueryResult, AppError> {
+        if ids.is_empty() {
+            return Err(AppError::validation("ids cannot be empty"));
+        }
+
+        let collection_id = self.collection_id(collection_name).await?;
+        let seeds = self
+            .load_embeddings(&collection_id, ids)
+            .await?
+            .into_iter()
+            .map(|(_, embedding)| embedding)
+            .collect::<Vec<Vec<f32>>>();
+        if seeds.is_empty() {
+            return Err(AppError::not_found(format!(
+                "none of the requested ids exist in collection '{}'",
+                collection_name
+            )));
+        }
+        let centroid = average_embeddings(&seeds).map_err(AppError::validation)?;
+
+        let candidates = self
+            .load_candidates(&collection_id, &CompiledWhere::default())
+            .await?;
+        let top_k = n_results.max(1) as usize;
+        let ranked = rank_candidates(&candidates, &centroid, top_k, ids);
+        Ok(build_query_result(vec![ranked]))
+    }
+
+    /// Recommends items similar to positive examples and dissimilar to negative ones.
+    ///
+    /// The query vector is `2 * mean(positive) - mean(negative)` (or the positive centroid
+    /// when no negative example exists); all example ids are excluded from results.
+    #[instrument(skip(self, positive_ids, negative_ids), fields(collection = collection_name, positive_count = positive_ids.len(), negative_count = negative_ids.len(), n_results = n_results))]
+    pub async fn recommend(
+        &self,
+        collection_name: &str,
+        positive_ids: &[String],
+        negative_ids: &[String],
+        n_results: u32,
+    ) -> Result<VectorQueryResult, AppError> {
+        if positive_ids.is_empty() {
+            return Err(AppError::validation("positive_ids cannot be empty"));
+        }
+
+        let collection_id = self.collection_id(collection_name).await?;
+        let seed_ids = positive_ids
+            .iter()
+            .chain(negative_ids.iter())
+            .cloned()
+            .collect::<Vec<String>>();
+        let mut positives = Vec::<Vec<f32>>::new();
+        let mut negatives = Vec::<Vec<f32>>::new();
+        for (id, embedding) in self.load_embeddings(&collection_id, &seed_ids).await? {
+            if positive_ids.contains(&id) {
+                positives.push(embedding);
+            } else {
+                negatives.push(embedding);
+            }
+        }
+        if positives.is_empty() {
+            return Err(AppError::not_found(format!(
+                "none of the positive ids exist in collection '{}'",
+                collection_name
+            )));
+        }
+
+        let positive_centroid = average_embeddings(&positives).map_err(AppError::validation)?;
+        let query_vector = if negatives.is_empty() {
+            positive_centroid
+        } else {
+            let negative_centroid = average_embeddings(&negatives).map_err(AppError::validation)?;
+            weighted_sum(&[(&positive_centroid, 2.0), (&negative_centroid, -1.0)])
+                .map_err(AppError::validation)?
+        };
+
+        let candidates = self
+            .load_candidates(&collection_id, &CompiledWhere::default())
+            .await?;
+        let top_k = n_results.max(1) as usize;
+        let ranked = rank_candidates(&candidates, &query_vector, top_k, &seed_ids);
+        Ok(build_query_result(vec![ranked]))
+    }
+
+    async fn fetch_keyset_page(
+        &self,
+        columns: &str,
+        collection_id: &str,
+        cursor: Option<&str>,
+        batch_size: u32,
+        filter: &CompiledWhere,
+    ) -> Result<Vec<sqlx::mysql::MySqlRow>, AppError> {
+        let mut sql = format!(
+            "SELECT {} FROM vector_items WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6))",
+            columns
+        );
+        if cursor.is_some() {
+            sql.push_str(" AND id > ?");
+        }
+        if let Some(clause) = &filter.clause {
+            sql.push_str(&format!(" AND ({})", clause));
+        }
+        sql.push_str(&format!(" ORDER BY id ASC LIMIT {}", batch_size));
+
+        let mut query = sqlx::query(&sql).bind(collection_id);
+        if let Some(cursor) = cursor {
+            query = query.bind(cursor);
+        }
+        Ok(bind_params(query, &filter.params)
+            .fetch_all(&self.pool)
+            .await?)
+    }
+
+    /// Loads scoring candidates, serving unfiltered loads from the embedding cache while
+    /// the collection's cache token is unchanged.
+    async fn load_candidates(
+        &self,
+        collection_id: &str,
+        filter: &CompiledWhere,
+    ) -> Result<Arc<Vec<VectorCandidate>>, AppError> {
+        Ok(self
+            .load_candidates_with_source(collection_id, filter)
+            .await?
+            .0)
+    }
+
+    /// Loads candidates and reports whether they were served from the candidate cache.
+    async fn load_candidates_with_source(
+        &self,
+        collection_id: &str,
+        filter: &CompiledWhere,
+    ) -> Result<(Arc<Vec<VectorCandidate>>, bool), AppError> {
+        if filter.clause.is_some() || self.cache_budget_bytes == 0 {
+            return Ok((
+                Arc::new(self.fetch_candidates(collection_id, filter).await?),
+                false,
+            ));
+        }
+
+        let token = self.cache_token(collection_id).await?;
+        if let Ok(mut cache) = candidate_cache().lock() {
+            if let Some(cached) = cache.get(collection_id, &token) {
+                return Ok((cached, true));
+            }
+        }
+        let candidates = Arc::new(self.fetch_candidates(collection_id, filter).await?);
+        let bytes = candidates
+            .iter()
+            .map(VectorCandidate::approximate_bytes)
+            .sum();
+        if let Ok(mut cache) = candidate_cache().lock() {
+            cache.insert(
+                collection_id,
+                token,
+                candidates.clone(),
+                bytes,
+                self.cache_budget_bytes,
             );
         }
+        Ok((candidates, false))
+    }
 
-        Ok(VectorQueryResult {
-            ids: response_ids,
-            documents: response_documents,
-            metadatas: response_metadatas,
-            distances: response_distances,
+    async fn cache_token(&self, collection_id: &str) -> Result<CacheToken, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS live_count, MAX(_updated_at) AS max_updated_at
+            FROM vector_items
+            WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6))
+            "#,
+        )
+        .bind(collection_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(CacheToken {
+            live_count: row.try_get::<i64, _>("live_count")?,
+            max_updated_at: row.try_get::<Option<chrono::NaiveDateTime>, _>("max_updated_at")?,
         })
     }
 
+    async fn fetch_candidates(
+        &self,
+        collection_id: &str,
+        filter: &CompiledWhere,
+    ) -> Result<Vec<VectorCandidate>, AppError> {
+        let mut sql = String::from(
+            "SELECT id, parent_id, embedding_blob, embedding_dim, embedding_norm, document, metadata \
+             FROM vector_items WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6))",
+        );
+        if let Some(clause) = &filter.clause {
+            sql.push_str(&format!(" AND ({})", clause));
+        }
+        let query = sqlx::query(&sql).bind(collection_id);
+        let rows = bind_params(query, &filter.params)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter().map(row_to_candidate).collect()
+    }
+
+    async fn load_embeddings(
+        &self,
+        collection_id: &str,
+        ids: &[String],
+    ) -> Result<Vec<(String, Vec<f32>)>, AppError> {
+        let placeholders = std::iter::repeat_n("?", ids.len())
+            .collect::<Vec<&str>>()
+            .join(", ");
+        let sql = format!(
+            "SELECT id, embedding_blob, embedding_dim FROM vector_items WHERE collection_id = ? AND (expires_at IS NULL OR expires_at > UTC_TIMESTAMP(6)) AND id IN ({})",
+            placeholders
+        );
+        let mut query = sqlx::query(&sql).bind(collection_id);
+        for id in ids {
+            query = query.bind(id);
+        }
+        let rows = query.fetch_all(&self.pool).await?;
+        rows.iter()
+            .map(|row| Ok((row.try_get::<String, _>("id")?, decode_row_embedding(row)?)))
+            .collect()
+    }
+
     async fn get_collection_by_name(
         &self,
         name: &str,
     ) -> Result<Option<VectorCollectionRecord>, AppError> {
         let row = sqlx::query(
             r#"
-            SELECT id, name, metadata, _created_at, _updated_at
+            SELECT id, name, metadata, metadata_schema, quantization, embedding_model,
+                embedding_dimension, _created_at, _updated_at
             FROM vector_collections
             WHERE name = ?
             "#,
@@ -469,24 +1931,395 @@ impl VectorRepository {
         row.map(row_to_collection).transpose()
     }
 
-    async fn collection_id(&self, collection_name: &str) -> Result<String, AppError> {
+    async fn write_target(
+        &self,
+        collection_name: &str,
+    ) -> Result<(VectorCollectionRecord, Option<MetadataSchema>), AppError> {
+        let collection = self.collection(collection_name).await?;
+        let schema = collection
+            .metadata_schema
+            .as_ref()
+            .map(MetadataSchema::parse)
+            .transpose()?;
+        Ok((collection, schema))
+    }
+
+    /// Purges expired items, then rebuilds (`OPTIMIZE TABLE`) and re-analyzes the vector
+    /// tables so space freed by deletes is reclaimed and index statistics are fresh.
+    ///
+    /// Items of every collection share one table, so compaction is storage-wide.
+    #[instrument(skip(self))]
+    pub async fn compact(&self) -> Result<VectorCompactionReport, AppError> {
+        let mut transaction = self.pool.begin().await?;
+        let purged = remove_items(&mut transaction, "expires_at <= UTC_TIMESTAMP(6)", &[]).await?;
+        transaction.commit().await?;
+        let mut steps = Vec::<VectorMaintenanceStep>::new();
+        for statement in ["OPTIMIZE", "ANALYZE"] {
+            let rows = sqlx::query(&format!("{} TABLE {}", statement, COMPACTED_TABLES))
+                .fetch_all(&self.pool)
+                .await?;
+            for row in rows {
+                steps.push(VectorMaintenanceStep {
+                    table: row.try_get::<String, _>("Table")?,
+                    operation: row.try_get::<String, _>("Op")?,
+                    status: row.try_get::<String, _>("Msg_type")?,
+                    message: row.try_get::<String, _>("Msg_text")?,
+                });
+            }
+        }
+        Ok(VectorCompactionReport {
+            purged_expired: purged.items,
+            purged_expired_bytes: purged.bytes,
+            steps,
+        })
+    }
+
+    /// Returns the named collection or a not-found error.
+    pub async fn collection(
+        &self,
+        collection_name: &str,
+    ) -> Result<VectorCollectionRecord, AppError> {
         self.get_collection_by_name(collection_name)
             .await?
-            .map(|collection| collection.id)
             .ok_or_else(|| {
                 AppError::not_found(format!("collection '{}' not found", collection_name))
             })
     }
+
+    async fn collection_id(&self, collection_name: &str) -> Result<String, AppError> {
+        Ok(self.collection(collection_name).await?.id)
+    }
 }
 
-struct VectorCandidate {
+type ScoredCandidate<'a> = (&'a VectorCandidate, f64);
+type ScoredGroup<'a> = (Value, Vec<ScoredCandidate<'a>>);
+
+pub(crate) struct VectorCandidate {
     id: String,
+    parent_id: Option<String>,
     vector: Vec<f32>,
     norm: f64,
     document: Option<String>,
     metadata: Option<Value>,
 }
 
+impl VectorCandidate {
+    /// Rough heap footprint used for the cache budget; metadata is sized by its JSON text.
+    fn approximate_bytes(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + std::mem::size_of_val(self.vector.as_slice())
+            + self.id.len()
+            + self.parent_id.as_ref().map_or(0, String::len)
+            + self.document.as_ref().map_or(0, String::len)
+            + self
+                .metadata
+                .as_ref()
+                .map_or(0, |metadata| metadata.to_string().len())
+    }
+}
+
+/// Ranks candidates per query vector, applying parent dedupe and MMR when requested.
+fn rank_query(
+    candidates: &Arc<Vec<VectorCandidate>>,
+    query_embeddings: &[Vec<f32>],
+    top_k: usize,
+    options: &VectorQueryOptions,
+) -> VectorQueryResult {
+    let ranked = query_embeddings
+        .iter()
+        .map(|query_embedding| {
+            let pool_size = match &options.mmr {
+                Some(mmr) => mmr.fetch_k.max(top_k),
+                None => top_k,
+            };
+            let pool = if options.dedupe_by_parent {
+                let scored = rank_candidates(candidates, query_embedding, usize::MAX, &[]);
+                dedupe_by_parent(scored, pool_size)
+            } else {
+                rank_candidates(candidates, query_embedding, pool_size, &[])
+            };
+            match &options.mmr {
+                Some(mmr) => mmr_rerank(pool, mmr.lambda, top_k),
+                None => pool,
+            }
+        })
+        .collect::<Vec<Vec<ScoredCandidate>>>();
+    build_query_result(ranked)
+}
+
+fn rank_candidates<'a>(
+    candidates: &'a Arc<Vec<VectorCandidate>>,
+    query_embedding: &[f32],
+    top_k: usize,
+    excluded_ids: &[String],
+) -> Vec<(&'a VectorCandidate, f64)> {
+    let eligible = Arc::new(
+        candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, candidate)| candidate.vector.len() == query_embedding.len())
+            .filter(|(_, candidate)| !excluded_ids.contains(&candidate.id))
+            .map(|(index, _)| index)
+            .collect::<Vec<usize>>(),
+    );
+    let query_norm = vector_norm(query_embedding);
+    // The scoring pool outlives this call, so the job owns handles to everything it reads.
+    let similarities = {
+        let (candidates, eligible) = (Arc::clone(candidates), Arc::clone(&eligible));
+        let query = Arc::<[f32]>::from(query_embedding);
+        score_in_parallel(eligible.len(), move |position| {
+            let candidate = &candidates[eligible[position]];
+            cosine_similarity_with_norms(&query, query_norm, &candidate.vector, candidate.norm)
+        })
+    };
+    let mut scored = eligible
+        .iter()
+        .map(|index| &candidates[*index])
+        .zip(similarities)
+        .collect::<Vec<(&VectorCandidate, f64)>>();
+
+    scored.sort_by(|left, right| {
+        right
+            .1
+            .partial_cmp(&left.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    scored.truncate(top_k);
+    scored
+}
+
+fn hybrid_rank<'a>(
+    candidates: &'a [(VectorCandidate, f64)],
+    query_embedding: &[f32],
+    alpha: f64,
+    top_k: usize,
+) -> Vec<ScoredCandidate<'a>> {
+    let max_text_score = candidates
+        .iter()
+        .map(|(_, text_score)| *text_score)
+        .fold(0.0_f64, f64::max);
+    let query_norm = vector_norm(query_embedding);
+    let mut scored = candidates
+        .iter()
+        .filter(|(candidate, _)| candidate.vector.len() == query_embedding.len())
+        .map(|(candidate, text_score)| {
+            let similarity = cosine_similarity_with_norms(
+                query_embedding,
+                query_norm,
+                &candidate.vector,
+                candidate.norm,
+            );
+            let text = if max_text_score > 0.0 {
+                text_score / max_text_score
+            } else {
+                0.0
+            };
+            (candidate, alpha * similarity + (1.0 - alpha) * text)
+        })
+        .collect::<Vec<ScoredCandidate>>();
+
+    scored.sort_by(|left, right| {
+        right
+            .1
+            .partial_cmp(&left.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    scored.truncate(top_k);
+    scored
+}
+
+/// Keeps the first (best-scoring) hit per parent id, up to `top_k` hits; items without a
+/// parent id are treated as their own parent.
+fn dedupe_by_parent(scored: Vec<ScoredCandidate>, top_k: usize) -> Vec<ScoredCandidate> {
+    let mut seen = std::collections::HashSet::<&str>::new();
+    let mut kept = Vec::<ScoredCandidate>::new();
+    for (candidate, similarity) in scored {
+        if kept.len() == top_k {
+            break;
+        }
+        let parent = candidate.parent_id.as_deref().unwrap_or(&candidate.id);
+        if seen.insert(parent) {
+            kept.push((candidate, similarity));
+        }
+    }
+    kept
+}
+
+/// Greedily selects `top_k` items maximising
+/// `lambda * sim(query, item) - (1 - lambda) * max sim(item, selected)`.
+fn mmr_rerank(pool: Vec<ScoredCandidate>, lambda: f64, top_k: usize) -> Vec<ScoredCandidate> {
+    let mut remaining = pool;
+    let mut selected = Vec::<ScoredCandidate>::with_capacity(top_k.min(remaining.len()));
+    while selected.len() < top_k && !remaining.is_empty() {
+        let mut best_index = 0;
+        let mut best_score = f64::NEG_INFINITY;
+        for (index, (candidate, relevance)) in remaining.iter().enumerate() {
+            let redundancy = selected
+                .iter()
+                .map(|(chosen, _)| {
+                    cosine_similarity_with_norms(
+                        &candidate.vector,
+                        candidate.norm,
+                        &chosen.vector,
+                        chosen.norm,
+                    )
+                })
+                .fold(f64::NEG_INFINITY, f64::max);
+            let redundancy = if redundancy.is_finite() {
+                redundancy
+            } else {
+                0.0
+            };
+            let score = lambda * relevance - (1.0 - lambda) * redundancy;
+            if score > best_score {
+                best_index = index;
+                best_score = score;
+            }
+        }
+        selected.push(remaining.remove(best_index));
+    }
+    selected
+}
+
+fn build_query_result(ranked: Vec<Vec<(&VectorCandidate, f64)>>) -> VectorQueryResult {
+    let mut response_ids = Vec::<Vec<String>>::new();
+    let mut response_parent_ids = Vec::<Vec<Option<String>>>::new();
+    let mut response_documents = Vec::<Vec<Option<String>>>::new();
+    let mut response_metadatas = Vec::<Vec<Option<Value>>>::new();
+    let mut response_distances = Vec::<Vec<f64>>::new();
+
+    for scored in ranked {
+        response_ids.push(
+            scored
+                .iter()
+                .map(|(candidate, _)| candidate.id.clone())
+                .collect(),
+        );
+        response_parent_ids.push(
+            scored
+                .iter()
+                .map(|(candidate, _)| candidate.parent_id.clone())
+                .collect(),
+        );
+        response_documents.push(
+            scored
+                .iter()
+                .map(|(candidate, _)| candidate.document.clone())
+                .collect(),
+        );
+        response_metadatas.push(
+            scored
+                .iter()
+                .map(|(candidate, _)| candidate.metadata.clone())
+                .collect(),
+        );
+        response_distances.push(
+            scored
+                .iter()
+                .map(|(_, similarity)| 1.0 - similarity)
+                .collect(),
+        );
+    }
+
+    VectorQueryResult {
+        ids: response_ids,
+        parent_ids: response_parent_ids,
+        documents: response_documents,
+        metadatas: response_metadatas,
+        distances: response_distances,
+        groups: None,
+        explain: None,
+    }
+}
+
+fn build_grouped_query_result(grouped: Vec<Vec<ScoredGroup>>) -> VectorQueryResult {
+    let flattened = grouped
+        .iter()
+        .map(|groups| {
+            groups
+                .iter()
+                .flat_map(|(_, members)| members.iter().copied())
+                .collect::<Vec<(&VectorCandidate, f64)>>()
+        })
+        .collect::<Vec<Vec<(&VectorCandidate, f64)>>>();
+    let mut result = build_query_result(flattened);
+    result.groups = Some(
+        grouped
+            .into_iter()
+            .map(|groups| {
+                groups
+                    .into_iter()
+                    .map(|(value, members)| VectorResultGroup {
+                        value,
+                        ids: members.iter().map(|(c, _)| c.id.clone()).collect(),
+                        documents: members.iter().map(|(c, _)| c.document.clone()).collect(),
+                        metadatas: members.iter().map(|(c, _)| c.metadata.clone()).collect(),
+                        distances: members.iter().map(|(_, s)| 1.0 - s).collect(),
+                    })
+                    .collect()
+            })
+            .collect(),
+    );
+    result
+}
+
+/// Buckets similarity-ordered hits by a metadata value, keeping the first `group_count`
+/// distinct values and at most `group_size` hits each. Hits without the key are skipped.
+fn group_ranked<'a>(
+    scored: Vec<ScoredCandidate<'a>>,
+    key_path: &[String],
+    group_count: usize,
+    group_size: usize,
+) -> Vec<ScoredGroup<'a>> {
+    let mut groups = Vec::<ScoredGroup>::new();
+    for (candidate, similarity) in scored {
+        let Some(value) = candidate
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata_value(metadata, key_path))
+        else {
+            continue;
+        };
+        let open_groups = groups.len();
+        match groups.iter_mut().find(|(existing, _)| existing == value) {
+            Some((_, members)) if members.len() < group_size => {
+                members.push((candidate, similarity));
+            }
+            Some(_) => {}
+            None if open_groups < group_count => {
+                groups.push((value.clone(), vec![(candidate, similarity)]));
+            }
+            None => {}
+        }
+        if groups.len() == group_count
+            && groups
+                .iter()
+                .all(|(_, members)| members.len() == group_size)
+        {
+            break;
+        }
+    }
+    groups
+}
+
+fn grouping_key_path(key: &str) -> Result<Vec<String>, AppError> {
+    let key = key.strip_prefix("metadata.").unwrap_or(key);
+    if key.is_empty() || key.split('.').any(|segment| segment.is_empty()) {
+        return Err(AppError::validation(format!(
+            "invalid group_by key '{}'",
+            key
+        )));
+    }
+    Ok(key.split('.').map(str::to_string).collect())
+}
+
+fn metadata_value<'a>(metadata: &'a Value, key_path: &[String]) -> Option<&'a Value> {
+    let value = key_path
+        .iter()
+        .try_fold(metadata, |current, segment| current.get(segment))?;
+    (!value.is_null()).then_some(value)
+}
+
 fn validate_embedding(embedding: &[f32], max_dimension: usize) -> Result<(), AppError> {
     if embedding.is_empty() {
         return Err(AppError::validation("embedding cannot be empty"));
@@ -501,6 +2334,205 @@ fn validate_embedding(embedding: &[f32], max_dimension: usize) -> Result<(), App
     Ok(())
 }
 
+/// Checks that embeddings match the collection's dimension, or each other while the
+/// collection has none yet, and returns the dimension they share.
+fn check_dimensions<'a>(
+    collection: &VectorCollectionRecord,
+    embeddings: impl IntoIterator<Item = &'a [f32]>,
+) -> Result<Option<u32>, AppError> {
+    let mut expected = collection.embedding_dimension;
+    for embedding in embeddings {
+        let dimension = embedding.len() as u32;
+        match expected {
+            Some(expected) if expected != dimension => {
+                return Err(dimension_mismatch(collection, expected, dimension));
+            }
+            Some(_) => {}
+            None => expected = Some(dimension),
+        }
+    }
+    Ok(expected)
+}
+
+fn dimension_mismatch(collection: &VectorCollectionRecord, expected: u32, found: u32) -> AppError {
+    let built_with = match &collection.embedding_model {
+        Some(model) => format!("{}-dimensional embeddings from '{}'", expected, model),
+        None => format!("{}-dimensional embeddings", expected),
+    };
+    AppError::validation(format!(
+        "embedding dimension {} does not match collection '{}', which holds {}; \
+         use the same embedding provider and model the collection was built with",
+        found, collection.name, built_with
+    ))
+}
+
+/// Stores the dimension of a collection's first write.
+async fn record_dimension(
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    collection: &VectorCollectionRecord,
+    dimension: Option<u32>,
+) -> Result<(), AppError> {
+    let (None, Some(dimension)) = (collection.embedding_dimension, dimension) else {
+        return Ok(());
+    };
+    sqlx::query(
+        r#"
+        UPDATE vector_collections
+        SET embedding_dimension = ?
+        WHERE id = ? AND embedding_dimension IS NULL
+        "#,
+    )
+    .bind(dimension)
+    .bind(&collection.id)
+    .execute(&mut **transaction)
+    .await?;
+    Ok(())
+}
+
+/// Arranges looked-up items in request order; a repeated id is returned only once.
+fn order_by_request(
+    ids: &[String],
+    mut take: impl FnMut(&str) -> Option<VectorItemRecord>,
+) -> VectorBatchGetResult {
+    let mut items = Vec::<VectorItemRecord>::with_capacity(ids.len());
+    let mut missing = Vec::<String>::new();
+    let mut seen = std::collections::HashSet::<&str>::new();
+    for id in ids {
+        if !seen.insert(id.as_str()) {
+            continue;
+        }
+        match take(id) {
+            Some(item) => items.push(item),
+            None => missing.push(id.clone()),
+        }
+    }
+    VectorBatchGetResult { items, missing }
+}
+
+/// Multi-row `INSERT` for `rows` new items, bound in the order used by `add_items`.
+fn insert_items_sql(rows: usize) -> String {
+    let values = std::iter::repeat_n(
+        "(?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, IF(? IS NULL, NULL, UTC_TIMESTAMP(6) + INTERVAL ? SECOND))",
+        rows,
+    )
+    .collect::<Vec<&str>>()
+    .join(", ");
+    format!(
+        "INSERT INTO vector_items (id, collection_id, namespace, parent_id, embedding_blob, embedding_dim, embedding_norm, document, metadata, uri, data, expires_at) VALUES {}",
+        values
+    )
+}
+
+async fn purge_expired(
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    collection_id: &str,
+) -> Result<VectorRemoval, AppError> {
+    remove_items(
+        transaction,
+        "collection_id = ? AND expires_at <= UTC_TIMESTAMP(6)",
+        &[SqlParam::String(collection_id.to_string())],
+    )
+    .await
+}
+
+/// Deletes the items matching `condition` and reports what they held. The rows are sized
+/// under a lock first, so the estimate covers exactly the rows the delete removes.
+async fn remove_items(
+    transaction: &mut sqlx::Transaction<'_, sqlx::MySql>,
+    condition: &str,
+    params: &[SqlParam],
+) -> Result<VectorRemoval, AppError> {
+    let sizing = format!(
+        "SELECT CAST(COALESCE(SUM({}), 0) AS SIGNED) AS removed_bytes \
+         FROM vector_items WHERE {} FOR UPDATE",
+        ITEM_BYTES_SQL, condition
+    );
+    let bytes = bind_params(sqlx::query(&sizing), params)
+        .fetch_one(&mut **transaction)
+        .await?
+        .try_get::<i64, _>("removed_bytes")?;
+    let delete = format!("DELETE FROM vector_items WHERE {}", condition);
+    let items = bind_params(sqlx::query(&delete), params)
+        .execute(&mut **transaction)
+        .await?
+        .rows_affected();
+    Ok(VectorRemoval {
+        items,
+        bytes: bytes.max(0) as u64,
+    })
+}
+
+/// ANDs a namespace equality onto a compiled metadata filter.
+fn scope_to_namespace(mut compiled: CompiledWhere, namespace: Option<&str>) -> CompiledWhere {
+    let Some(namespace) = namespace else {
+        return compiled;
+    };
+    compiled.clause = Some(match compiled.clause {
+        Some(clause) => format!("namespace = ? AND ({})", clause),
+        None => "namespace = ?".to_string(),
+    });
+    compiled
+        .params
+        .insert(0, SqlParam::String(namespace.to_string()));
+    compiled
+}
+
+fn validate_namespace(namespace: Option<&str>) -> Result<(), AppError> {
+    match namespace {
+        Some(namespace) if namespace.len() > MAX_NAMESPACE_LENGTH => Err(AppError::validation(
+            format!("namespace exceeds {} characters", MAX_NAMESPACE_LENGTH),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Checks the URI length and that data is a flat object of scalar columns.
+fn validate_columns(uri: Option<&str>, data: Option<&Value>) -> Result<(), AppError> {
+    if let Some(uri) = uri {
+        if uri.len() > MAX_URI_LENGTH {
+            return Err(AppError::validation(format!(
+                "uri exceeds {} characters",
+                MAX_URI_LENGTH
+            )));
+        }
+    }
+    let Some(data) = data else {
+        return Ok(());
+    };
+    let Some(columns) = data.as_object() else {
+        return Err(AppError::validation("data must be an object of columns"));
+    };
+    for (name, value) in columns {
+        let valid_name = !name.is_empty()
+            && name.len() <= MAX_DATA_COLUMN_LENGTH
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            return Err(AppError::validation(format!(
+                "invalid data column name '{}'",
+                name
+            )));
+        }
+        if value.is_array() || value.is_object() {
+            return Err(AppError::validation(format!(
+                "data column '{}' must be a string, number, boolean or null",
+                name
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn validate_metadata(
+    schema: Option<&MetadataSchema>,
+    metadata: Option<&Value>,
+) -> Result<(), AppError> {
+    match schema {
+        Some(schema) => schema.validate(metadata),
+        None => Ok(()),
+    }
+}
+
 fn row_to_collection(row: sqlx::mysql::MySqlRow) -> Result<VectorCollectionRecord, AppError> {
     Ok(VectorCollectionRecord {
         id: row.try_get::<String, _>("id")?,
@@ -508,6 +2540,13 @@ fn row_to_collection(row: sqlx::mysql::MySqlRow) -> Result<VectorCollectionRecor
         metadata: row
             .try_get::<Option<sqlx::types::Json<Value>>, _>("metadata")?
             .map(|json| json.0),
+        metadata_schema: row
+            .try_get::<Option<sqlx::types::Json<Value>>, _>("metadata_schema")?
+            .map(|json| json.0),
+        quantization: EmbeddingQuantization::parse(&row.try_get::<String, _>("quantization")?)
+            .map_err(AppError::internal)?,
+        embedding_model: row.try_get::<Option<String>, _>("embedding_model")?,
+        embedding_dimension: row.try_get::<Option<u32>, _>("embedding_dimension")?,
         created_at: row
             .try_get::<chrono::NaiveDateTime, _>("_created_at")
             .map(|value| value.to_string())
@@ -522,9 +2561,289 @@ fn row_to_collection(row: sqlx::mysql::MySqlRow) -> Result<VectorCollectionRecor
 fn row_to_item(row: sqlx::mysql::MySqlRow) -> Result<VectorItemRecord, AppError> {
     Ok(VectorItemRecord {
         id: row.try_get::<String, _>("id")?,
+        parent_id: row.try_get::<Option<String>, _>("parent_id")?,
         document: row.try_get::<Option<String>, _>("document")?,
         metadata: row
             .try_get::<Option<sqlx::types::Json<Value>>, _>("metadata")?
             .map(|json| json.0),
     })
 }
+
+fn row_to_columns(row: &sqlx::mysql::MySqlRow) -> Result<VectorItemColumns, AppError> {
+    Ok(VectorItemColumns {
+        uri: row.try_get::<Option<String>, _>("uri")?,
+        data: row
+            .try_get::<Option<sqlx::types::Json<Value>>, _>("data")?
+            .map(|json| json.0),
+        embedding: None,
+    })
+}
+
+fn decode_row_embedding(row: &sqlx::mysql::MySqlRow) -> Result<Vec<f32>, AppError> {
+    let embedding_blob = row.try_get::<Vec<u8>, _>("embedding_blob")?;
+    let embedding_dim = row.try_get::<i32, _>("embedding_dim")? as usize;
+    decode_stored_embedding(&embedding_blob, embedding_dim).map_err(AppError::internal)
+}
+
+fn row_to_candidate(row: sqlx::mysql::MySqlRow) -> Result<VectorCandidate, AppError> {
+    Ok(VectorCandidate {
+        vector: decode_row_embedding(&row)?,
+        id: row.try_get::<String, _>("id")?,
+        parent_id: row.try_get::<Option<String>, _>("parent_id")?,
+        norm: row.try_get::<f64, _>("embedding_norm")?,
+        document: row.try_get::<Option<String>, _>("document")?,
+        metadata: row
+            .try_get::<Option<sqlx::types::Json<Value>>, _>("metadata")?
+            .map(|json| json.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_dimensions, dedupe_by_parent, group_ranked, grouping_key_path, hybrid_rank,
+        insert_items_sql, mmr_rerank, order_by_request, scope_to_namespace, validate_columns,
+        NewVectorItem, VectorCandidate, VectorCollectionRecord, VectorItemRecord,
+        MAX_INSERT_CHUNK_SIZE,
+    };
+    use crate::codec::EmbeddingQuantization;
+    use serde_json::{json, Value};
+
+    fn candidate(id: &str, metadata: Value) -> VectorCandidate {
+        VectorCandidate {
+            id: id.to_string(),
+            parent_id: None,
+            vector: vec![1.0],
+            norm: 1.0,
+            document: None,
+            metadata: Some(metadata),
+        }
+    }
+
+    #[test]
+    fn groups_hits_by_metadata_value_in_similarity_order() {
+        let candidates = [
+            candidate("a1", json!({"source": "a"})),
+            candidate("b1", json!({"source": "b"})),
+            candidate("a2", json!({"source": "a"})),
+            candidate("x", json!({"other": 1})),
+            candidate("a3", json!({"source": "a"})),
+            candidate("c1", json!({"source": "c"})),
+        ];
+        let scored = candidates
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| (candidate, 1.0 - index as f64 / 10.0))
+            .collect::<Vec<_>>();
+
+        let key_path = grouping_key_path("metadata.source").expect("key path");
+        let groups = group_ranked(scored, &key_path, 2, 2);
+        let summary = groups
+            .iter()
+            .map(|(value, members)| {
+                (
+                    value.clone(),
+                    members
+                        .iter()
+                        .map(|(candidate, _)| candidate.id.as_str())
+                        .collect::<Vec<&str>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![(json!("a"), vec!["a1", "a2"]), (json!("b"), vec!["b1"])]
+        );
+        assert!(grouping_key_path("metadata.").is_err());
+    }
+
+    #[test]
+    fn orders_items_by_request_and_reports_missing() {
+        let ids = ["c", "a", "x", "c", "b"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<String>>();
+        let mut stored = ["a", "b", "c"]
+            .iter()
+            .map(|id| VectorItemRecord {
+                id: id.to_string(),
+                parent_id: None,
+                document: None,
+                metadata: None,
+            })
+            .collect::<Vec<VectorItemRecord>>();
+        let result = order_by_request(&ids, |id| {
+            let index = stored.iter().position(|item| item.id == id)?;
+            Some(stored.remove(index))
+        });
+
+        let ordered = result
+            .items
+            .iter()
+            .map(|item| item.id.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(ordered, vec!["c", "a", "b"]);
+        assert_eq!(result.missing, vec!["x".to_string()]);
+    }
+
+    #[test]
+    fn hybrid_rank_blends_vector_and_text_scores() {
+        let mut close = candidate("close", json!({}));
+        close.vector = vec![1.0, 0.0];
+        let mut keyword = candidate("keyword", json!({}));
+        keyword.vector = vec![0.0, 1.0];
+        let candidates = vec![(close, 0.0), (keyword, 4.0)];
+
+        let vector_only = hybrid_rank(&candidates, &[1.0, 0.0], 1.0, 2);
+        assert_eq!(vector_only[0].0.id, "close");
+
+        let text_only = hybrid_rank(&candidates, &[1.0, 0.0], 0.0, 2);
+        assert_eq!(text_only[0].0.id, "keyword");
+        assert_eq!(text_only[0].1, 1.0);
+    }
+
+    #[test]
+    fn mmr_prefers_diverse_items_over_near_duplicates() {
+        let mut first = candidate("first", json!({}));
+        first.vector = vec![1.0, 0.0];
+        let mut duplicate = candidate("duplicate", json!({}));
+        duplicate.vector = vec![1.0, 0.01];
+        duplicate.norm = (1.0_f64 + 0.0001).sqrt();
+        let mut diverse = candidate("diverse", json!({}));
+        diverse.vector = vec![0.0, 1.0];
+        let pool = vec![(&first, 0.99), (&duplicate, 0.98), (&diverse, 0.6)];
+
+        let relevance_only = mmr_rerank(pool.clone(), 1.0, 2);
+        assert_eq!(relevance_only[1].0.id, "duplicate");
+
+        let diversified = mmr_rerank(pool, 0.5, 2);
+        assert_eq!(diversified[0].0.id, "first");
+        assert_eq!(diversified[1].0.id, "diverse");
+    }
+
+    #[test]
+    fn dedupe_keeps_best_chunk_per_parent() {
+        let mut first = candidate("doc1#0", json!({}));
+        first.parent_id = Some("doc1".to_string());
+        let mut second = candidate("doc1#1", json!({}));
+        second.parent_id = Some("doc1".to_string());
+        let standalone = candidate("solo", json!({}));
+        let mut other = candidate("doc2#0", json!({}));
+        other.parent_id = Some("doc2".to_string());
+        let scored = vec![
+            (&first, 0.9),
+            (&second, 0.8),
+            (&standalone, 0.7),
+            (&other, 0.6),
+        ];
+
+        let kept = dedupe_by_parent(scored.clone(), 10)
+            .iter()
+            .map(|(candidate, _)| candidate.id.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(kept, vec!["doc1#0", "solo", "doc2#0"]);
+        assert_eq!(dedupe_by_parent(scored, 2).len(), 2);
+    }
+
+    #[test]
+    fn check_dimensions_locks_to_collection_or_first_embedding() {
+        let mut collection = VectorCollectionRecord {
+            id: "c1".to_string(),
+            name: "docs".to_string(),
+            metadata: None,
+            metadata_schema: None,
+            quantization: EmbeddingQuantization::None,
+            embedding_model: None,
+            embedding_dimension: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        let (a, b, c) = ([1.0_f32, 0.0], [0.0_f32, 1.0], [1.0_f32, 0.0, 0.0]);
+
+        assert_eq!(
+            check_dimensions(&collection, [&a[..], &b[..]]).unwrap(),
+            Some(2)
+        );
+        assert!(check_dimensions(&collection, [&a[..], &c[..]]).is_err());
+
+        collection.embedding_model = Some("openai:text-embedding-3-small".to_string());
+        collection.embedding_dimension = Some(3);
+        assert_eq!(check_dimensions(&collection, [&c[..]]).unwrap(), Some(3));
+        let error = check_dimensions(&collection, [&a[..]]).unwrap_err();
+        assert!(error.to_string().contains("openai:text-embedding-3-small"));
+    }
+
+    #[test]
+    fn data_columns_must_be_flat_scalars_with_identifier_names() {
+        validate_columns(
+            Some("https://example.com/a.pdf"),
+            Some(&json!({"source": "crawl", "chunk_index": 3, "reviewed": true, "note": null})),
+        )
+        .expect("scalar columns are accepted");
+        assert!(validate_columns(None, Some(&json!(["a"]))).is_err());
+        assert!(validate_columns(None, Some(&json!({"tags": ["a"]}))).is_err());
+        assert!(validate_columns(None, Some(&json!({"1st": 1}))).is_err());
+        assert!(validate_columns(None, Some(&json!({"bad-name": 1}))).is_err());
+        assert!(validate_columns(Some(&"u".repeat(2049)), None).is_err());
+    }
+
+    #[test]
+    fn namespace_scope_prefixes_the_filter_clause() {
+        use mesosphere_database::query_builder::{CompiledWhere, SqlParam};
+
+        let filter = CompiledWhere {
+            clause: Some("JSON_EXTRACT(metadata, ?) = ?".to_string()),
+            params: vec![
+                SqlParam::String("$.kind".to_string()),
+                SqlParam::String("faq".to_string()),
+            ],
+        };
+        let scoped = scope_to_namespace(filter, Some("tenant-a"));
+        assert_eq!(
+            scoped.clause.as_deref(),
+            Some("namespace = ? AND (JSON_EXTRACT(metadata, ?) = ?)")
+        );
+        assert!(matches!(&scoped.params[0], SqlParam::String(value) if value == "tenant-a"));
+        assert_eq!(scoped.params.len(), 3);
+
+        let unscoped = scope_to_namespace(CompiledWhere::default(), None);
+        assert!(unscoped.clause.is_none());
+    }
+
+    #[test]
+    fn insert_chunks_stay_under_the_bind_limit() {
+        let sql = insert_items_sql(3);
+        assert_eq!(sql.matches('?').count(), 3 * 13);
+        assert_eq!(sql.matches("), (").count(), 2);
+        assert!(insert_items_sql(MAX_INSERT_CHUNK_SIZE).matches('?').count() <= 65_535);
+    }
+
+    #[test]
+    fn content_hash_ids_are_stable_and_content_sensitive() {
+        let item = NewVectorItem {
+            id: None,
+            namespace: None,
+            parent_id: None,
+            embedding: vec![0.5, -1.0],
+            document: Some("hello".to_string()),
+            metadata: Some(json!({"lang": "en"})),
+            ttl_seconds: None,
+            uri: None,
+            data: None,
+        };
+        let id = item.content_hash_id("docs");
+        assert_eq!(id.len(), 36);
+        assert_eq!(id, item.clone().content_hash_id("docs"));
+        assert_ne!(id, item.content_hash_id("other"));
+        let changed = NewVectorItem {
+            document: Some("hello!".to_string()),
+            ..item.clone()
+        };
+        assert_ne!(id, changed.content_hash_id("docs"));
+        let namespaced = NewVectorItem {
+            namespace: Some("tenant".to_string()),
+            ..item
+        };
+        assert_ne!(id, namespaced.content_hash_id("docs"));
+    }
+}
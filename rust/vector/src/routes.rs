@@ -1,20 +1,43 @@
-use axum::extract::{Path, State};
-use axum::routing::{delete, post};
+use axum::body::Body;
+use axum::extract::{Extension, Path, State};
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::{delete, get, post};
 use axum::{Json, Router};
+use futures_util::{StreamExt, TryStreamExt};
 
 use mesosphere_common::api::envelope::{AffectedRowsResponse, ApiEnvelope};
 
 use crate::api_models::{
-    CollectionResponse, CreateCollectionRequest, VectorAddItemsRequest, VectorDeleteItemsRequest,
-    VectorGetItemsRequest, VectorItemResponse, VectorQueryRequest, VectorQueryResponse,
-    VectorUpdateItemsRequest,
+    CollectionResponse, CreateCollectionRequest, VectorAddItemsRequest, VectorBatchGetRequest,
+    VectorBatchGetResponse, VectorBulkLineError, VectorBulkUpsertResponse, VectorCountRequest,
+    VectorCountResponse, VectorDeleteItemsRequest, VectorExistsRequest, VectorExistsResponse,
+    VectorFacetsRequest, VectorFacetsResponse, VectorGetItemsRequest, VectorHybridQueryRequest,
+    VectorItemResponse, VectorQueryByIdsRequest, VectorQueryRequest, VectorQueryResponse,
+    VectorRecommendRequest, VectorScanRequest, VectorScanResponse, VectorScrollRequest,
+    VectorScrollResponse, VectorTextSearchRequest, VectorTextSearchResponse,
+    VectorUpdateItemsRequest, VectorUpsertItemsRequest, VectorUpsertItemsResponse,
+    VectorUsageResponse,
 };
+use crate::bulk::{parse_bulk_line, NdjsonLines};
+use crate::compaction::COMPACTION;
+use crate::embedder::server_embedder;
 use crate::repository::{
-    NewVectorItem, UpdateVectorItem, VectorItemRecord, VectorQueryResult, VectorRepository,
+    ItemPage, NewVectorItem, QueryGrouping, QueryMmr, UpdateVectorItem, VectorCollectionStats,
+    VectorCompactionReport, VectorIdMode, VectorItemRecord, VectorNamespaceCount,
+    VectorQueryOptions, VectorQueryResult, VectorRemoval, VectorRepository, VectorUpsertResult,
+    DEFAULT_INSERT_CHUNK_SIZE,
 };
-use mesosphere_application::state::AppState;
+use crate::snapshot::{encode_jsonl, parse_jsonl_line, VectorSnapshotRecord};
+use crate::usage::{estimate_item_bytes, UsageLedger, VectorQuotas};
+use mesosphere_application::state::{ApiKeyId, AppState};
 use mesosphere_errors::AppError;
 
+/// Items written per transaction when importing a snapshot.
+const IMPORT_CHUNK_SIZE: usize = 500;
+/// Line failures reported in a bulk upsert response.
+const BULK_MAX_REPORTED_ERRORS: usize = 100;
+
 /// Registers vector collection and item endpoints.
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -22,21 +45,66 @@ pub fn router() -> Router<AppState> {
             "/vector/collections",
             post(create_collection).get(list_collections),
         )
+        .route("/vector/compact", post(compact_storage))
+        .route("/usage", get(get_usage))
         .route("/vector/collections/:name", delete(delete_collection))
+        .route("/vector/collections/:name/export", get(export_collection))
+        .route("/vector/collections/:name/import", post(import_collection))
         .route("/vector/collections/:name/items/add", post(add_items))
         .route("/vector/collections/:name/items/update", post(update_items))
+        .route("/vector/collections/:name/items/upsert", post(upsert_items))
+        .route(
+            "/vector/collections/:name/items/bulk",
+            post(bulk_upsert_items),
+        )
         .route("/vector/collections/:name/items/delete", post(delete_items))
         .route("/vector/collections/:name/items/get", post(get_items))
+        .route(
+            "/vector/collections/:name/items/get-ordered",
+            post(get_items_ordered),
+        )
+        .route(
+            "/vector/collections/:name/items/purge-expired",
+            post(purge_expired_items),
+        )
+        .route("/vector/collections/:name/items/scroll", post(scroll_items))
+        .route("/vector/collections/:name/items/exists", post(item_exists))
+        .route("/vector/collections/:name/items/count", post(count_items))
+        .route("/vector/collections/:name/stats", get(collection_stats))
+        .route("/vector/collections/:name/namespaces", get(list_namespaces))
+        .route(
+            "/vector/collections/:name/namespaces/:namespace",
+            delete(delete_namespace),
+        )
+        .route("/vector/collections/:name/facets", post(facet_items))
         .route("/vector/collections/:name/query", post(query_items))
+        .route("/vector/collections/:name/query/scan", post(scan_items))
+        .route("/vector/collections/:name/search/text", post(text_search))
+        .route(
+            "/vector/collections/:name/query/hybrid",
+            post(hybrid_query_items),
+        )
+        .route(
+            "/vector/collections/:name/query/by-ids",
+            post(query_items_by_ids),
+        )
+        .route("/vector/collections/:name/recommend", post(recommend_items))
 }
 
 async fn create_collection(
     State(state): State<AppState>,
     Json(request): Json<CreateCollectionRequest>,
 ) -> Result<Json<ApiEnvelope<CollectionResponse>>, AppError> {
-    let repository = VectorRepository::new(state.pool.clone(), state.config.vector_max_dim);
+    let repository = repository(&state);
     let collection = repository
-        .create_collection(&request.name, request.metadata)
+        .create_collection(
+            &request.name,
+            request.metadata,
+            request.metadata_schema,
+            request.quantization,
+            request.embedding_model,
+            request.embedding_dimension,
+        )
         .await?;
     Ok(Json(ApiEnvelope::ok(to_collection_response(collection))))
 }
@@ -44,7 +112,7 @@ async fn create_collection(
 async fn list_collections(
     State(state): State<AppState>,
 ) -> Result<Json<ApiEnvelope<Vec<CollectionResponse>>>, AppError> {
-    let repository = VectorRepository::new(state.pool.clone(), state.config.vector_max_dim);
+    let repository = repository(&state);
     let collections = repository
         .list_collections()
         .await?
@@ -57,36 +125,485 @@ async fn list_collections(
 async fn delete_collection(
     State(state): State<AppState>,
     Path(name): Path<String>,
+    key: Option<Extension<ApiKeyId>>,
 ) -> Result<Json<ApiEnvelope<AffectedRowsResponse>>, AppError> {
-    let repository = VectorRepository::new(state.pool.clone(), state.config.vector_max_dim);
-    let affected_rows = repository.delete_collection(&name).await?;
+    let repository = repository(&state);
+    let (affected_rows, removed) = repository.delete_collection(&name).await?;
+    record_delete_usage(&state, key.as_ref().map(|Extension(key)| key), removed).await?;
     Ok(Json(ApiEnvelope::ok(AffectedRowsResponse {
         affected_rows,
     })))
 }
 
+async fn compact_storage(
+    State(state): State<AppState>,
+    key: Option<Extension<ApiKeyId>>,
+) -> Result<Json<ApiEnvelope<VectorCompactionReport>>, AppError> {
+    let report = repository(&state).compact().await?;
+    record_delete_usage(
+        &state,
+        key.as_ref().map(|Extension(key)| key),
+        purged_by(&report),
+    )
+    .await?;
+    Ok(Json(ApiEnvelope::ok(report)))
+}
+
+/// Starts a background compaction once deletes since the last one reach the configured
+/// threshold. Expired items it purges are accounted to the caller that triggered it.
+fn maybe_auto_compact(state: &AppState, key: Option<&ApiKeyId>, deleted_rows: u64) {
+    let threshold = state.config.vector_auto_compact_threshold as u64;
+    if !COMPACTION.record_deletions(deleted_rows, threshold) {
+        return;
+    }
+    let repository = repository(state);
+    let (state, key) = (state.clone(), key.cloned());
+    tokio::spawn(async move {
+        let outcome = match repository.compact().await {
+            Ok(report) => record_delete_usage(&state, key.as_ref(), purged_by(&report))
+                .await
+                .map(|()| report),
+            Err(error) => Err(error),
+        };
+        match outcome {
+            Ok(report) => tracing::info!(
+                purged_expired = report.purged_expired,
+                steps = report.steps.len(),
+                "vector auto-compaction finished"
+            ),
+            Err(error) => tracing::warn!(error = %error, "vector auto-compaction failed"),
+        }
+        COMPACTION.finish();
+    });
+}
+
+/// Expired items a compaction purged.
+fn purged_by(report: &VectorCompactionReport) -> VectorRemoval {
+    VectorRemoval {
+        items: report.purged_expired,
+        bytes: report.purged_expired_bytes,
+    }
+}
+
+async fn get_usage(
+    State(state): State<AppState>,
+    key: Option<Extension<ApiKeyId>>,
+) -> Result<Json<ApiEnvelope<VectorUsageResponse>>, AppError> {
+    let Some(Extension(ApiKeyId(key_id))) = key else {
+        return Err(AppError::unauthorized("usage requires an API key"));
+    };
+    let usage = usage_ledger(&state).usage(&key_id).await?;
+    Ok(Json(ApiEnvelope::ok(VectorUsageResponse {
+        usage,
+        quotas: VectorQuotas::from_config(&state.config),
+    })))
+}
+
+/// Rejects a write that would exceed the caller's storage quotas. Items whose id is already
+/// stored are updates, so only the rest count, matching what `record_write_usage` accounts.
+async fn check_write_quota(
+    state: &AppState,
+    key: Option<&ApiKeyId>,
+    repository: &VectorRepository,
+    items: &[NewVectorItem],
+) -> Result<(), AppError> {
+    let Some(ApiKeyId(key_id)) = key else {
+        return Ok(());
+    };
+    let ledger = usage_ledger(state);
+    if !ledger.limits_storage() {
+        return Ok(());
+    }
+    let ids = items
+        .iter()
+        .filter_map(|item| item.id.clone())
+        .collect::<Vec<String>>();
+    let stored = repository.live_item_ids(&ids).await?;
+    let new_items = items
+        .iter()
+        .filter(|item| item.id.as_ref().is_none_or(|id| !stored.contains(id)))
+        .collect::<Vec<&NewVectorItem>>();
+    ledger.check_write(key_id, &new_items).await
+}
+
+/// Accounts a written batch to the caller: its new items, the bytes written less the bytes
+/// of the items it replaced, and the expired items the write purged.
+async fn record_write_usage(
+    state: &AppState,
+    key: Option<&ApiKeyId>,
+    items: &[NewVectorItem],
+    result: &VectorUpsertResult,
+) -> Result<(), AppError> {
+    let Some(ApiKeyId(key_id)) = key else {
+        return Ok(());
+    };
+    let ledger = usage_ledger(state);
+    let written = items.iter().map(estimate_item_bytes).sum::<u64>();
+    ledger
+        .record_write(
+            key_id,
+            result.inserted_ids.len() as u64,
+            written as i64 - result.replaced_bytes as i64,
+        )
+        .await?;
+    ledger.record_delete(key_id, result.purged).await
+}
+
+async fn record_delete_usage(
+    state: &AppState,
+    key: Option<&ApiKeyId>,
+    removed: VectorRemoval,
+) -> Result<(), AppError> {
+    match key {
+        Some(ApiKeyId(key_id)) => usage_ledger(state).record_delete(key_id, removed).await,
+        None => Ok(()),
+    }
+}
+
+/// Rejects a query up front once the caller's daily quota is spent, without counting it.
+async fn check_query_quota(state: &AppState, key: Option<&ApiKeyId>) -> Result<(), AppError> {
+    match key {
+        Some(ApiKeyId(key_id)) => usage_ledger(state).check_query(key_id).await,
+        None => Ok(()),
+    }
+}
+
+/// Counts a served query against the caller's daily quota.
+async fn record_query_usage(state: &AppState, key: Option<&ApiKeyId>) -> Result<(), AppError> {
+    match key {
+        Some(ApiKeyId(key_id)) => usage_ledger(state).record_query(key_id).await,
+        None => Ok(()),
+    }
+}
+
+/// Streams the collection as JSONL, reading and encoding one page at a time so memory stays
+/// bounded regardless of collection size.
+async fn export_collection(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let repository = repository(&state);
+    let collection_id = repository.collection(&name).await?.id;
+    // `None` ends the stream; `Some(cursor)` reads the page after `cursor`.
+    let pages = futures_util::stream::try_unfold(Some(None::<String>), move |cursor| {
+        let repository = repository.clone();
+        let collection_id = collection_id.clone();
+        async move {
+            let Some(cursor) = cursor else {
+                return Ok(None);
+            };
+            let page = repository
+                .export_page(&collection_id, cursor.as_deref())
+                .await?;
+            let chunk = encode_jsonl(&page.records)?;
+            Ok::<_, AppError>(Some((chunk, page.next_cursor.map(Some))))
+        }
+    })
+    .inspect_err(|error| tracing::warn!(error = %error, "vector export aborted"));
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(pages),
+    ))
+}
+
+/// Imports a JSONL snapshot streamed from the request body, writing it in chunked
+/// transactions. Chunks before an invalid line stay committed.
+async fn import_collection(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    key: Option<Extension<ApiKeyId>>,
+    body: Body,
+) -> Result<Json<ApiEnvelope<VectorUpsertItemsResponse>>, AppError> {
+    let repository = repository(&state);
+    repository.collection(&name).await?;
+
+    let key = key.as_ref().map(|Extension(key)| key);
+    let mut summary = VectorUpsertResult::default();
+    let mut pending = Vec::<NewVectorItem>::with_capacity(IMPORT_CHUNK_SIZE);
+    let mut lines = NdjsonLines::default();
+    let mut stream = body.into_data_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|error| {
+            AppError::validation(format!("failed to read request body: {}", error))
+        })?;
+        for (line_number, line) in lines.push(&chunk)? {
+            pending.push(snapshot_item(parse_jsonl_line(line_number, &line)?));
+            if pending.len() >= IMPORT_CHUNK_SIZE {
+                import_chunk(&state, key, &repository, &name, &mut pending, &mut summary).await?;
+            }
+        }
+    }
+    for (line_number, line) in lines.finish()? {
+        pending.push(snapshot_item(parse_jsonl_line(line_number, &line)?));
+    }
+    import_chunk(&state, key, &repository, &name, &mut pending, &mut summary).await?;
+    Ok(Json(ApiEnvelope::ok(to_upsert_response(summary))))
+}
+
+fn snapshot_item(record: VectorSnapshotRecord) -> NewVectorItem {
+    NewVectorItem {
+        id: Some(record.id),
+        namespace: record.namespace,
+        parent_id: record.parent_id,
+        embedding: record.embedding,
+        document: record.document,
+        metadata: record.metadata,
+        ttl_seconds: None,
+        uri: record.uri,
+        data: record.data,
+    }
+}
+
+/// Upserts the pending snapshot items in one transaction and drains them.
+async fn import_chunk(
+    state: &AppState,
+    key: Option<&ApiKeyId>,
+    repository: &VectorRepository,
+    collection: &str,
+    pending: &mut Vec<NewVectorItem>,
+    summary: &mut VectorUpsertResult,
+) -> Result<(), AppError> {
+    if pending.is_empty() {
+        return Ok(());
+    }
+    check_write_quota(state, key, repository, pending).await?;
+    let result = repository.upsert_items(collection, pending).await?;
+    record_write_usage(state, key, pending, &result).await?;
+    summary.inserted_ids.extend(result.inserted_ids);
+    summary.updated_ids.extend(result.updated_ids);
+    pending.clear();
+    Ok(())
+}
+
 async fn add_items(
     State(state): State<AppState>,
     Path(name): Path<String>,
+    key: Option<Extension<ApiKeyId>>,
     Json(request): Json<VectorAddItemsRequest>,
 ) -> Result<Json<ApiEnvelope<Vec<String>>>, AppError> {
-    let repository = VectorRepository::new(state.pool.clone(), state.config.vector_max_dim);
-    let inserted_ids = repository
-        .add_items(
-            &name,
-            &request
-                .items
-                .into_iter()
-                .map(|item| NewVectorItem {
-                    id: item.id,
-                    embedding: item.embedding,
-                    document: item.document,
-                    metadata: item.metadata,
-                })
-                .collect::<Vec<NewVectorItem>>(),
-        )
-        .await?;
-    Ok(Json(ApiEnvelope::ok(inserted_ids)))
+    let repository = repository(&state);
+    let mut items = request
+        .items
+        .into_iter()
+        .map(|item| NewVectorItem {
+            id: item.id,
+            namespace: request.namespace.clone(),
+            parent_id: item.parent_id,
+            embedding: item.embedding,
+            document: item.document,
+            metadata: item.metadata,
+            ttl_seconds: item.ttl_seconds,
+            uri: item.uri,
+            data: item.data,
+        })
+        .collect::<Vec<NewVectorItem>>();
+    embed_missing(
+        &repository,
+        &name,
+        &mut items,
+        request.embedding_model.as_deref(),
+    )
+    .await?;
+    let key = key.as_ref().map(|Extension(key)| key);
+    if request.id_mode == VectorIdMode::ContentHash {
+        // Content ids make re-adding identical items idempotent, so write them as upserts.
+        // They are assigned before the quota check so items already stored count as updates.
+        let mut ids = Vec::with_capacity(items.len());
+        for item in &mut items {
+            let id = match &item.id {
+                Some(id) => id.clone(),
+                None => item.content_hash_id(&name),
+            };
+            item.id = Some(id.clone());
+            ids.push(id);
+        }
+        check_write_quota(&state, key, &repository, &items).await?;
+        let result = repository.upsert_items(&name, &items).await?;
+        record_write_usage(&state, key, &items, &result).await?;
+        return Ok(Json(ApiEnvelope::ok(ids)));
+    }
+    check_write_quota(&state, key, &repository, &items).await?;
+    let chunk_size = request.chunk_size.unwrap_or(DEFAULT_INSERT_CHUNK_SIZE);
+    let result = repository.add_items(&name, &items, chunk_size).await?;
+    record_write_usage(&state, key, &items, &result).await?;
+    Ok(Json(ApiEnvelope::ok(result.inserted_ids)))
+}
+
+async fn upsert_items(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    key: Option<Extension<ApiKeyId>>,
+    Json(request): Json<VectorUpsertItemsRequest>,
+) -> Result<Json<ApiEnvelope<VectorUpsertItemsResponse>>, AppError> {
+    let repository = repository(&state);
+    let mut items = request
+        .items
+        .into_iter()
+        .map(|item| NewVectorItem {
+            id: item.id,
+            namespace: request.namespace.clone(),
+            parent_id: item.parent_id,
+            embedding: item.embedding,
+            document: item.document,
+            metadata: item.metadata,
+            ttl_seconds: item.ttl_seconds,
+            uri: item.uri,
+            data: item.data,
+        })
+        .collect::<Vec<NewVectorItem>>();
+    embed_missing(
+        &repository,
+        &name,
+        &mut items,
+        request.embedding_model.as_deref(),
+    )
+    .await?;
+    let key = key.as_ref().map(|Extension(key)| key);
+    check_write_quota(&state, key, &repository, &items).await?;
+    let result = repository.upsert_items(&name, &items).await?;
+    record_write_usage(&state, key, &items, &result).await?;
+    Ok(Json(ApiEnvelope::ok(to_upsert_response(result))))
+}
+
+async fn bulk_upsert_items(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    key: Option<Extension<ApiKeyId>>,
+    body: Body,
+) -> Result<Json<ApiEnvelope<VectorBulkUpsertResponse>>, AppError> {
+    let repository = repository(&state);
+    repository.collection(&name).await?;
+
+    let key = key.as_ref().map(|Extension(key)| key);
+    let mut bulk = BulkUpsert::new(&state, key, &repository, &name);
+    let mut lines = NdjsonLines::default();
+    let mut stream = body.into_data_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|error| {
+            AppError::validation(format!("failed to read request body: {}", error))
+        })?;
+        for (line_number, line) in lines.push(&chunk)? {
+            bulk.push(line_number, &line).await;
+        }
+    }
+    for (line_number, line) in lines.finish()? {
+        bulk.push(line_number, &line).await;
+    }
+    bulk.flush().await;
+
+    let mut response = bulk.response;
+    response.lines = lines.line_count() as u64;
+    Ok(Json(ApiEnvelope::ok(response)))
+}
+
+/// Buffers bulk lines and writes them in chunked transactions.
+struct BulkUpsert<'a> {
+    state: &'a AppState,
+    key: Option<&'a ApiKeyId>,
+    repository: &'a VectorRepository,
+    collection: &'a str,
+    pending: Vec<(usize, NewVectorItem)>,
+    response: VectorBulkUpsertResponse,
+}
+
+impl<'a> BulkUpsert<'a> {
+    fn new(
+        state: &'a AppState,
+        key: Option<&'a ApiKeyId>,
+        repository: &'a VectorRepository,
+        collection: &'a str,
+    ) -> Self {
+        Self {
+            state,
+            key,
+            repository,
+            collection,
+            pending: Vec::with_capacity(IMPORT_CHUNK_SIZE),
+            response: VectorBulkUpsertResponse {
+                lines: 0,
+                inserted: 0,
+                updated: 0,
+                failed: 0,
+                chunks: 0,
+                errors: Vec::new(),
+            },
+        }
+    }
+
+    async fn push(&mut self, line_number: usize, line: &str) {
+        let record = match parse_bulk_line(line) {
+            Ok(record) => record,
+            Err(error) => return self.fail(line_number, error),
+        };
+        if record.embedding.is_none() && record.document.is_none() {
+            return self.fail(line_number, "embedding or document is required".to_string());
+        }
+        self.pending.push((
+            line_number,
+            NewVectorItem {
+                id: record.id,
+                namespace: record.namespace,
+                parent_id: record.parent_id,
+                embedding: record.embedding.unwrap_or_default(),
+                document: record.document,
+                metadata: record.metadata,
+                ttl_seconds: record.ttl_seconds,
+                uri: record.uri,
+                data: record.data,
+            },
+        ));
+        if self.pending.len() >= IMPORT_CHUNK_SIZE {
+            self.flush().await;
+        }
+    }
+
+    async fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let (line_numbers, mut items): (Vec<usize>, Vec<NewVectorItem>) =
+            std::mem::take(&mut self.pending).into_iter().unzip();
+        let outcome = self.write_chunk(&mut items).await;
+        match outcome {
+            Ok(result) => {
+                self.response.inserted += result.inserted_ids.len() as u64;
+                self.response.updated += result.updated_ids.len() as u64;
+                self.response.chunks += 1;
+            }
+            Err(error) => {
+                let message = format!(
+                    "chunk of lines {}-{} was rolled back: {}",
+                    line_numbers[0],
+                    line_numbers[line_numbers.len() - 1],
+                    error
+                );
+                for line_number in line_numbers {
+                    self.fail(line_number, message.clone());
+                }
+            }
+        }
+    }
+
+    async fn write_chunk(
+        &self,
+        items: &mut [NewVectorItem],
+    ) -> Result<VectorUpsertResult, AppError> {
+        embed_missing(self.repository, self.collection, items, None).await?;
+        check_write_quota(self.state, self.key, self.repository, items).await?;
+        let result = self.repository.upsert_items(self.collection, items).await?;
+        record_write_usage(self.state, self.key, items, &result).await?;
+        Ok(result)
+    }
+
+    fn fail(&mut self, line_number: usize, error: String) {
+        self.response.failed += 1;
+        if self.response.errors.len() < BULK_MAX_REPORTED_ERRORS {
+            self.response.errors.push(VectorBulkLineError {
+                line: line_number as u64,
+                error,
+            });
+        }
+    }
 }
 
 async fn update_items(
@@ -94,7 +611,7 @@ async fn update_items(
     Path(name): Path<String>,
     Json(request): Json<VectorUpdateItemsRequest>,
 ) -> Result<Json<ApiEnvelope<AffectedRowsResponse>>, AppError> {
-    let repository = VectorRepository::new(state.pool.clone(), state.config.vector_max_dim);
+    let repository = repository(&state);
     let affected_rows = repository
         .update_items(
             &name,
@@ -103,9 +620,13 @@ async fn update_items(
                 .into_iter()
                 .map(|item| UpdateVectorItem {
                     id: item.id,
+                    parent_id: item.parent_id,
                     embedding: item.embedding,
                     document: item.document,
                     metadata: item.metadata,
+                    ttl_seconds: item.ttl_seconds,
+                    uri: item.uri,
+                    data: item.data,
                 })
                 .collect::<Vec<UpdateVectorItem>>(),
         )
@@ -118,12 +639,62 @@ async fn update_items(
 async fn delete_items(
     State(state): State<AppState>,
     Path(name): Path<String>,
+    key: Option<Extension<ApiKeyId>>,
     Json(request): Json<VectorDeleteItemsRequest>,
 ) -> Result<Json<ApiEnvelope<AffectedRowsResponse>>, AppError> {
-    let repository = VectorRepository::new(state.pool.clone(), state.config.vector_max_dim);
-    let affected_rows = repository.delete_items(&name, &request.ids).await?;
+    let repository = repository(&state);
+    let removed = repository
+        .delete_items(
+            &name,
+            &request.ids,
+            request.filter.as_ref(),
+            request.namespace.as_deref(),
+        )
+        .await?;
+    let key = key.as_ref().map(|Extension(key)| key);
+    record_delete_usage(&state, key, removed).await?;
+    maybe_auto_compact(&state, key, removed.items);
     Ok(Json(ApiEnvelope::ok(AffectedRowsResponse {
-        affected_rows,
+        affected_rows: removed.items,
+    })))
+}
+
+async fn list_namespaces(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiEnvelope<Vec<VectorNamespaceCount>>>, AppError> {
+    let repository = repository(&state);
+    let namespaces = repository.list_namespaces(&name).await?;
+    Ok(Json(ApiEnvelope::ok(namespaces)))
+}
+
+async fn delete_namespace(
+    State(state): State<AppState>,
+    Path((name, namespace)): Path<(String, String)>,
+    key: Option<Extension<ApiKeyId>>,
+) -> Result<Json<ApiEnvelope<AffectedRowsResponse>>, AppError> {
+    let repository = repository(&state);
+    let removed = repository.delete_namespace(&name, &namespace).await?;
+    let key = key.as_ref().map(|Extension(key)| key);
+    record_delete_usage(&state, key, removed).await?;
+    maybe_auto_compact(&state, key, removed.items);
+    Ok(Json(ApiEnvelope::ok(AffectedRowsResponse {
+        affected_rows: removed.items,
+    })))
+}
+
+async fn purge_expired_items(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    key: Option<Extension<ApiKeyId>>,
+) -> Result<Json<ApiEnvelope<AffectedRowsResponse>>, AppError> {
+    let repository = repository(&state);
+    let removed = repository.purge_expired_items(&name).await?;
+    let key = key.as_ref().map(|Extension(key)| key);
+    record_delete_usage(&state, key, removed).await?;
+    maybe_auto_compact(&state, key, removed.items);
+    Ok(Json(ApiEnvelope::ok(AffectedRowsResponse {
+        affected_rows: removed.items,
     })))
 }
 
@@ -132,34 +703,459 @@ async fn get_items(
     Path(name): Path<String>,
     Json(request): Json<VectorGetItemsRequest>,
 ) -> Result<Json<ApiEnvelope<Vec<VectorItemResponse>>>, AppError> {
-    let repository = VectorRepository::new(state.pool.clone(), state.config.vector_max_dim);
-    let rows = repository
-        .get_items(&name, &request.ids)
+    let include = ItemInclude::parse(&request.include)?;
+    let repository = repository(&state);
+    let mut rows = repository
+        .get_items(
+            &name,
+            &request.ids,
+            request.filter.as_ref(),
+            request.namespace.as_deref(),
+            item_page(request.limit, request.offset),
+        )
         .await?
         .into_iter()
         .map(to_item_response)
         .collect::<Vec<VectorItemResponse>>();
+    attach_item_columns(&repository, &name, include, &mut rows).await?;
     Ok(Json(ApiEnvelope::ok(rows)))
 }
 
+/// Builds the SQL window for a get request; `None` keeps the unpaged read.
+fn item_page(limit: Option<u64>, offset: Option<u64>) -> Option<ItemPage> {
+    if limit.is_none() && offset.is_none() {
+        return None;
+    }
+    Some(ItemPage {
+        limit,
+        offset: offset.unwrap_or(0),
+    })
+}
+
+async fn get_items_ordered(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<VectorBatchGetRequest>,
+) -> Result<Json<ApiEnvelope<VectorBatchGetResponse>>, AppError> {
+    let include = ItemInclude::parse(&request.include)?;
+    let repository = repository(&state);
+    let result = repository.get_items_ordered(&name, &request.ids).await?;
+    let mut items = result
+        .items
+        .into_iter()
+        .map(to_item_response)
+        .collect::<Vec<VectorItemResponse>>();
+    attach_item_columns(&repository, &name, include, &mut items).await?;
+    Ok(Json(ApiEnvelope::ok(VectorBatchGetResponse {
+        items,
+        missing: result.missing,
+    })))
+}
+
+async fn scroll_items(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<VectorScrollRequest>,
+) -> Result<Json<ApiEnvelope<VectorScrollResponse>>, AppError> {
+    let repository = repository(&state);
+    let page = repository
+        .scroll(
+            &name,
+            request.cursor.as_deref(),
+            request.batch_size.unwrap_or(100),
+            request.filter.as_ref(),
+        )
+        .await?;
+    Ok(Json(ApiEnvelope::ok(VectorScrollResponse {
+        items: page.items.into_iter().map(to_item_response).collect(),
+        next_cursor: page.next_cursor,
+    })))
+}
+
+async fn facet_items(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<VectorFacetsRequest>,
+) -> Result<Json<ApiEnvelope<VectorFacetsResponse>>, AppError> {
+    let repository = repository(&state);
+    let facets = repository
+        .facets(
+            &name,
+            &request.keys,
+            request.filter.as_ref(),
+            request.limit.unwrap_or(20),
+        )
+        .await?;
+    Ok(Json(ApiEnvelope::ok(VectorFacetsResponse { facets })))
+}
+
+async fn item_exists(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<VectorExistsRequest>,
+) -> Result<Json<ApiEnvelope<VectorExistsResponse>>, AppError> {
+    let repository = repository(&state);
+    let exists = repository.item_exists(&name, &request.id).await?;
+    Ok(Json(ApiEnvelope::ok(VectorExistsResponse { exists })))
+}
+
+async fn count_items(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(request): Json<VectorCountRequest>,
+) -> Result<Json<ApiEnvelope<VectorCountResponse>>, AppError> {
+    let repository = repository(&state);
+    let count = repository
+        .count_items(&name, request.filter.as_ref())
+        .await?;
+    Ok(Json(ApiEnvelope::ok(VectorCountResponse { count })))
+}
+
+async fn collection_stats(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiEnvelope<VectorCollectionStats>>, AppError> {
+    let stats = repository(&state).collection_stats(&name).await?;
+    Ok(Json(ApiEnvelope::ok(stats)))
+}
+
 async fn query_items(
     State(state): State<AppState>,
     Path(name): Path<String>,
+    key: Option<Extension<ApiKeyId>>,
     Json(request): Json<VectorQueryRequest>,
 ) -> Result<Json<ApiEnvelope<VectorQueryResponse>>, AppError> {
-    let repository = VectorRepository::new(state.pool.clone(), state.config.vector_max_dim);
+    let include = ItemInclude::parse(&request.include)?;
+    let key = key.as_ref().map(|Extension(key)| key);
+    check_query_quota(&state, key).await?;
+    let repository = repository(&state);
+    let n_results = request.n_results.unwrap_or(10);
+    let query_embeddings = match (
+        request.query_embeddings.is_empty(),
+        request.query_texts.is_empty(),
+    ) {
+        (false, true) => request.query_embeddings,
+        (true, false) => {
+            let model =
+                embedding_model_for(&repository, &name, request.embedding_model.as_deref()).await?;
+            server_embedder()?
+                .embed(&request.query_texts, model.as_deref())
+                .await?
+        }
+        _ => {
+            return Err(AppError::validation(
+                "provide exactly one of query_embeddings or query_texts",
+            ))
+        }
+    };
+    let options = VectorQueryOptions {
+        filter: request.filter,
+        grouping: request.group_by.map(|key| QueryGrouping {
+            key,
+            group_size: request.group_size.unwrap_or(1) as usize,
+        }),
+        mmr: request.mmr.map(|mmr| QueryMmr {
+            lambda: mmr.lambda.unwrap_or(0.5),
+            fetch_k: mmr
+                .fetch_k
+                .map(|value| value as usize)
+                .unwrap_or(n_results as usize * 4),
+        }),
+        dedupe_by_parent: request.dedupe_by_parent,
+        namespace: request.namespace,
+        explain: request.explain,
+    };
+    let result = repository
+        .query(&name, &query_embeddings, n_results, &options)
+        .await?;
+    let mut response = to_query_response(result);
+    if include.any() {
+        let ids = response.ids.concat();
+        let columns = repository
+            .item_columns(&name, &ids, include.embeddings)
+            .await?;
+        let column = |id: &String| columns.get(id).cloned().unwrap_or_default();
+        if include.uris {
+            response.uris = Some(
+                response
+                    .ids
+                    .iter()
+                    .map(|ids| ids.iter().map(|id| column(id).uri).collect())
+                    .collect(),
+            );
+        }
+        if include.data {
+            response.data = Some(
+                response
+                    .ids
+                    .iter()
+                    .map(|ids| ids.iter().map(|id| column(id).data).collect())
+                    .collect(),
+            );
+        }
+        if include.embeddings {
+            response.embeddings = Some(
+                response
+                    .ids
+                    .iter()
+                    .map(|ids| ids.iter().map(|id| column(id).embedding).collect())
+                    .collect(),
+            );
+        }
+    }
+    record_query_usage(&state, key).await?;
+    Ok(Json(ApiEnvelope::ok(response)))
+}
+
+async fn scan_items(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    key: Option<Extension<ApiKeyId>>,
+    Json(request): Json<VectorScanRequest>,
+) -> Result<Json<ApiEnvelope<VectorScanResponse>>, AppError> {
+    let key = key.as_ref().map(|Extension(key)| key);
+    check_query_quota(&state, key).await?;
+    let repository = repository(&state);
+    let page = repository
+        .scan_scores(
+            &name,
+            &request.query_embedding,
+            request.cursor.as_deref(),
+            request.batch_size.unwrap_or(100),
+            request.filter.as_ref(),
+            request.min_similarity,
+        )
+        .await?;
+    record_query_usage(&state, key).await?;
+    Ok(Json(ApiEnvelope::ok(VectorScanResponse {
+        items: page.items,
+        next_cursor: page.next_cursor,
+    })))
+}
+
+async fn text_search(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    key: Option<Extension<ApiKeyId>>,
+    Json(request): Json<VectorTextSearchRequest>,
+) -> Result<Json<ApiEnvelope<VectorTextSearchResponse>>, AppError> {
+    let key = key.as_ref().map(|Extension(key)| key);
+    check_query_quota(&state, key).await?;
+    let repository = repository(&state);
+    let result = repository
+        .text_search(
+            &name,
+            &request.query,
+            request.n_results.unwrap_or(10),
+            request.filter.as_ref(),
+        )
+        .await?;
+    record_query_usage(&state, key).await?;
+    Ok(Json(ApiEnvelope::ok(VectorTextSearchResponse {
+        items: result.items.into_iter().map(to_item_response).collect(),
+        scores: result.scores,
+    })))
+}
+
+async fn hybrid_query_items(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    key: Option<Extension<ApiKeyId>>,
+    Json(request): Json<VectorHybridQueryRequest>,
+) -> Result<Json<ApiEnvelope<VectorQueryResponse>>, AppError> {
+    let key = key.as_ref().map(|Extension(key)| key);
+    check_query_quota(&state, key).await?;
+    let repository = repository(&state);
+    let result = repository
+        .hybrid_query(
+            &name,
+            &request.query_embedding,
+            &request.query_text,
+            request.alpha.unwrap_or(0.5),
+            request.n_results.unwrap_or(10),
+            request.filter.as_ref(),
+        )
+        .await?;
+    record_query_usage(&state, key).await?;
+    Ok(Json(ApiEnvelope::ok(to_query_response(result))))
+}
+
+async fn query_items_by_ids(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    key: Option<Extension<ApiKeyId>>,
+    Json(request): Json<VectorQueryByIdsRequest>,
+) -> Result<Json<ApiEnvelope<VectorQueryResponse>>, AppError> {
+    let key = key.as_ref().map(|Extension(key)| key);
+    check_query_quota(&state, key).await?;
+    let repository = repository(&state);
     let n_results = request.n_results.unwrap_or(10);
     let result = repository
-        .query(&name, &request.query_embeddings, n_results)
+        .query_by_ids(&name, &request.ids, n_results)
         .await?;
+    record_query_usage(&state, key).await?;
     Ok(Json(ApiEnvelope::ok(to_query_response(result))))
 }
 
+async fn recommend_items(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    key: Option<Extension<ApiKeyId>>,
+    Json(request): Json<VectorRecommendRequest>,
+) -> Result<Json<ApiEnvelope<VectorQueryResponse>>, AppError> {
+    let key = key.as_ref().map(|Extension(key)| key);
+    check_query_quota(&state, key).await?;
+    let repository = repository(&state);
+    let n_results = request.n_results.unwrap_or(10);
+    let result = repository
+        .recommend(
+            &name,
+            &request.positive_ids,
+            &request.negative_ids,
+            n_results,
+        )
+        .await?;
+    record_query_usage(&state, key).await?;
+    Ok(Json(ApiEnvelope::ok(to_query_response(result))))
+}
+
+/// Picks the model the server embeds a collection's texts with: the requested model, else the
+/// model the collection was built with when this deployment serves it, else the default.
+async fn embedding_model_for(
+    repository: &VectorRepository,
+    collection: &str,
+    requested: Option<&str>,
+) -> Result<Option<String>, AppError> {
+    if let Some(model) = requested {
+        return Ok(Some(model.to_string()));
+    }
+    let embedder = server_embedder()?;
+    let collection = repository.collection(collection).await?;
+    Ok(collection
+        .embedding_model
+        .filter(|model| embedder.serves(model)))
+}
+
+/// Embeds the documents of items sent without an embedding using the server-side embedder.
+async fn embed_missing(
+    repository: &VectorRepository,
+    collection: &str,
+    items: &mut [NewVectorItem],
+    requested_model: Option<&str>,
+) -> Result<(), AppError> {
+    let missing = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.embedding.is_empty())
+        .map(|(index, _)| index)
+        .collect::<Vec<usize>>();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let texts = missing
+        .iter()
+        .map(|&index| {
+            items[index].document.clone().ok_or_else(|| {
+                AppError::validation(format!(
+                    "item {} has neither an embedding nor a document",
+                    index
+                ))
+            })
+        })
+        .collect::<Result<Vec<String>, AppError>>()?;
+    let model = embedding_model_for(repository, collection, requested_model).await?;
+    let embeddings = server_embedder()?.embed(&texts, model.as_deref()).await?;
+    for (index, embedding) in missing.into_iter().zip(embeddings) {
+        items[index].embedding = embedding;
+    }
+    Ok(())
+}
+
+/// Optional item columns requested through `include`.
+#[derive(Debug, Clone, Copy, Default)]
+struct ItemInclude {
+    uris: bool,
+    data: bool,
+    embeddings: bool,
+}
+
+impl ItemInclude {
+    fn parse(include: &[String]) -> Result<Self, AppError> {
+        let mut parsed = Self::default();
+        for field in include {
+            match field.as_str() {
+                "uris" => parsed.uris = true,
+                "data" => parsed.data = true,
+                "embeddings" => parsed.embeddings = true,
+                // Always returned; accepted so Chroma-style include lists work unchanged.
+                "documents" | "metadatas" | "distances" => {}
+                other => {
+                    return Err(AppError::validation(format!(
+                        "unsupported include field '{}'",
+                        other
+                    )))
+                }
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn any(self) -> bool {
+        self.uris || self.data || self.embeddings
+    }
+}
+
+async fn attach_item_columns(
+    repository: &VectorRepository,
+    name: &str,
+    include: ItemInclude,
+    items: &mut [VectorItemResponse],
+) -> Result<(), AppError> {
+    if !include.any() {
+        return Ok(());
+    }
+    let ids = items
+        .iter()
+        .map(|item| item.id.clone())
+        .collect::<Vec<String>>();
+    let columns = repository
+        .item_columns(name, &ids, include.embeddings)
+        .await?;
+    for item in items {
+        let Some(column) = columns.get(&item.id) else {
+            continue;
+        };
+        if include.uris {
+            item.uri = column.uri.clone();
+        }
+        if include.data {
+            item.data = column.data.clone();
+        }
+        if include.embeddings {
+            item.embedding = column.embedding.clone();
+        }
+    }
+    Ok(())
+}
+
+fn usage_ledger(state: &AppState) -> UsageLedger {
+    UsageLedger::new(state.pool.clone(), VectorQuotas::from_config(&state.config))
+}
+
+fn repository(state: &AppState) -> VectorRepository {
+    VectorRepository::new(state.pool.clone(), state.config.vector_max_dim)
+        .with_cache_budget(state.config.vector_cache_max_bytes)
+}
+
 fn to_collection_response(record: crate::repository::VectorCollectionRecord) -> CollectionResponse {
     CollectionResponse {
         id: record.id,
         name: record.name,
         metadata: record.metadata,
+        metadata_schema: record.metadata_schema,
+        quantization: record.quantization,
+        embedding_model: record.embedding_model,
+        embedding_dimension: record.embedding_dimension,
         created_at: record.created_at,
         updated_at: record.updated_at,
     }
@@ -168,16 +1164,35 @@ fn to_collection_response(record: crate::repository::VectorCollectionRecord) ->
 fn to_item_response(record: VectorItemRecord) -> VectorItemResponse {
     VectorItemResponse {
         id: record.id,
+        parent_id: record.parent_id,
         document: record.document,
         metadata: record.metadata,
+        uri: None,
+        data: None,
+        embedding: None,
+    }
+}
+
+fn to_upsert_response(result: VectorUpsertResult) -> VectorUpsertItemsResponse {
+    VectorUpsertItemsResponse {
+        inserted: result.inserted_ids.len() as u64,
+        updated: result.updated_ids.len() as u64,
+        inserted_ids: result.inserted_ids,
+        updated_ids: result.updated_ids,
     }
 }
 
 fn to_query_response(result: VectorQueryResult) -> VectorQueryResponse {
     VectorQueryResponse {
         ids: result.ids,
+        parent_ids: result.parent_ids,
         documents: result.documents,
         metadatas: result.metadatas,
         distances: result.distances,
+        groups: result.groups,
+        uris: None,
+        data: None,
+        embeddings: None,
+        explain: result.explain,
     }
 }
@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use mesosphere_errors::AppError;
+
+/// Value type accepted by a declared metadata field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetadataFieldType {
+    /// JSON string.
+    String,
+    /// Any JSON number.
+    Number,
+    /// JSON number without a fractional part.
+    Integer,
+    /// JSON boolean.
+    Boolean,
+    /// JSON array.
+    Array,
+    /// JSON object.
+    Object,
+}
+
+impl MetadataFieldType {
+    /// Returns the schema spelling of the type.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Integer => "integer",
+            Self::Boolean => "boolean",
+            Self::Array => "array",
+            Self::Object => "object",
+        }
+    }
+}
+
+/// Declared metadata field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MetadataFieldSchema {
+    /// Expected value type.
+    #[serde(rename = "type")]
+    pub field_type: MetadataFieldType,
+    /// Whether every item must carry the field.
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Strict metadata schema declared on a collection; undeclared keys are rejected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MetadataSchema {
+    /// Declared fields by top-level metadata key.
+    pub fields: BTreeMap<String, MetadataFieldSchema>,
+}
+
+impl MetadataSchema {
+    /// Parses a schema declaration such as `{"source": {"type": "string", "required": true}}`.
+    pub fn parse(value: &Value) -> Result<Self, AppError> {
+        let schema = serde_json::from_value::<MetadataSchema>(value.clone())
+            .map_err(|error| AppError::validation(format!("invalid metadata schema: {}", error)))?;
+        if schema.fields.is_empty() {
+            return Err(AppError::validation(
+                "metadata schema must declare at least one field",
+            ));
+        }
+        Ok(schema)
+    }
+
+    /// Validates item metadata against the declared fields.
+    pub fn validate(&self, metadata: Option<&Value>) -> Result<(), AppError> {
+        let empty = serde_json::Map::new();
+        let object = match metadata {
+            None | Some(Value::Null) => &empty,
+            Some(Value::Object(object)) => object,
+            Some(_) => {
+                return Err(AppError::validation(
+                    "metadata must be an object for collections with a metadata schema",
+                ));
+            }
+        };
+
+        if let Some(unknown) = object.keys().find(|key| !self.fields.contains_key(*key)) {
+            return Err(AppError::validation(format!(
+                "metadata key '{}' is not declared in the collection schema",
+                unknown
+            )));
+        }
+        for (key, field) in &self.fields {
+            match object.get(key) {
+                None | Some(Value::Null) if field.required => {
+                    return Err(AppError::validation(format!(
+                        "metadata key '{}' is required",
+                        key
+                    )));
+                }
+                None | Some(Value::Null) => {}
+                Some(value) if !matches_type(field.field_type, value) => {
+                    return Err(AppError::validation(format!(
+                        "metadata key '{}' must be of type {}",
+                        key,
+                        field.field_type.as_str()
+                    )));
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn matches_type(field_type: MetadataFieldType, value: &Value) -> bool {
+    match field_type {
+        MetadataFieldType::String => value.is_string(),
+        MetadataFieldType::Number => value.is_number(),
+        MetadataFieldType::Integer => value.is_i64() || value.is_u64(),
+        MetadataFieldType::Boolean => value.is_boolean(),
+        MetadataFieldType::Array => value.is_array(),
+        MetadataFieldType::Object => value.is_object(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MetadataSchema;
+    use serde_json::json;
+
+    #[test]
+    fn validates_types_required_and_unknown_keys() {
+        let schema = MetadataSchema::parse(&json!({
+            "source": {"type": "string", "required": true},
+            "page": {"type": "integer"}
+        }))
+        .expect("schema");
+
+        assert!(schema
+            .validate(Some(&json!({"source": "a", "page": 3})))
+            .is_ok());
+        assert!(schema.validate(Some(&json!({"source": "a"}))).is_ok());
+        assert!(schema.validate(Some(&json!({"page": 3}))).is_err());
+        assert!(schema.validate(None).is_err());
+        assert!(schema
+            .validate(Some(&json!({"source": "a", "page": 1.5})))
+            .is_err());
+        assert!(schema
+            .validate(Some(&json!({"source": "a", "extra": 1})))
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_schema() {
+        assert!(MetadataSchema::parse(&json!({})).is_err());
+        assert!(MetadataSchema::parse(&json!({"a": {"type": "date"}})).is_err());
+        assert!(MetadataSchema::parse(&json!({"a": {"type": "string", "unique": true}})).is_err());
+    }
+}
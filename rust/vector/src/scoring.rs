@@ -1,22 +1,206 @@
-﻿/// Computes cosine similarity between query and item vectors using precomputed item norm.
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+
+use crate::codec::vector_norm;
+
+/// Number of independent accumulators used to let the compiler vectorise the dot product.
+const LANES: usize = 4;
+/// Candidate count from which scoring is split across threads.
+pub(crate) const PARALLEL_SCORING_THRESHOLD: usize = 4096;
+
+/// Computes cosine similarity between query and item vectors using precomputed item norm.
 pub fn cosine_similarity(query: &[f32], item: &[f32], item_norm: f64) -> f64 {
-    if query.len() != item.len() || query.is_empty() || item_norm == 0.0 {
+    cosine_similarity_with_norms(query, vector_norm(query), item, item_norm)
+}
+
+/// Computes cosine similarity when both norms are already known, so only the dot
+/// product is evaluated per item. Callers scoring many items compute the query norm once.
+pub fn cosine_similarity_with_norms(
+    query: &[f32],
+    query_norm: f64,
+    item: &[f32],
+    item_norm: f64,
+) -> f64 {
+    if query.len() != item.len() || query.is_empty() || item_norm == 0.0 || query_norm == 0.0 {
         return 0.0;
     }
+    dot_product(query, item) / (query_norm * item_norm)
+}
 
-    let mut dot = 0.0_f64;
-    let mut query_squared = 0.0_f64;
-    for (query_value, item_value) in query.iter().zip(item.iter()) {
-        let q = *query_value as f64;
-        let i = *item_value as f64;
-        dot += q * i;
-        query_squared += q * q;
+fn dot_product(left: &[f32], right: &[f32]) -> f64 {
+    let mut dot = [0.0_f64; LANES];
+    let left_chunks = left.chunks_exact(LANES);
+    let right_chunks = right.chunks_exact(LANES);
+    let left_tail = left_chunks.remainder();
+    let right_tail = right_chunks.remainder();
+    for (left_chunk, right_chunk) in left_chunks.zip(right_chunks) {
+        for lane in 0..LANES {
+            dot[lane] += left_chunk[lane] as f64 * right_chunk[lane] as f64;
+        }
+    }
+    for (lane, (left_value, right_value)) in left_tail.iter().zip(right_tail).enumerate() {
+        dot[lane] += *left_value as f64 * *right_value as f64;
     }
+    dot.iter().sum()
+}
 
-    let query_norm = query_squared.sqrt();
-    if query_norm == 0.0 {
-        return 0.0;
+/// Job run by a scoring pool thread.
+type ScoringJob = Box<dyn FnOnce() + Send>;
+
+/// Helper threads shared by every query, so concurrent large queries queue for the same
+/// `available_parallelism - 1` threads instead of each spawning their own.
+struct ScoringPool {
+    jobs: mpsc::Sender<ScoringJob>,
+    helpers: usize,
+}
+
+static SCORING_POOL: OnceLock<ScoringPool> = OnceLock::new();
+
+fn scoring_pool() -> &'static ScoringPool {
+    SCORING_POOL.get_or_init(|| {
+        let (jobs, receiver) = mpsc::channel::<ScoringJob>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let wanted = std::thread::available_parallelism()
+            .map(|count| count.get())
+            .unwrap_or(1)
+            .saturating_sub(1);
+        let helpers = (0..wanted)
+            .filter(|index| {
+                let receiver = Arc::clone(&receiver);
+                std::thread::Builder::new()
+                    .name(format!("vector-scoring-{}", index))
+                    .spawn(move || loop {
+                        let job = match receiver.lock() {
+                            Ok(receiver) => receiver.recv(),
+                            Err(_) => return,
+                        };
+                        let Ok(job) = job else {
+                            return;
+                        };
+                        // A panicking job drops its result sender, which the caller reports.
+                        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(job));
+                    })
+                    .is_ok()
+            })
+            .count();
+        ScoringPool { jobs, helpers }
+    })
+}
+
+/// Scores item indices `0..len`, splitting large inputs across the shared scoring pool.
+///
+/// Output order always matches index order, so results do not depend on scheduling. The caller
+/// scores the first chunk itself and then waits for the rest; on a multi-threaded runtime that
+/// happens inside `block_in_place`, so the calling worker hands its other tasks off instead of
+/// stalling them for the whole pass.
+pub(crate) fn score_in_parallel<F>(len: usize, score: F) -> Vec<f64>
+where
+    F: Fn(usize) -> f64 + Send + Sync + 'static,
+{
+    if len < PARALLEL_SCORING_THRESHOLD {
+        return (0..len).map(score).collect();
+    }
+    let pool = scoring_pool();
+    if pool.helpers == 0 {
+        return (0..len).map(score).collect();
+    }
+
+    let chunk_size = len.div_ceil(pool.helpers + 1);
+    let score = Arc::new(score);
+    run_blocking(|| {
+        let (results, received) = mpsc::channel::<(usize, Vec<f64>)>();
+        let mut pending = 0;
+        for start in (chunk_size..len).step_by(chunk_size) {
+            let end = (start + chunk_size).min(len);
+            let (score, results) = (Arc::clone(&score), results.clone());
+            let job: ScoringJob = Box::new(move || {
+                let _ = results.send((start, (start..end).map(|index| score(index)).collect()));
+            });
+            if pool.jobs.send(job).is_err() {
+                panic!("vector scoring pool is unavailable");
+            }
+            pending += 1;
+        }
+        drop(results);
+
+        let mut chunks = vec![(0, (0..chunk_size).map(|index| score(index)).collect())];
+        for _ in 0..pending {
+            chunks.push(received.recv().expect("scoring job panicked"));
+        }
+        chunks.sort_unstable_by_key(|(start, _)| *start);
+        chunks.into_iter().flat_map(|(_, scores)| scores).collect()
+    })
+}
+
+/// Runs blocking `work`, moving it off the async scheduler when inside a multi-threaded
+/// tokio runtime. Elsewhere (no runtime, or a current-thread one) it simply runs inline.
+fn run_blocking<R>(work: impl FnOnce() -> R) -> R {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(work)
+        }
+        _ => work(),
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cosine_similarity, cosine_similarity_with_norms, score_in_parallel,
+        PARALLEL_SCORING_THRESHOLD,
+    };
 
-    dot / (query_norm * item_norm)
+    #[test]
+    fn chunked_cosine_matches_scalar_reference() {
+        let query = (0..13)
+            .map(|value| value as f32 * 0.5 - 2.0)
+            .collect::<Vec<f32>>();
+        let item = (0..13)
+            .map(|value| (value as f32).sin())
+            .collect::<Vec<f32>>();
+        let item_norm = item
+            .iter()
+            .map(|value| (*value as f64).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        let query_norm = query
+            .iter()
+            .map(|value| (*value as f64).powi(2))
+            .sum::<f64>()
+            .sqrt();
+        let dot = query
+            .iter()
+            .zip(&item)
+            .map(|(q, i)| *q as f64 * *i as f64)
+            .sum::<f64>();
+
+        let similarity = cosine_similarity(&query, &item, item_norm);
+        assert!((similarity - dot / (query_norm * item_norm)).abs() < 1e-12);
+        assert_eq!(
+            cosine_similarity_with_norms(&query, query_norm, &item, item_norm),
+            similarity
+        );
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0], 1.0), 0.0);
+    }
+
+    #[test]
+    fn parallel_scoring_preserves_input_order() {
+        let len = PARALLEL_SCORING_THRESHOLD * 2 + 3;
+        let scores = score_in_parallel(len, |index| index as f64);
+        assert_eq!(scores.len(), len);
+        assert!(scores
+            .iter()
+            .enumerate()
+            .all(|(index, score)| *score == index as f64));
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .build()
+            .expect("runtime");
+        let on_worker = runtime
+            .block_on(
+                runtime.spawn(async move { score_in_parallel(len, |index| index as f64).len() }),
+            )
+            .expect("scoring task");
+        assert_eq!(on_worker, scores.len());
+    }
 }
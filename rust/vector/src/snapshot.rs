@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use mesosphere_errors::AppError;
+
+/// One item line in a JSONL collection snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VectorSnapshotRecord {
+    /// Item id.
+    pub id: String,
+    /// Namespace, omitted for the default namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Optional parent document id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    /// Embedding values.
+    pub embedding: Vec<f32>,
+    /// Optional document.
+    #[serde(default)]
+    pub document: Option<String>,
+    /// Optional metadata.
+    #[serde(default)]
+    pub metadata: Option<Value>,
+    /// Optional source URI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// Optional data columns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+/// Serializes snapshot records as newline-delimited JSON.
+pub fn encode_jsonl(records: &[VectorSnapshotRecord]) -> Result<String, AppError> {
+    let mut output = String::new();
+    for record in records {
+        let line = serde_json::to_string(record).map_err(|error| {
+            AppError::internal(format!("failed to encode snapshot record: {}", error))
+        })?;
+        output.push_str(&line);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Parses newline-delimited JSON snapshot records; blank lines are ignored.
+pub fn parse_jsonl(text: &str) -> Result<Vec<VectorSnapshotRecord>, AppError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| parse_jsonl_line(index + 1, line))
+        .collect()
+}
+
+/// Parses one snapshot line; `line_number` is 1-based and only used in the error.
+pub fn parse_jsonl_line(line_number: usize, line: &str) -> Result<VectorSnapshotRecord, AppError> {
+    serde_json::from_str::<VectorSnapshotRecord>(line).map_err(|error| {
+        AppError::validation(format!(
+            "invalid snapshot record on line {}: {}",
+            line_number, error
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_jsonl, parse_jsonl, VectorSnapshotRecord};
+    use serde_json::json;
+
+    #[test]
+    fn jsonl_round_trips_records() {
+        let records = vec![
+            VectorSnapshotRecord {
+                id: "a".to_string(),
+                namespace: Some("tenant-a".to_string()),
+                parent_id: Some("doc".to_string()),
+                embedding: vec![0.5, -1.0],
+                document: Some("first".to_string()),
+                metadata: Some(json!({"source": "x"})),
+                uri: Some("s3://bucket/doc.pdf".to_string()),
+                data: Some(json!({"chunk_index": 0})),
+            },
+            VectorSnapshotRecord {
+                id: "b".to_string(),
+                namespace: None,
+                parent_id: None,
+                embedding: vec![1.0],
+                document: None,
+                metadata: None,
+                uri: None,
+                data: None,
+            },
+        ];
+
+        let encoded = encode_jsonl(&records).expect("encode");
+        assert_eq!(encoded.lines().count(), 2);
+        assert_eq!(
+            parse_jsonl(&format!("{}\n\n", encoded)).expect("parse"),
+            records
+        );
+    }
+
+    #[test]
+    fn parse_reports_offending_line() {
+        let error = parse_jsonl("{\"id\":\"a\",\"embedding\":[1]}\nnot json")
+            .expect_err("second line is invalid");
+        assert!(error.to_string().contains("line 2"));
+    }
+}
@@ -0,0 +1,306 @@
+use serde_json::{json, Map, Value};
+
+use crate::repository::NewVectorItem;
+
+/// Words used for synthetic documents and text columns.
+const VOCABULARY: [&str; 24] = [
+    "alpha", "beta", "gamma", "delta", "orbit", "comet", "nebula", "quasar", "signal", "vector",
+    "index", "cluster", "stream", "shard", "ledger", "query", "filter", "upload", "archive",
+    "report", "sensor", "metric", "region", "tenant",
+];
+
+/// Seeded SplitMix64 generator; the same seed yields the same values on every platform.
+#[derive(Debug, Clone)]
+pub struct DataRng(u64);
+
+impl DataRng {
+    /// Creates a generator from `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Returns the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = self.0;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+
+    /// Returns a value uniformly distributed in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    /// Returns a value uniformly distributed in `[-1, 1)`.
+    pub fn next_signed_f32(&mut self) -> f32 {
+        (self.next_f64() * 2.0 - 1.0) as f32
+    }
+
+    /// Returns a value in `0..bound` (`0` when `bound` is zero).
+    pub fn below(&mut self, bound: u64) -> u64 {
+        if bound == 0 {
+            return 0;
+        }
+        self.next_u64() % bound
+    }
+
+    /// Returns a random version 4 UUID built from the generator.
+    pub fn uuid(&mut self) -> String {
+        let mut bytes = [0_u8; 16];
+        bytes[..8].copy_from_slice(&self.next_u64().to_le_bytes());
+        bytes[8..].copy_from_slice(&self.next_u64().to_le_bytes());
+        uuid::Builder::from_random_bytes(bytes)
+            .into_uuid()
+            .to_string()
+    }
+
+    /// Returns `count` space-separated vocabulary words.
+    pub fn sentence(&mut self, count: usize) -> String {
+        (0..count)
+            .map(|_| VOCABULARY[self.below(VOCABULARY.len() as u64) as usize])
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+}
+
+/// Shape of a synthetic vector collection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorDatasetSpec {
+    /// Generator seed.
+    pub seed: u64,
+    /// Number of items.
+    pub items: usize,
+    /// Embedding dimension.
+    pub dimension: usize,
+    /// Number of embedding clusters; `0` spreads embeddings uniformly.
+    pub clusters: usize,
+    /// Distinct values of the `category` metadata key.
+    pub category_cardinality: u64,
+    /// Number of namespaces items are spread over; `0` keeps the default namespace.
+    pub namespaces: usize,
+    /// Words per document; `0` omits documents.
+    pub document_words: usize,
+}
+
+impl Default for VectorDatasetSpec {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            items: 1_000,
+            dimension: 384,
+            clusters: 8,
+            category_cardinality: 16,
+            namespaces: 0,
+            document_words: 12,
+        }
+    }
+}
+
+/// Generates the items described by `spec`.
+///
+/// Items carry a seeded UUID, an embedding (near a cluster centroid when clusters are
+/// requested), `category`/`rank`/`score`/`featured` metadata and an optional document.
+pub fn vector_items(spec: &VectorDatasetSpec) -> Vec<NewVectorItem> {
+    let mut rng = DataRng::new(spec.seed);
+    let centroids = (0..spec.clusters)
+        .map(|_| random_embedding(&mut rng, spec.dimension))
+        .collect::<Vec<Vec<f32>>>();
+    (0..spec.items)
+        .map(|rank| {
+            let embedding = if centroids.is_empty() {
+                random_embedding(&mut rng, spec.dimension)
+            } else {
+                let centroid = &centroids[rng.below(centroids.len() as u64) as usize];
+                centroid
+                    .iter()
+                    .map(|value| value + 0.1 * rng.next_signed_f32())
+                    .collect()
+            };
+            let namespace = (spec.namespaces > 0)
+                .then(|| format!("namespace-{}", rng.below(spec.namespaces as u64)));
+            NewVectorItem {
+                id: Some(rng.uuid()),
+                namespace,
+                parent_id: None,
+                embedding,
+                document: (spec.document_words > 0).then(|| rng.sentence(spec.document_words)),
+                metadata: Some(json!({
+                    "category": format!("category-{}", rng.below(spec.category_cardinality)),
+                    "rank": rank,
+                    "score": (rng.next_f64() * 1000.0).round() / 1000.0,
+                    "featured": rng.below(10) == 0,
+                })),
+                ttl_seconds: None,
+                uri: None,
+                data: None,
+            }
+        })
+        .collect()
+}
+
+fn random_embedding(rng: &mut DataRng, dimension: usize) -> Vec<f32> {
+    (0..dimension).map(|_| rng.next_signed_f32()).collect()
+}
+
+/// Value generator for one synthetic table column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnKind {
+    /// Seeded UUID per row.
+    Id,
+    /// Integer in `min..=max`.
+    Integer {
+        /// Smallest value.
+        min: i64,
+        /// Largest value.
+        max: i64,
+    },
+    /// Float in `[0, 1)` rounded to three decimals.
+    Float,
+    /// One of `cardinality` labels (`<column>-<n>`).
+    Category {
+        /// Distinct labels.
+        cardinality: u64,
+    },
+    /// Sentence of `words` vocabulary words.
+    Text {
+        /// Words per value.
+        words: usize,
+    },
+    /// `true` or `false`.
+    Boolean,
+    /// Unix milliseconds within the year after `start_ms`.
+    Timestamp {
+        /// Earliest timestamp.
+        start_ms: i64,
+    },
+}
+
+/// Named column of a synthetic table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableColumnSpec {
+    /// Column name.
+    pub name: String,
+    /// Value generator.
+    pub kind: ColumnKind,
+    /// Fraction of rows left `null`, in `[0, 1]`.
+    pub null_ratio: f64,
+}
+
+impl TableColumnSpec {
+    /// Column that is never `null`.
+    pub fn new(name: impl Into<String>, kind: ColumnKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            null_ratio: 0.0,
+        }
+    }
+}
+
+/// Shape of a synthetic table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDatasetSpec {
+    /// Generator seed.
+    pub seed: u64,
+    /// Number of rows.
+    pub rows: usize,
+    /// Columns in output order.
+    pub columns: Vec<TableColumnSpec>,
+}
+
+/// Generates the rows described by `spec` as JSON objects keyed by column name.
+pub fn table_rows(spec: &TableDatasetSpec) -> Vec<Map<String, Value>> {
+    let mut rng = DataRng::new(spec.seed);
+    (0..spec.rows)
+        .map(|_| {
+            spec.columns
+                .iter()
+                .map(|column| {
+                    let value = if column.null_ratio > 0.0 && rng.next_f64() < column.null_ratio {
+                        Value::Null
+                    } else {
+                        column_value(&mut rng, column)
+                    };
+                    (column.name.clone(), value)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn column_value(rng: &mut DataRng, column: &TableColumnSpec) -> Value {
+    match &column.kind {
+        ColumnKind::Id => Value::String(rng.uuid()),
+        ColumnKind::Integer { min, max } => {
+            let span = max.saturating_sub(*min).max(0) as u64 + 1;
+            json!(min + rng.below(span) as i64)
+        }
+        ColumnKind::Float => json!((rng.next_f64() * 1000.0).round() / 1000.0),
+        ColumnKind::Category { cardinality } => {
+            json!(format!("{}-{}", column.name, rng.below(*cardinality)))
+        }
+        ColumnKind::Text { words } => json!(rng.sentence(*words)),
+        ColumnKind::Boolean => json!(rng.below(2) == 1),
+        ColumnKind::Timestamp { start_ms } => {
+            json!(start_ms + rng.below(365 * 24 * 60 * 60 * 1000) as i64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{table_rows, vector_items, ColumnKind, TableColumnSpec, TableDatasetSpec};
+    use super::{DataRng, VectorDatasetSpec};
+
+    #[test]
+    fn datasets_are_reproducible_and_respect_their_spec() {
+        let spec = VectorDatasetSpec {
+            items: 50,
+            dimension: 8,
+            category_cardinality: 3,
+            namespaces: 2,
+            ..VectorDatasetSpec::default()
+        };
+        let first = vector_items(&spec);
+        let second = vector_items(&spec);
+        assert_eq!(first.len(), 50);
+        assert_eq!(
+            first.iter().map(|item| &item.id).collect::<Vec<_>>(),
+            second.iter().map(|item| &item.id).collect::<Vec<_>>()
+        );
+        assert_eq!(first[7].embedding, second[7].embedding);
+        assert!(first.iter().all(|item| item.embedding.len() == 8));
+        let categories = first
+            .iter()
+            .map(|item| item.metadata.as_ref().unwrap()["category"].clone())
+            .collect::<HashSet<_>>();
+        assert!(categories.len() <= 3);
+        let reseeded = vector_items(&VectorDatasetSpec { seed: 7, ..spec });
+        assert_ne!(first[0].id, reseeded[0].id);
+
+        let table = TableDatasetSpec {
+            seed: 1,
+            rows: 20,
+            columns: vec![
+                TableColumnSpec::new("id", ColumnKind::Id),
+                TableColumnSpec::new("age", ColumnKind::Integer { min: 18, max: 20 }),
+                TableColumnSpec {
+                    null_ratio: 1.0,
+                    ..TableColumnSpec::new("note", ColumnKind::Text { words: 3 })
+                },
+            ],
+        };
+        let rows = table_rows(&table);
+        assert_eq!(rows, table_rows(&table));
+        assert!(rows.iter().all(|row| {
+            let age = row["age"].as_i64().unwrap();
+            (18..=20).contains(&age) && row["note"].is_null() && row["id"].as_str().is_some()
+        }));
+
+        assert_eq!(DataRng::new(3).next_u64(), DataRng::new(3).next_u64());
+    }
+}
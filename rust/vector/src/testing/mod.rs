@@ -0,0 +1,2 @@
+/// Reproducible synthetic vector collections and table rows.
+pub mod datagen;
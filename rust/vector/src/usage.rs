@@ -0,0 +1,281 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlPool, Row};
+
+use mesosphere_application::config::AppConfig;
+use mesosphere_errors::AppError;
+
+use crate::repository::{NewVectorItem, VectorRemoval};
+
+/// Vector usage accounted to one API key.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorUsage {
+    /// Identifier of the API key.
+    pub key_id: String,
+    /// Items written minus items deleted or purged.
+    pub vector_items: u64,
+    /// Estimated bytes of embeddings, documents and metadata stored by those items.
+    pub storage_bytes: u64,
+    /// Queries served since accounting started.
+    pub query_count: u64,
+    /// Queries served during the current UTC day.
+    pub queries_today: u64,
+}
+
+/// Per-key limits; `0` disables a limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorQuotas {
+    /// Live items allowed.
+    pub max_vector_items: u64,
+    /// Estimated storage bytes allowed.
+    pub max_storage_bytes: u64,
+    /// Queries allowed per UTC day.
+    pub max_queries_per_day: u64,
+}
+
+impl VectorQuotas {
+    /// Reads quota limits from the application config.
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            max_vector_items: config.quota_max_vector_items,
+            max_storage_bytes: config.quota_max_storage_bytes,
+            max_queries_per_day: config.quota_max_queries_per_day,
+        }
+    }
+
+    /// Returns whether an item or storage limit is set.
+    pub fn limits_storage(&self) -> bool {
+        self.max_vector_items > 0 || self.max_storage_bytes > 0
+    }
+
+    /// Rejects a write of `items` items and `bytes` bytes that would exceed a storage quota.
+    pub fn check_write(&self, usage: &VectorUsage, items: u64, bytes: u64) -> Result<(), AppError> {
+        if self.max_vector_items > 0 && usage.vector_items + items > self.max_vector_items {
+            return Err(AppError::forbidden(format!(
+                "vector item quota of {} would be exceeded ({} stored, {} requested)",
+                self.max_vector_items, usage.vector_items, items
+            )));
+        }
+        if self.max_storage_bytes > 0 && usage.storage_bytes + bytes > self.max_storage_bytes {
+            return Err(AppError::forbidden(format!(
+                "vector storage quota of {} bytes would be exceeded ({} stored, {} requested)",
+                self.max_storage_bytes, usage.storage_bytes, bytes
+            )));
+        }
+        Ok(())
+    }
+
+    /// Rejects a query once the daily query quota is spent.
+    pub fn check_query(&self, queries_today: u64) -> Result<(), AppError> {
+        if self.max_queries_per_day > 0 && queries_today > self.max_queries_per_day {
+            return Err(AppError::too_many_requests(format!(
+                "daily query quota of {} is exhausted",
+                self.max_queries_per_day
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Estimates the stored size of an item: its `f32` embedding plus document and JSON payloads.
+pub fn estimate_item_bytes(item: &NewVectorItem) -> u64 {
+    let json_len = |value: &Option<serde_json::Value>| {
+        value
+            .as_ref()
+            .map(|value| value.to_string().len())
+            .unwrap_or(0)
+    };
+    (item.embedding.len() * std::mem::size_of::<f32>()
+        + item.document.as_ref().map(String::len).unwrap_or(0)
+        + json_len(&item.metadata)
+        + json_len(&item.data)
+        + item.uri.as_ref().map(String::len).unwrap_or(0)) as u64
+}
+
+/// Reads and updates per-key usage counters in `_mesosphere_usage`.
+#[derive(Clone)]
+pub struct UsageLedger {
+    pool: MySqlPool,
+    quotas: VectorQuotas,
+}
+
+impl UsageLedger {
+    /// Creates a ledger enforcing `quotas`.
+    pub fn new(pool: MySqlPool, quotas: VectorQuotas) -> Self {
+        Self { pool, quotas }
+    }
+
+    /// Returns the usage of `key_id`, zeroed when nothing was recorded yet.
+    pub async fn usage(&self, key_id: &str) -> Result<VectorUsage, AppError> {
+        let row = sqlx::query(
+            r#"
+            SELECT vector_items, storage_bytes, query_count,
+                   IF(query_day = UTC_DATE(), queries_today, 0) AS queries_today
+            FROM _mesosphere_usage
+            WHERE key_id = ?
+            "#,
+        )
+        .bind(key_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some(row) = row else {
+            return Ok(VectorUsage {
+                key_id: key_id.to_string(),
+                ..VectorUsage::default()
+            });
+        };
+        let counter = |column: &str| -> Result<u64, AppError> {
+            Ok(row.try_get::<i64, _>(column)?.max(0) as u64)
+        };
+        Ok(VectorUsage {
+            key_id: key_id.to_string(),
+            vector_items: counter("vector_items")?,
+            storage_bytes: counter("storage_bytes")?,
+            query_count: counter("query_count")?,
+            queries_today: counter("queries_today")?,
+        })
+    }
+
+    /// Returns whether writes are checked against a storage quota.
+    pub fn limits_storage(&self) -> bool {
+        self.quotas.limits_storage()
+    }
+
+    /// Fails with 403 when storing `new_items` would push `key_id` past a storage quota.
+    pub async fn check_write(
+        &self,
+        key_id: &str,
+        new_items: &[&NewVectorItem],
+    ) -> Result<(), AppError> {
+        if !self.limits_storage() {
+            return Ok(());
+        }
+        let usage = self.usage(key_id).await?;
+        let bytes = new_items.iter().copied().map(estimate_item_bytes).sum();
+        self.quotas
+            .check_write(&usage, new_items.len() as u64, bytes)
+    }
+
+    /// Adds newly stored items to `key_id` and applies the change in estimated bytes, which is
+    /// negative when a write replaced items with smaller ones.
+    pub async fn record_write(&self, key_id: &str, items: u64, bytes: i64) -> Result<(), AppError> {
+        if items == 0 && bytes == 0 {
+            return Ok(());
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO _mesosphere_usage (key_id, vector_items, storage_bytes)
+            VALUES (?, ?, GREATEST(?, 0))
+            ON DUPLICATE KEY UPDATE
+                vector_items = vector_items + VALUES(vector_items),
+                storage_bytes = GREATEST(storage_bytes + ?, 0)
+            "#,
+        )
+        .bind(key_id)
+        .bind(items as i64)
+        .bind(bytes)
+        .bind(bytes)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes deleted or purged items and their estimated bytes from `key_id`.
+    pub async fn record_delete(
+        &self,
+        key_id: &str,
+        removed: VectorRemoval,
+    ) -> Result<(), AppError> {
+        if removed.items == 0 && removed.bytes == 0 {
+            return Ok(());
+        }
+        sqlx::query(
+            r#"
+            UPDATE _mesosphere_usage
+            SET vector_items = GREATEST(vector_items - ?, 0),
+                storage_bytes = GREATEST(storage_bytes - ?, 0)
+            WHERE key_id = ?
+            "#,
+        )
+        .bind(removed.items as i64)
+        .bind(removed.bytes as i64)
+        .bind(key_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Fails with 429 when `key_id` has no queries left today, without counting one.
+    pub async fn check_query(&self, key_id: &str) -> Result<(), AppError> {
+        if self.quotas.max_queries_per_day == 0 {
+            return Ok(());
+        }
+        let usage = self.usage(key_id).await?;
+        self.quotas.check_query(usage.queries_today + 1)
+    }
+
+    /// Counts a query against `key_id` and fails with 429 once the daily quota is spent.
+    pub async fn record_query(&self, key_id: &str) -> Result<(), AppError> {
+        let mut transaction = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO _mesosphere_usage (key_id, query_count, query_day, queries_today)
+            VALUES (?, 1, UTC_DATE(), 1)
+            ON DUPLICATE KEY UPDATE
+                query_count = query_count + 1,
+                queries_today = IF(query_day = UTC_DATE(), queries_today + 1, 1),
+                query_day = UTC_DATE()
+            "#,
+        )
+        .bind(key_id)
+        .execute(&mut *transaction)
+        .await?;
+        let queries_today = sqlx::query_scalar::<_, i64>(
+            "SELECT queries_today FROM _mesosphere_usage WHERE key_id = ?",
+        )
+        .bind(key_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+        transaction.commit().await?;
+        self.quotas.check_query(queries_today.max(0) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VectorQuotas, VectorUsage};
+    use mesosphere_errors::AppError;
+
+    #[test]
+    fn quotas_reject_writes_and_queries_past_their_limits() {
+        let quotas = VectorQuotas {
+            max_vector_items: 10,
+            max_storage_bytes: 1_000,
+            max_queries_per_day: 2,
+        };
+        let usage = VectorUsage {
+            vector_items: 8,
+            storage_bytes: 900,
+            ..VectorUsage::default()
+        };
+        assert!(quotas.check_write(&usage, 2, 100).is_ok());
+        assert!(matches!(
+            quotas.check_write(&usage, 3, 0),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(quotas.check_write(&usage, 1, 101).is_err());
+        assert!(quotas.check_query(2).is_ok());
+        assert!(matches!(
+            quotas.check_query(3),
+            Err(AppError::TooManyRequests(_))
+        ));
+        assert!(VectorQuotas::default()
+            .check_write(&usage, u32::MAX as u64, u32::MAX as u64)
+            .is_ok());
+        assert!(quotas.limits_storage());
+        assert!(!VectorQuotas {
+            max_queries_per_day: 5,
+            ..VectorQuotas::default()
+        }
+        .limits_storage());
+    }
+}